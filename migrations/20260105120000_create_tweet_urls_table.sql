@@ -0,0 +1,11 @@
+CREATE TABLE tweet_urls (
+    id SERIAL PRIMARY KEY,
+    tweet_id INTEGER NOT NULL REFERENCES tweets (id) ON DELETE CASCADE,
+    start_offset INTEGER NOT NULL,
+    end_offset INTEGER NOT NULL,
+    url TEXT NOT NULL,
+    expanded_url TEXT NOT NULL,
+    display_url TEXT NOT NULL
+);
+
+CREATE INDEX tweet_urls_tweet_id_idx ON tweet_urls (tweet_id);
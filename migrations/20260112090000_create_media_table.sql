@@ -0,0 +1,16 @@
+CREATE TABLE media (
+    id SERIAL PRIMARY KEY,
+    owner_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    tweet_id INTEGER REFERENCES tweets (id) ON DELETE SET NULL,
+    blob_key TEXT NOT NULL,
+    url TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    alt_text TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX media_tweet_id_idx ON media (tweet_id);
+CREATE INDEX media_blob_key_idx ON media (blob_key);
@@ -0,0 +1,13 @@
+CREATE TABLE media_variants (
+    id SERIAL PRIMARY KEY,
+    media_id INTEGER NOT NULL REFERENCES media (id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    blob_key TEXT NOT NULL,
+    url TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    UNIQUE (media_id, name)
+);
+
+CREATE INDEX media_variants_blob_key_idx ON media_variants (blob_key);
@@ -0,0 +1,21 @@
+CREATE TABLE polls (
+    id SERIAL PRIMARY KEY,
+    tweet_id INTEGER NOT NULL UNIQUE REFERENCES tweets (id) ON DELETE CASCADE,
+    closes_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE poll_options (
+    poll_id INTEGER NOT NULL REFERENCES polls (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    label TEXT NOT NULL,
+    PRIMARY KEY (poll_id, position)
+);
+
+CREATE TABLE poll_votes (
+    poll_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (poll_id, user_id),
+    FOREIGN KEY (poll_id, position) REFERENCES poll_options (poll_id, position) ON DELETE CASCADE
+);
@@ -0,0 +1,22 @@
+-- Tweets written before authorship existed keep a NULL author
+ALTER TABLE tweets ADD COLUMN author_id INTEGER REFERENCES users (id) ON DELETE CASCADE;
+
+CREATE INDEX tweets_author_id_created_at_idx ON tweets (author_id, created_at DESC, id DESC);
+
+-- A draft with scheduled_at set is a scheduled tweet
+CREATE TABLE drafts (
+    id SERIAL PRIMARY KEY,
+    author_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    content TEXT NOT NULL,
+    media_ids INTEGER[] NOT NULL DEFAULT '{}',
+    poll JSONB,
+    scheduled_at TIMESTAMPTZ,
+    last_error TEXT,
+    -- Lease held by the instance currently publishing this draft
+    claimed_until TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX drafts_author_id_idx ON drafts (author_id, updated_at DESC);
+CREATE INDEX drafts_scheduled_at_idx ON drafts (scheduled_at) WHERE scheduled_at IS NOT NULL;
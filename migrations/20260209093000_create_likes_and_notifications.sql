@@ -0,0 +1,33 @@
+ALTER TABLE tweets ADD COLUMN reply_to_id INTEGER REFERENCES tweets (id) ON DELETE SET NULL;
+
+CREATE INDEX tweets_reply_to_id_idx ON tweets (reply_to_id);
+
+CREATE TABLE likes (
+    user_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    tweet_id INTEGER NOT NULL REFERENCES tweets (id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (user_id, tweet_id)
+);
+
+CREATE INDEX likes_tweet_id_idx ON likes (tweet_id);
+
+CREATE TABLE notifications (
+    id BIGSERIAL PRIMARY KEY,
+    recipient_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    actor_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    tweet_id INTEGER REFERENCES tweets (id) ON DELETE CASCADE,
+    -- Notifications sharing a key are shown as one entry ("A and 3 others ...")
+    group_key TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX notifications_recipient_group_idx ON notifications (recipient_id, group_key, id DESC);
+
+-- Everything up to and including the watermark counts as read
+CREATE TABLE notification_reads (
+    user_id INTEGER PRIMARY KEY REFERENCES users (id) ON DELETE CASCADE,
+    watermark BIGINT NOT NULL
+);
+
+ALTER TABLE drafts ADD COLUMN reply_to_id INTEGER REFERENCES tweets (id) ON DELETE SET NULL;
@@ -0,0 +1,29 @@
+-- Side effects of a write, recorded in the write's own transaction and
+-- delivered afterwards by the relay worker
+CREATE TABLE outbox (
+    id BIGSERIAL PRIMARY KEY,
+    event JSONB NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    claimed_until TIMESTAMPTZ,
+    last_error TEXT,
+    processed_at TIMESTAMPTZ,
+    -- Set instead of processed_at once retries are exhausted
+    abandoned_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX outbox_pending_idx ON outbox (next_attempt_at)
+    WHERE processed_at IS NULL AND abandoned_at IS NULL;
+
+-- Relayed events are delivered at least once, so a retry must not notify twice
+DELETE FROM notifications a
+USING notifications b
+WHERE a.id > b.id
+  AND a.recipient_id = b.recipient_id
+  AND a.actor_id = b.actor_id
+  AND a.kind = b.kind
+  AND a.tweet_id IS NOT DISTINCT FROM b.tweet_id;
+
+CREATE UNIQUE INDEX notifications_event_idx
+    ON notifications (recipient_id, actor_id, kind, tweet_id) NULLS NOT DISTINCT;
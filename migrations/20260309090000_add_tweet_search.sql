@@ -0,0 +1,6 @@
+-- Kept in sync by Postgres, so every write path is indexed without extra code
+ALTER TABLE tweets
+    ADD COLUMN search_vector TSVECTOR
+    GENERATED ALWAYS AS (to_tsvector('english', content)) STORED;
+
+CREATE INDEX tweets_search_vector_idx ON tweets USING GIN (search_vector);
@@ -0,0 +1,15 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- Editable later with the rest of the profile; searchable from now on
+ALTER TABLE users ADD COLUMN display_name TEXT;
+
+-- Prefix matches for typeahead
+CREATE INDEX users_username_prefix_idx ON users (lower(username) text_pattern_ops);
+CREATE INDEX users_display_name_prefix_idx ON users (lower(display_name) text_pattern_ops);
+
+-- Fuzzy matches for search
+CREATE INDEX users_username_trgm_idx ON users USING GIN (lower(username) gin_trgm_ops);
+CREATE INDEX users_display_name_trgm_idx ON users USING GIN (lower(display_name) gin_trgm_ops);
+
+-- Follower counts used for ranking
+CREATE INDEX follows_following_id_idx ON follows (following_id);
@@ -0,0 +1,15 @@
+-- One row per distinct #tag in a tweet, lowercased, with the tweet's author
+-- and time copied in so trend windows never touch the tweets table
+CREATE TABLE tweet_hashtags (
+    tweet_id INTEGER NOT NULL REFERENCES tweets (id) ON DELETE CASCADE,
+    tag TEXT NOT NULL,
+    author_id INTEGER REFERENCES users (id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (tweet_id, tag)
+);
+
+CREATE INDEX tweet_hashtags_created_at_idx ON tweet_hashtags (created_at);
+
+INSERT INTO tweet_hashtags (tweet_id, tag, author_id, created_at)
+SELECT DISTINCT t.id, lower(m[1]), t.author_id, COALESCE(t.created_at, now())
+FROM tweets t, regexp_matches(t.content, '(?:^|[^[:alnum:]_])#([[:alnum:]_]+)', 'g') AS m;
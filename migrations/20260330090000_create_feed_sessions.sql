@@ -0,0 +1,13 @@
+-- A ranked feed frozen at its first page, so later pages do not reshuffle
+CREATE TABLE feed_sessions (
+    id TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    tweet_ids INTEGER[] NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX feed_sessions_expires_at_idx ON feed_sessions (expires_at);
+
+-- Candidate generation looks up likes by followed users
+CREATE INDEX likes_user_id_created_at_idx ON likes (user_id, created_at DESC);
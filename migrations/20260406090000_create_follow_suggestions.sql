@@ -0,0 +1,11 @@
+-- Friends-of-friends precomputed for accounts that follow too many people
+-- to work them out on every request
+CREATE TABLE follow_suggestions (
+    user_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    suggested_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    mutual_count BIGINT NOT NULL,
+    -- A few of the followed accounts that follow the suggestion, for the reason text
+    mutual_ids INTEGER[] NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (user_id, suggested_id)
+);
@@ -0,0 +1,73 @@
+-- The first tweet of the thread a tweet belongs to; NULL on the first tweet
+-- itself. Stored, so a thread stays whole when a tweet in the middle is deleted.
+ALTER TABLE tweets ADD COLUMN conversation_id INTEGER;
+
+WITH RECURSIVE threads AS (
+    SELECT id, id AS root
+    FROM tweets
+    WHERE reply_to_id IS NULL
+    UNION ALL
+    SELECT t.id, threads.root
+    FROM tweets t
+    JOIN threads ON t.reply_to_id = threads.id
+)
+UPDATE tweets
+SET conversation_id = threads.root
+FROM threads
+WHERE tweets.id = threads.id AND threads.root <> tweets.id;
+
+CREATE INDEX tweets_conversation_id_idx ON tweets (conversation_id);
+
+-- Mutes are private to the muter; nothing tells the muted party
+CREATE TABLE user_mutes (
+    user_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    muted_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (user_id, muted_id),
+    CHECK (user_id <> muted_id)
+);
+
+CREATE TABLE keyword_mutes (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    -- Lowercased, with whitespace collapsed
+    phrase TEXT NOT NULL,
+    expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (user_id, phrase)
+);
+
+CREATE TABLE conversation_mutes (
+    user_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    conversation_id INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (user_id, conversation_id)
+);
+
+-- Whether `viewer` muted a tweet: its author, its thread, or a phrase in it
+-- as a whole word. Keywords never hide the viewer's own tweets. Any NULL
+-- argument matches nothing, so a missing viewer or tweet mutes nothing.
+CREATE FUNCTION is_muted(viewer INTEGER, author INTEGER, conversation INTEGER, content TEXT)
+RETURNS BOOLEAN
+LANGUAGE sql STABLE
+AS $$
+    SELECT EXISTS (
+        SELECT 1 FROM user_mutes m
+        WHERE m.user_id = viewer AND m.muted_id = author
+    )
+    OR EXISTS (
+        SELECT 1 FROM conversation_mutes m
+        WHERE m.user_id = viewer AND m.conversation_id = conversation
+    )
+    OR EXISTS (
+        SELECT 1 FROM keyword_mutes m
+        WHERE m.user_id = viewer
+          AND author IS DISTINCT FROM viewer
+          AND (m.expires_at IS NULL OR m.expires_at > now())
+          AND content ~* (
+              '(^|[^[:alnum:]_])'
+              || regexp_replace(m.phrase, '([^[:alnum:][:space:]_])', '\\\1', 'g')
+              || '($|[^[:alnum:]_])'
+          )
+    )
+$$;
@@ -0,0 +1,30 @@
+-- Tweets of protected accounts are only shown to their approved followers
+ALTER TABLE users ADD COLUMN protected BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Follows of protected accounts wait here until approved
+CREATE TABLE follow_requests (
+    follower_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    following_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (follower_id, following_id)
+);
+
+CREATE INDEX follow_requests_following_id_idx ON follow_requests (following_id, created_at DESC);
+
+-- Whether `author` is protected and `viewer` is neither them nor an approved
+-- follower. A NULL viewer is anonymous and sees no protected tweets.
+CREATE FUNCTION is_protected_from(viewer INTEGER, author INTEGER)
+RETURNS BOOLEAN
+LANGUAGE sql STABLE
+AS $$
+    SELECT EXISTS (
+        SELECT 1 FROM users u
+        WHERE u.id = author
+          AND u.protected
+          AND author IS DISTINCT FROM viewer
+          AND NOT EXISTS (
+              SELECT 1 FROM follows f
+              WHERE f.follower_id = viewer AND f.following_id = author
+          )
+    )
+$$;
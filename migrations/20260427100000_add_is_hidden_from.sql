@@ -0,0 +1,15 @@
+-- Whether a tweet stays out of `viewer`'s timelines and feeds: its author
+-- and the viewer blocked each other either way, the viewer muted it, or the
+-- author is protected and the viewer is not an approved follower
+CREATE FUNCTION is_hidden_from(viewer INTEGER, author INTEGER, conversation INTEGER, content TEXT)
+RETURNS BOOLEAN
+LANGUAGE sql STABLE
+AS $$
+    SELECT EXISTS (
+        SELECT 1 FROM blocks b
+        WHERE (b.blocker_id = author AND b.blocked_id = viewer)
+           OR (b.blocker_id = viewer AND b.blocked_id = author)
+    )
+    OR is_muted(viewer, author, conversation, content)
+    OR is_protected_from(viewer, author)
+$$;
@@ -0,0 +1,35 @@
+-- Only accounts the user follows may start or send them direct messages
+ALTER TABLE users ADD COLUMN dms_from_following_only BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Direct message threads; unrelated to tweets' conversation_id
+CREATE TABLE dm_conversations (
+    id SERIAL PRIMARY KEY,
+    created_by INTEGER REFERENCES users (id) ON DELETE SET NULL,
+    -- "low:high" user ids of a one-to-one conversation, so each pair has one;
+    -- NULL for groups
+    pair_key TEXT UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_message_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE dm_participants (
+    conversation_id INTEGER NOT NULL REFERENCES dm_conversations (id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    -- Read receipt: the newest message the participant has seen
+    last_read_message_id INTEGER,
+    joined_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (conversation_id, user_id)
+);
+
+CREATE INDEX dm_participants_user_id_idx ON dm_participants (user_id);
+
+CREATE TABLE direct_messages (
+    id SERIAL PRIMARY KEY,
+    conversation_id INTEGER NOT NULL REFERENCES dm_conversations (id) ON DELETE CASCADE,
+    sender_id INTEGER REFERENCES users (id) ON DELETE SET NULL,
+    content TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX direct_messages_conversation_idx
+    ON direct_messages (conversation_id, created_at DESC, id DESC);
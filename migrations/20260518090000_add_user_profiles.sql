@@ -0,0 +1,9 @@
+-- Public profile fields; display_name already exists for user search
+ALTER TABLE users
+    ADD COLUMN bio TEXT,
+    ADD COLUMN location TEXT,
+    ADD COLUMN website TEXT,
+    ADD COLUMN avatar_media_id INTEGER REFERENCES media (id) ON DELETE SET NULL,
+    ADD COLUMN banner_media_id INTEGER REFERENCES media (id) ON DELETE SET NULL,
+    -- Accounts that predate this column get the time of the migration
+    ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT now();
@@ -0,0 +1,10 @@
+-- Events relayed between instances; NOTIFY only carries the row id, so an
+-- event of any size reaches every instance
+CREATE TABLE relayed_events (
+    id BIGSERIAL PRIMARY KEY,
+    origin TEXT NOT NULL,
+    event JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX relayed_events_created_at_idx ON relayed_events (created_at);
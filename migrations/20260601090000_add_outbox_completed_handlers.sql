@@ -0,0 +1,3 @@
+-- Handlers that already dealt with an event, so a retry after another
+-- handler failed does not repeat their side effects
+ALTER TABLE outbox ADD COLUMN completed_handlers TEXT[] NOT NULL DEFAULT '{}';
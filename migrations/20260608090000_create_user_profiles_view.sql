@@ -0,0 +1,17 @@
+-- Users as the API shows them, with avatar and banner media resolved to URLs.
+-- Views hide NOT NULL from query checkers, so queries mark id, username and
+-- created_at as non-null themselves.
+CREATE VIEW user_profiles AS
+SELECT
+    u.id,
+    u.username,
+    u.display_name,
+    u.bio,
+    u.location,
+    u.website,
+    avatar.url AS avatar_url,
+    banner.url AS banner_url,
+    u.created_at
+FROM users u
+LEFT JOIN media avatar ON avatar.id = u.avatar_media_id
+LEFT JOIN media banner ON banner.id = u.banner_media_id;
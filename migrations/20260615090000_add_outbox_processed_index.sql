@@ -0,0 +1,3 @@
+-- Lets the retention sweep find old processed events without a full scan
+CREATE INDEX outbox_processed_idx ON outbox (processed_at)
+    WHERE processed_at IS NOT NULL;
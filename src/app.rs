@@ -2,47 +2,364 @@ use crate::repositories::user_repository::UserRepository;
 use crate::services::user_service::UserService;
 use axum::{
     Router,
+    extract::DefaultBodyLimit,
     routing::{get, post},
 };
+use std::sync::Arc;
 
-use crate::routes::users::create_user;
+use crate::events::EventBus;
+
+use crate::routes::users::{
+    create_user, get_me, get_settings, get_user, get_user_by_username, search_users, typeahead,
+    update_profile, update_settings,
+};
 use sqlx::PgPool;
 
 use crate::repositories::tweet_repository::TweetRepository;
-use crate::routes::tweets::{create_tweet, get_tweet, timeline, timeline_cursor};
+use crate::routes::tweets::{
+    create_tweet, delete_tweet, get_tweet, like_tweet, timeline, timeline_cursor, unlike_tweet,
+    validate_tweet, vote_in_poll,
+};
 use crate::services::tweet_service::TweetService;
 //====================
 use crate::repositories::follow_repository::FollowRepository;
-use crate::routes::follow::{follow, unfollow};
+use crate::routes::follow::{
+    approve_follow_request, deny_follow_request, follow, list_follow_requests, unfollow,
+};
 use crate::services::follow_service::FollowService;
+//====================
+use crate::repositories::media_repository::MediaRepository;
+use crate::routes::media::{get_media_file, upload_media};
+use crate::services::image_processing::ThumbnailSize;
+use crate::services::media_service::{MAX_MEDIA_BYTES, MediaService};
+use crate::storage::BlobStore;
+//====================
+use crate::repositories::draft_repository::DraftRepository;
+use crate::routes::drafts::{create_draft, delete_draft, get_draft, list_drafts, update_draft};
+use crate::services::draft_service::DraftService;
+//====================
+use crate::repositories::notification_repository::NotificationRepository;
+use crate::routes::notifications::{list_notifications, mark_read, unread_count};
+use crate::services::notification_service::NotificationService;
+//====================
+use crate::repositories::outbox_repository::OutboxRepository;
+use crate::services::outbox_service::OutboxService;
+//====================
+use crate::repositories::job_repository::JobRepository;
+use crate::services::job_queue::{JobHandler, JobQueue};
+//====================
+use crate::repositories::webhook_repository::WebhookRepository;
+use crate::routes::webhooks::{
+    create_webhook, delete_webhook, get_webhook, list_deliveries, list_webhooks, replay_delivery,
+    update_webhook,
+};
+use crate::services::webhook_service::WebhookService;
+//====================
+use crate::routes::search::search_tweets;
+use crate::search::SearchIndex;
+use crate::services::search_service::SearchService;
+//====================
+use crate::repositories::trend_repository::TrendRepository;
+use crate::routes::trends::trends;
+use crate::services::trend_service::TrendService;
+//====================
+use crate::repositories::feed_repository::FeedRepository;
+use crate::routes::feed::for_you_timeline;
+use crate::services::feed_service::FeedService;
+use crate::services::ranking::WeightedRanker;
+//====================
+use crate::repositories::suggestion_repository::SuggestionRepository;
+use crate::routes::suggestions::follow_suggestions;
+use crate::services::suggestion_service::SuggestionService;
+//====================
+use crate::repositories::block_repository::BlockRepository;
+use crate::routes::blocks::{block_user, unblock_user};
+use crate::services::block_service::BlockService;
+//====================
+use crate::repositories::mute_repository::MuteRepository;
+use crate::routes::mutes::{
+    list_muted_keywords, list_muted_users, mute_conversation, mute_keyword, mute_user,
+    unmute_conversation, unmute_keyword, unmute_user,
+};
+use crate::services::mute_service::MuteService;
+//====================
+use crate::repositories::list_repository::ListRepository;
+use crate::routes::lists::{
+    add_list_member, create_list, delete_list, get_list, list_members, list_timeline, my_lists,
+    remove_list_member, subscribe_list, unsubscribe_list, update_list, user_lists,
+};
+use crate::services::list_service::ListService;
+//====================
+use crate::repositories::direct_message_repository::DirectMessageRepository;
+use crate::routes::direct_messages::{
+    create_conversation, get_conversation, list_conversations, list_messages,
+    mark_conversation_read, send_message,
+};
+use crate::services::direct_message_service::DirectMessageService;
+//====================
+use crate::routes::stream::stream_timeline;
+use crate::routes::ws::ws_gateway;
 
 #[derive(Clone)]
 pub struct AppState {
     pub tweet_service: TweetService,
     pub user_service: UserService,
     pub follow_service: FollowService,
+    pub media_service: MediaService,
+    pub draft_service: DraftService,
+    pub notification_service: NotificationService,
+    pub events: Arc<dyn EventBus>,
+    pub outbox_service: OutboxService,
+    pub job_queue: JobQueue,
+    pub webhook_service: WebhookService,
+    pub search_service: SearchService,
+    pub trend_service: TrendService,
+    pub feed_service: FeedService,
+    pub suggestion_service: SuggestionService,
+    pub block_service: BlockService,
+    pub mute_service: MuteService,
+    pub list_service: ListService,
+    pub direct_message_service: DirectMessageService,
 }
 
-pub fn create_app(pool: PgPool) -> Router {
+/// Wire repositories into services; background workers share this state
+pub fn build_state(
+    pool: PgPool,
+    blob_store: Arc<dyn BlobStore>,
+    thumbnail_sizes: Vec<ThumbnailSize>,
+    events: Arc<dyn EventBus>,
+    search_index: Arc<dyn SearchIndex>,
+    webhook_allowed_hosts: Vec<String>,
+) -> AppState {
     let user_repository = UserRepository::new(pool.clone());
-    let user_service = UserService::new(user_repository);
+    let block_repository = BlockRepository::new(pool.clone());
+    let mute_repository = MuteRepository::new(pool.clone());
     let tweet_repository = TweetRepository::new(pool.clone());
-    let tweet_service = TweetService::new(tweet_repository);
+    let notification_repository = NotificationRepository::new(pool.clone());
+    let notification_service = NotificationService::new(
+        notification_repository,
+        user_repository.clone(),
+        block_repository.clone(),
+        mute_repository.clone(),
+        tweet_repository.clone(),
+        events.clone(),
+    );
+    let media_repository = MediaRepository::new(pool.clone());
+    let user_service = UserService::new(
+        user_repository.clone(),
+        media_repository.clone(),
+        events.clone(),
+    );
+    let job_queue = JobQueue::new(JobRepository::new(pool.clone()));
+    let media_service = MediaService::new(
+        media_repository.clone(),
+        blob_store,
+        thumbnail_sizes,
+        job_queue.clone(),
+    );
+    let webhook_service = WebhookService::new(
+        WebhookRepository::new(pool.clone()),
+        tweet_repository.clone(),
+        user_repository.clone(),
+        block_repository.clone(),
+        job_queue.clone(),
+        webhook_allowed_hosts,
+    );
+    let search_service = SearchService::new(
+        search_index,
+        tweet_repository.clone(),
+        user_repository.clone(),
+        block_repository.clone(),
+    );
+    let suggestion_service = SuggestionService::new(
+        SuggestionRepository::new(pool.clone()),
+        user_repository.clone(),
+    );
+    let feed_service = FeedService::new(
+        FeedRepository::new(pool.clone()),
+        tweet_repository.clone(),
+        Arc::new(WeightedRanker::default()),
+    );
+    let mute_service = MuteService::new(
+        mute_repository,
+        user_repository.clone(),
+        tweet_repository.clone(),
+    );
+    let list_service = ListService::new(
+        ListRepository::new(pool.clone()),
+        tweet_repository.clone(),
+        user_repository.clone(),
+        block_repository.clone(),
+    );
+    let tweet_service = TweetService::new(
+        tweet_repository,
+        media_repository,
+        block_repository.clone(),
+        notification_service.clone(),
+        events.clone(),
+    );
     let follow_repository = FollowRepository::new(pool.clone());
-    let follow_service = FollowService::new(follow_repository);
+    let direct_message_service = DirectMessageService::new(
+        DirectMessageRepository::new(pool.clone()),
+        user_repository.clone(),
+        follow_repository.clone(),
+        block_repository.clone(),
+        events.clone(),
+    );
+    let follow_service = FollowService::new(
+        follow_repository,
+        user_repository.clone(),
+        block_repository.clone(),
+        notification_service.clone(),
+        events.clone(),
+    );
+    let block_service = BlockService::new(
+        block_repository,
+        user_repository,
+        notification_service.clone(),
+        events.clone(),
+    );
+    let draft_repository = DraftRepository::new(pool.clone());
+    let draft_service = DraftService::new(draft_repository, tweet_service.clone());
+    let outbox_service = OutboxService::new(
+        OutboxRepository::new(pool.clone()),
+        vec![
+            Arc::new(tweet_service.clone()),
+            Arc::new(follow_service.clone()),
+            Arc::new(webhook_service.clone()),
+            Arc::new(search_service.clone()),
+        ],
+    );
 
-    let state = AppState {
+    let trend_service = TrendService::new(TrendRepository::new(pool.clone()));
+
+    AppState {
         tweet_service,
         user_service,
         follow_service,
-    };
+        media_service,
+        draft_service,
+        notification_service,
+        events,
+        outbox_service,
+        job_queue,
+        webhook_service,
+        search_service,
+        trend_service,
+        feed_service,
+        suggestion_service,
+        block_service,
+        mute_service,
+        list_service,
+        direct_message_service,
+    }
+}
+
+/// Everything the background job runner knows how to execute
+pub fn job_handlers(state: &AppState) -> Vec<Arc<dyn JobHandler>> {
+    vec![
+        Arc::new(state.media_service.clone()),
+        Arc::new(state.webhook_service.clone()),
+    ]
+}
+
+pub fn create_app(state: AppState) -> Router {
     Router::new()
         .route("/tweets", post(create_tweet))
+        .route("/tweets/validate", post(validate_tweet))
         .route("/timeline", get(timeline))
-        .route("/tweets/:id", get(get_tweet))
+        .route("/tweets/:id", get(get_tweet).delete(delete_tweet))
+        .route("/tweets/:id/poll/vote", post(vote_in_poll))
+        .route("/tweets/:id/like", post(like_tweet).delete(unlike_tweet))
         .route("/timeline/cursor", get(timeline_cursor))
+        .route("/timeline/for_you", get(for_you_timeline))
+        .route("/stream/timeline", get(stream_timeline))
+        .route("/ws", get(ws_gateway))
         .route("/users", post(create_user))
+        .route("/users/search", get(search_users))
+        .route("/users/typeahead", get(typeahead))
+        .route("/users/me", get(get_me).patch(update_profile))
+        .route("/users/by-username/:username", get(get_user_by_username))
+        .route("/users/:id", get(get_user))
+        .route(
+            "/users/me/settings",
+            get(get_settings).patch(update_settings),
+        )
+        .route("/users/:id/suggestions", get(follow_suggestions))
         .route("/follow", post(follow))
         .route("/follow", axum::routing::delete(unfollow))
+        .route("/follow_requests", get(list_follow_requests))
+        .route(
+            "/follow_requests/:follower_id/approve",
+            post(approve_follow_request),
+        )
+        .route(
+            "/follow_requests/:follower_id/deny",
+            post(deny_follow_request),
+        )
+        .route("/blocks/:user_id", post(block_user).delete(unblock_user))
+        .route("/mutes/users", get(list_muted_users))
+        .route("/mutes/users/:user_id", post(mute_user).delete(unmute_user))
+        .route(
+            "/mutes/keywords",
+            post(mute_keyword).get(list_muted_keywords),
+        )
+        .route("/mutes/keywords/:id", axum::routing::delete(unmute_keyword))
+        .route(
+            "/mutes/conversations/:tweet_id",
+            post(mute_conversation).delete(unmute_conversation),
+        )
+        .route("/lists", post(create_list).get(my_lists))
+        .route(
+            "/lists/:id",
+            get(get_list).put(update_list).delete(delete_list),
+        )
+        .route("/lists/:id/members", get(list_members))
+        .route(
+            "/lists/:id/members/:user_id",
+            post(add_list_member).delete(remove_list_member),
+        )
+        .route(
+            "/lists/:id/subscribe",
+            post(subscribe_list).delete(unsubscribe_list),
+        )
+        .route("/lists/:id/tweets", get(list_timeline))
+        .route("/users/:id/lists", get(user_lists))
+        .route(
+            "/conversations",
+            post(create_conversation).get(list_conversations),
+        )
+        .route("/conversations/:id", get(get_conversation))
+        .route(
+            "/conversations/:id/messages",
+            post(send_message).get(list_messages),
+        )
+        .route("/conversations/:id/read", post(mark_conversation_read))
+        .route(
+            "/media",
+            // Leave headroom for the multipart framing around the file
+            post(upload_media).layer(DefaultBodyLimit::max(MAX_MEDIA_BYTES + 64 * 1024)),
+        )
+        .route("/media/files/:key", get(get_media_file))
+        .route("/notifications", get(list_notifications))
+        .route("/notifications/unread_count", get(unread_count))
+        .route("/notifications/read", post(mark_read))
+        .route("/drafts", post(create_draft).get(list_drafts))
+        .route(
+            "/drafts/:id",
+            get(get_draft).put(update_draft).delete(delete_draft),
+        )
+        .route("/search/tweets", get(search_tweets))
+        .route("/trends", get(trends))
+        .route("/webhooks", post(create_webhook).get(list_webhooks))
+        .route(
+            "/webhooks/:id",
+            get(get_webhook).put(update_webhook).delete(delete_webhook),
+        )
+        .route("/webhooks/:id/deliveries", get(list_deliveries))
+        .route(
+            "/webhooks/:id/deliveries/:delivery_id/replay",
+            post(replay_delivery),
+        )
         .with_state(state)
 }
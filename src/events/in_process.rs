@@ -0,0 +1,29 @@
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+
+use crate::events::{DomainEvent, EventBus};
+
+/// Single-node bus: events never leave this process
+pub struct InProcessEventBus {
+    sender: broadcast::Sender<DomainEvent>,
+}
+
+impl InProcessEventBus {
+    /// `capacity` is how far a subscriber may fall behind before it lags
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+}
+
+#[async_trait]
+impl EventBus for InProcessEventBus {
+    async fn publish(&self, event: DomainEvent) {
+        // An error only means nobody is listening right now
+        let _ = self.sender.send(event);
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<DomainEvent> {
+        self.sender.subscribe()
+    }
+}
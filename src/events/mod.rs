@@ -0,0 +1,87 @@
+pub mod in_process;
+pub mod postgres;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::models::direct_message::DirectMessage;
+use crate::models::notification::NotificationKind;
+use crate::models::tweet::TweetResponse;
+
+/// A tweet as pushed to live timeline subscribers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEvent {
+    pub tweet: TweetResponse,
+    pub created_at: DateTime<Utc>,
+    /// Posted by a protected account; only their followers may see it
+    #[serde(default)]
+    pub protected: bool,
+}
+
+impl TimelineEvent {
+    /// The same `created_at|id` cursor the paginated timeline uses
+    pub fn cursor(&self) -> String {
+        format!("{}|{}", self.created_at.to_rfc3339(), self.tweet.id)
+    }
+}
+
+/// A notification that was just created for `recipient_id`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationEvent {
+    pub recipient_id: i32,
+    pub kind: NotificationKind,
+    pub actor_id: i32,
+    pub tweet_id: Option<i32>,
+}
+
+/// Current engagement totals of one tweet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountersEvent {
+    pub tweet_id: u64,
+    pub likes: i64,
+    pub replies: i64,
+}
+
+/// A direct message, for every participant of its conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageEvent {
+    pub participant_ids: Vec<i32>,
+    pub message: DirectMessage,
+}
+
+/// A participant read a conversation up to `message_id`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadReceiptEvent {
+    pub participant_ids: Vec<i32>,
+    pub conversation_id: i32,
+    pub user_id: i32,
+    pub message_id: i32,
+}
+
+/// Something that happened which other parts of the system, possibly on
+/// other instances, may react to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum DomainEvent {
+    TweetCreated(TimelineEvent),
+    UserCreated { user_id: i32, username: String },
+    Followed { follower_id: i32, following_id: i32 },
+    Unfollowed { follower_id: i32, following_id: i32 },
+    Notification(NotificationEvent),
+    Counters(CountersEvent),
+    DirectMessage(MessageEvent),
+    MessagesRead(ReadReceiptEvent),
+}
+
+/// Fans domain events out to every subscriber. Delivery is best effort:
+/// publishing never fails the action that caused the event.
+#[async_trait]
+pub trait EventBus: Send + Sync {
+    async fn publish(&self, event: DomainEvent);
+
+    /// Events published from now on; a receiver that falls too far behind
+    /// gets `Lagged` and should resynchronise
+    fn subscribe(&self) -> broadcast::Receiver<DomainEvent>;
+}
@@ -0,0 +1,188 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use sqlx::postgres::PgListener;
+use tokio::sync::broadcast;
+
+use crate::events::{DomainEvent, EventBus};
+
+/// Postgres channel every instance listens on
+const CHANNEL: &str = "twitter_lite_events";
+
+/// Pause before listening again after the connection dropped
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Relayed events are only read right after their notification; anything
+/// older is deleted
+const RETENTION_SECONDS: f64 = 300.0;
+
+/// How often old relayed events are deleted
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// What NOTIFY carries: the stored event's id, never the event itself, which
+/// may be larger than a notification can hold
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    origin: String,
+    id: i64,
+}
+
+/// Multi-node bus relaying events between instances with `LISTEN/NOTIFY`.
+/// Local subscribers get events straight away; other instances are notified
+/// with the id of a stored copy, which they fetch unless it is their own.
+pub struct PostgresEventBus {
+    pool: PgPool,
+    sender: broadcast::Sender<DomainEvent>,
+    instance_id: String,
+}
+
+impl PostgresEventBus {
+    /// Start listening on the shared channel; must run inside the runtime
+    pub fn new(pool: PgPool, capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or_default();
+        let instance_id = format!("{}-{}", std::process::id(), nanos);
+
+        tokio::spawn(listen(pool.clone(), sender.clone(), instance_id.clone()));
+        tokio::spawn(prune(pool.clone()));
+
+        Self {
+            pool,
+            sender,
+            instance_id,
+        }
+    }
+}
+
+#[async_trait]
+impl EventBus for PostgresEventBus {
+    async fn publish(&self, event: DomainEvent) {
+        let payload = match serde_json::to_value(&event) {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::warn!(error = ?e, "event could not be serialized");
+                return;
+            }
+        };
+
+        let _ = self.sender.send(event);
+
+        // Store the event and notify with its id in one statement
+        if let Err(e) = sqlx::query!(
+            r#"
+            WITH stored AS (
+                INSERT INTO relayed_events (origin, event)
+                VALUES ($2, $3)
+                RETURNING id, origin
+            )
+            SELECT pg_notify($1, json_build_object('origin', origin, 'id', id)::TEXT)
+            FROM stored
+            "#,
+            CHANNEL,
+            self.instance_id,
+            payload
+        )
+        .execute(&self.pool)
+        .await
+        {
+            tracing::warn!(error = ?e, "relaying event failed");
+        }
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<DomainEvent> {
+        self.sender.subscribe()
+    }
+}
+
+/// Forward other instances' events to local subscribers for as long as the
+/// process runs. Events sent while reconnecting are lost.
+async fn listen(pool: PgPool, sender: broadcast::Sender<DomainEvent>, instance_id: String) {
+    loop {
+        let mut listener = match PgListener::connect_with(&pool).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::warn!(error = ?e, "event listener could not connect");
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+
+        if let Err(e) = listener.listen(CHANNEL).await {
+            tracing::warn!(error = ?e, "event listener could not subscribe");
+            tokio::time::sleep(RECONNECT_DELAY).await;
+            continue;
+        }
+
+        loop {
+            let notification = match listener.recv().await {
+                Ok(notification) => notification,
+                Err(e) => {
+                    tracing::warn!(error = ?e, "event listener lost its connection");
+                    break;
+                }
+            };
+
+            match serde_json::from_str::<Envelope>(notification.payload()) {
+                Ok(envelope) if envelope.origin == instance_id => {}
+                Ok(envelope) => {
+                    if let Some(event) = fetch(&pool, envelope.id).await {
+                        let _ = sender.send(event);
+                    }
+                }
+                Err(e) => tracing::warn!(error = ?e, "ignoring malformed notification"),
+            }
+        }
+
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// The relayed event with this id; `None` if it is gone or unreadable
+async fn fetch(pool: &PgPool, id: i64) -> Option<DomainEvent> {
+    let event = match sqlx::query_scalar!("SELECT event FROM relayed_events WHERE id = $1", id)
+        .fetch_optional(pool)
+        .await
+    {
+        Ok(Some(event)) => event,
+        Ok(None) => {
+            tracing::warn!(id, "relayed event expired before it was read");
+            return None;
+        }
+        Err(e) => {
+            tracing::warn!(error = ?e, id, "relayed event could not be read");
+            return None;
+        }
+    };
+
+    serde_json::from_value(event)
+        .inspect_err(|e| tracing::warn!(error = ?e, id, "ignoring malformed event"))
+        .ok()
+}
+
+/// Delete relayed events every instance has had the chance to read
+async fn prune(pool: PgPool) {
+    let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = sqlx::query!(
+            r#"
+            DELETE FROM relayed_events
+            WHERE created_at < now() - make_interval(secs => $1)
+            "#,
+            RETENTION_SECONDS
+        )
+        .execute(&pool)
+        .await
+        {
+            tracing::warn!(error = ?e, "pruning relayed events failed");
+        }
+    }
+}
@@ -1,29 +1,121 @@
 mod app;
 mod db;
+mod events;
 mod models;
 mod repositories;
 mod routes;
+mod search;
 mod services;
+mod storage;
+mod workers;
 
 use dotenvy::dotenv;
+use events::EventBus;
+use events::in_process::InProcessEventBus;
+use events::postgres::PostgresEventBus;
+use repositories::search_repository::SearchRepository;
+use search::embedded::EmbeddedSearchIndex;
+use search::postgres::PostgresSearchIndex;
+use search::{SearchIndex, SearchIndexError};
+use services::image_processing::parse_thumbnail_sizes;
 use std::env;
+use std::sync::Arc;
+use storage::local::LocalBlobStore;
+
+/// Live subscribers further behind than this many events are disconnected
+const EVENT_BUS_CAPACITY: usize = 1024;
 
 #[tokio::main]
 async fn main() {
     dotenv().ok();
+    tracing_subscriber::fmt::init();
 
     let database_url = env::var("DATABASE_URL").expect("DATABASE_URL not set");
 
     let pool = db::pool::create_pool(&database_url).await;
 
-    let app = app::create_app(pool);
+    let media_dir = env::var("MEDIA_DIR").unwrap_or_else(|_| "media".into());
+    let media_base_url = env::var("MEDIA_BASE_URL").unwrap_or_else(|_| "/media/files".into());
+    let blob_store = Arc::new(LocalBlobStore::new(media_dir, media_base_url));
+
+    let thumbnail_sizes = parse_thumbnail_sizes(
+        &env::var("THUMBNAIL_SIZES").unwrap_or_else(|_| "thumb:150,small:680,medium:1200".into()),
+    );
+
+    // Several instances behind a load balancer need the Postgres relay
+    let multi_instance = env::var("EVENT_BUS").as_deref() == Ok("postgres");
+    let events: Arc<dyn EventBus> = if multi_instance {
+        Arc::new(PostgresEventBus::new(pool.clone(), EVENT_BUS_CAPACITY))
+    } else {
+        Arc::new(InProcessEventBus::new(EVENT_BUS_CAPACITY))
+    };
+
+    // The embedded index lives on this instance's disk and is fed from the
+    // outbox, whose events each go to whichever instance claims them first
+    let search_index: Arc<dyn SearchIndex> = match env::var("SEARCH_INDEX").as_deref() {
+        Ok("embedded") => {
+            if multi_instance {
+                exit_with(
+                    "SEARCH_INDEX=embedded only supports a single instance; use the Postgres index with EVENT_BUS=postgres",
+                );
+            }
+
+            let dir = env::var("SEARCH_INDEX_DIR").unwrap_or_else(|_| "search-index".into());
+            match EmbeddedSearchIndex::open(dir) {
+                Ok(index) => Arc::new(index),
+                Err(SearchIndexError::Locked) => exit_with(
+                    "search index is in use by another process; a running server must be stopped before reindexing",
+                ),
+                Err(e) => exit_with(&format!("search index could not be opened: {e}")),
+            }
+        }
+        _ => Arc::new(PostgresSearchIndex::new(SearchRepository::new(
+            pool.clone(),
+        ))),
+    };
+
+    // Hosts webhooks may reach even on a private address, e.g. `localhost`
+    // for local testing
+    let webhook_allowed_hosts = env::var("WEBHOOK_ALLOWED_HOSTS")
+        .map(|hosts| hosts.split(',').map(str::to_string).collect())
+        .unwrap_or_default();
+
+    let state = app::build_state(
+        pool,
+        blob_store,
+        thumbnail_sizes,
+        events,
+        search_index,
+        webhook_allowed_hosts,
+    );
+
+    // `twitter-lite reindex` rebuilds the search index from the tweets table and exits
+    if env::args().nth(1).as_deref() == Some("reindex") {
+        match state.search_service.rebuild_index().await {
+            Ok(indexed) => println!("reindexed {indexed} tweets"),
+            Err(e) => exit_with(&format!("search index rebuild failed: {e:?}")),
+        }
+        return;
+    }
+
+    tokio::spawn(workers::scheduler::run(state.draft_service.clone()));
+    tokio::spawn(workers::outbox_relay::run(state.outbox_service.clone()));
+    tokio::spawn(workers::outbox_relay::prune(state.outbox_service.clone()));
+    tokio::spawn(workers::trends::run(state.trend_service.clone()));
+    tokio::spawn(workers::suggestions::run(state.suggestion_service.clone()));
+    workers::job_runner::spawn(state.job_queue.clone(), app::job_handlers(&state));
+
+    let app = app::create_app(state);
+
+    let bind_addr = env::var("BIND_ADDR").unwrap_or_else(|_| "127.0.0.1:3000".into());
+
+    axum::serve(tokio::net::TcpListener::bind(bind_addr).await.unwrap(), app)
+        .await
+        .unwrap();
+}
 
-    axum::serve(
-        tokio::net::TcpListener::bind("127.0.0.1:3000")
-            .await
-            .unwrap(),
-        app,
-    )
-    .await
-    .unwrap();
+/// Report a configuration or startup problem and stop without a panic
+fn exit_with(message: &str) -> ! {
+    eprintln!("error: {message}");
+    std::process::exit(1)
 }
@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectMessage {
+    pub id: i32,
+    pub conversation_id: i32,
+    pub sender_id: Option<i32>,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Participant {
+    pub id: i32,
+    pub username: String,
+    /// How far the participant has read
+    pub last_read_message_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Conversation {
+    pub id: i32,
+    /// More than one other participant, now or when it was started
+    pub group: bool,
+    pub participants: Vec<Participant>,
+    pub last_message: Option<DirectMessage>,
+    /// Messages from others newer than the caller's read receipt
+    pub unread_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub last_message_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateConversationRequest {
+    /// Everyone but the caller
+    pub participant_ids: Vec<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SendMessageRequest {
+    pub content: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct MarkReadRequest {
+    /// Newest message the client has shown; without it, everything is read
+    pub up_to: Option<i32>,
+}
+
+/// Raw conversation row before participants are attached
+#[derive(Debug)]
+pub struct ConversationRow {
+    pub id: i32,
+    pub group: bool,
+    pub unread_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub last_message_at: DateTime<Utc>,
+    pub last_message_id: Option<i32>,
+    pub last_message_sender_id: Option<i32>,
+    pub last_message_content: Option<String>,
+    pub last_message_created_at: Option<DateTime<Utc>>,
+}
+
+/// Raw participant row, tagged with its conversation
+#[derive(Debug)]
+pub struct ParticipantRow {
+    pub conversation_id: i32,
+    pub id: i32,
+    pub username: String,
+    pub last_read_message_id: Option<i32>,
+}
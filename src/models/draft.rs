@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::models::poll::CreatePollRequest;
+use crate::models::tweet::{ClaimedDraft, CreateTweetRequest};
+
+/// An unpublished tweet; with `scheduled_at` set it is a scheduled tweet
+#[derive(Debug, Serialize)]
+pub struct Draft {
+    pub id: i32,
+    pub author_id: i32,
+    pub content: String,
+    pub media_ids: Vec<i32>,
+    pub poll: Option<CreatePollRequest>,
+    pub reply_to_id: Option<i32>,
+    pub scheduled_at: Option<DateTime<Utc>>,
+    /// Why the last publish attempt failed, if it did
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Draft {
+    /// The request publishing this draft amounts to
+    pub fn to_request(&self) -> CreateTweetRequest {
+        CreateTweetRequest {
+            content: self.content.clone(),
+            media_ids: self.media_ids.clone(),
+            poll: self.poll.clone(),
+            in_reply_to: self.reply_to_id.map(|id| id as u64),
+            scheduled_at: None,
+        }
+    }
+
+    /// The version being published, so a later edit is not lost
+    pub fn claim(&self) -> ClaimedDraft {
+        ClaimedDraft {
+            id: self.id,
+            updated_at: self.updated_at,
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+
+/// A tweet that may go into someone's ranked feed, with what a ranker
+/// needs to score it
+#[derive(Debug, Clone)]
+pub struct FeedCandidate {
+    pub tweet_id: i32,
+    pub created_at: DateTime<Utc>,
+    /// Written by the viewer or by someone they follow
+    pub from_network: bool,
+    /// How many of the accounts the viewer follows liked it
+    pub liked_by_followed: i64,
+    pub likes: i64,
+    pub replies: i64,
+}
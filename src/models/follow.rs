@@ -0,0 +1,19 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// What a follow attempt led to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FollowStatus {
+    Following,
+    /// The account is protected and has to approve the request first
+    Pending,
+}
+
+/// Someone waiting for a protected account to approve their follow
+#[derive(Debug, Serialize)]
+pub struct PendingFollower {
+    pub id: i32,
+    pub username: String,
+    pub requested_at: DateTime<Utc>,
+}
@@ -0,0 +1,11 @@
+use serde_json::Value;
+
+/// A job leased to a worker
+#[derive(Debug)]
+pub struct Job {
+    pub id: i64,
+    pub job_type: String,
+    pub payload: Value,
+    pub attempts: i32,
+    pub max_attempts: i32,
+}
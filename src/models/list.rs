@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+pub struct List {
+    pub id: i32,
+    pub owner_id: i32,
+    pub name: String,
+    pub description: String,
+    /// Only the owner can see the list, its members and its tweets
+    pub private: bool,
+    pub member_count: i64,
+    pub subscriber_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Creates a list, or replaces every field of an existing one
+#[derive(Debug, Deserialize)]
+pub struct ListRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub private: bool,
+}
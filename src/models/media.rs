@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Media {
+    pub id: i32,
+    pub url: String,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub alt_text: Option<String>,
+    /// Downscaled renditions; empty until background processing finishes
+    pub variants: Vec<MediaVariant>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaVariant {
+    pub name: String,
+    pub url: String,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// Everything known about an upload once it has been stored
+#[derive(Debug)]
+pub struct NewMedia {
+    pub owner_id: i32,
+    pub blob_key: String,
+    pub url: String,
+    pub content_type: String,
+    pub size_bytes: i32,
+    pub width: i32,
+    pub height: i32,
+    pub alt_text: Option<String>,
+}
+
+/// A generated rendition once it has been stored
+#[derive(Debug)]
+pub struct NewMediaVariant {
+    pub media_id: i32,
+    pub name: String,
+    pub blob_key: String,
+    pub url: String,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+}
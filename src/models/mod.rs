@@ -1,2 +1,17 @@
+pub mod direct_message;
+pub mod draft;
+pub mod feed;
+pub mod follow;
+pub mod job;
+pub mod list;
+pub mod media;
+pub mod mute;
+pub mod notification;
+pub mod outbox;
+pub mod poll;
+pub mod search;
+pub mod suggestion;
+pub mod trend;
 pub mod tweet;
 pub mod user;
+pub mod webhook;
@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A muted word or phrase
+#[derive(Debug, Serialize)]
+pub struct KeywordMute {
+    pub id: i32,
+    pub phrase: String,
+    /// Muted for good when absent
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateKeywordMuteRequest {
+    pub phrase: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
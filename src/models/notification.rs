@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationKind {
+    Follow,
+    Mention,
+    Reply,
+    Like,
+}
+
+impl NotificationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::Follow => "follow",
+            NotificationKind::Mention => "mention",
+            NotificationKind::Reply => "reply",
+            NotificationKind::Like => "like",
+        }
+    }
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "follow" => Some(NotificationKind::Follow),
+            "mention" => Some(NotificationKind::Mention),
+            "reply" => Some(NotificationKind::Reply),
+            "like" => Some(NotificationKind::Like),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct NotificationActor {
+    pub id: i32,
+    pub username: String,
+}
+
+/// One entry in the notifications list, possibly standing for many events
+#[derive(Debug, Serialize)]
+pub struct NotificationGroup {
+    pub kind: NotificationKind,
+    pub tweet_id: Option<i32>,
+    /// Most recent actors first, at most three
+    pub actors: Vec<NotificationActor>,
+    pub actor_count: i64,
+    /// e.g. "alice and 3 others liked your tweet"
+    pub summary: String,
+    pub latest_id: i64,
+    pub latest_at: DateTime<Utc>,
+    pub unread: bool,
+}
+
+/// Raw grouped row before actor names are resolved
+#[derive(Debug)]
+pub struct NotificationGroupRow {
+    pub kind: String,
+    pub tweet_id: Option<i32>,
+    pub actor_ids: Vec<i32>,
+    pub actor_count: i64,
+    pub latest_id: i64,
+    pub latest_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MarkReadRequest {
+    /// Newest notification id the client has shown
+    pub up_to: i64,
+}
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A committed write whose side effects still have to happen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutboxEvent {
+    TweetCreated {
+        tweet_id: i32,
+        author_id: i32,
+        reply_to_id: Option<i32>,
+        created_at: DateTime<Utc>,
+    },
+    TweetDeleted {
+        tweet_id: i32,
+        author_id: i32,
+    },
+    Followed {
+        follower_id: i32,
+        following_id: i32,
+    },
+}
+
+/// An outbox row leased to the relay
+#[derive(Debug)]
+pub struct OutboxMessage {
+    pub id: i64,
+    pub event: OutboxEvent,
+    pub attempts: i32,
+    /// Names of the handlers that already succeeded on an earlier attempt
+    pub completed_handlers: Vec<String>,
+}
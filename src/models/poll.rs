@@ -0,0 +1,38 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePollRequest {
+    pub options: Vec<String>,
+    pub duration_minutes: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VoteRequest {
+    /// Zero-based index into the poll's options
+    pub option: i32,
+}
+
+/// Tallies are `None` until the viewer has voted or the poll has closed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Poll {
+    pub options: Vec<PollOption>,
+    pub total_votes: Option<i64>,
+    pub closes_at: DateTime<Utc>,
+    pub closed: bool,
+    pub voted_option: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollOption {
+    pub position: i32,
+    pub label: String,
+    pub votes: Option<i64>,
+}
+
+/// A validated poll ready to be stored with its tweet
+#[derive(Debug)]
+pub struct NewPoll {
+    pub options: Vec<String>,
+    pub closes_at: DateTime<Utc>,
+}
@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchSort {
+    /// Best matches first
+    #[default]
+    Relevance,
+    /// Newest first
+    Recent,
+}
+
+/// A parsed `q` parameter
+#[derive(Debug, Clone, Default)]
+pub struct TweetSearchQuery {
+    /// Words, `"quoted phrases"`, `OR` and `-excluded` words, in
+    /// `websearch_to_tsquery` syntax
+    pub text: Option<String>,
+    /// `from:username`
+    pub from: Option<String>,
+    /// The user `from` names, resolved before the query reaches an index
+    pub author_id: Option<i32>,
+    /// `#hashtag`, without the `#`
+    pub hashtags: Vec<String>,
+    /// `since:` is inclusive
+    pub since: Option<DateTime<Utc>>,
+    /// `until:` is exclusive
+    pub until: Option<DateTime<Utc>>,
+    /// Authors the viewer must not see, such as those blocked either way
+    pub excluded_authors: Vec<i32>,
+    /// Who is searching; an index that can't check who may read a protected
+    /// account leaves that to the caller
+    pub viewer_id: Option<i32>,
+}
+
+/// Where the previous page ended, in the order of the requested sort
+#[derive(Debug, Clone, Copy)]
+pub enum SearchCursor {
+    Relevance(f32, i32),
+    Recent(DateTime<Utc>, i32),
+}
+
+/// A matching tweet and the keys it was ordered by
+#[derive(Debug)]
+pub struct TweetHit {
+    pub tweet_id: i32,
+    pub created_at: DateTime<Utc>,
+    pub rank: f32,
+}
+
+/// What a search index stores about a tweet
+#[derive(Debug, Clone)]
+pub struct IndexedTweet {
+    pub id: i32,
+    pub author_id: Option<i32>,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
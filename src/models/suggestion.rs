@@ -0,0 +1,26 @@
+use serde::Serialize;
+
+/// An account followed by people the user follows
+#[derive(Debug)]
+pub struct SuggestedUser {
+    pub id: i32,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub followers_count: i64,
+    /// How many of the user's followees follow this account
+    pub mutual_count: i64,
+    /// Some of those followees, most recent follow first
+    pub mutual_ids: Vec<i32>,
+}
+
+/// A who-to-follow entry as returned to clients
+#[derive(Debug, Serialize)]
+pub struct FollowSuggestion {
+    pub id: i32,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub followers_count: i64,
+    pub mutual_count: i64,
+    /// e.g. "Followed by alice and 2 others"
+    pub reason: String,
+}
@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// A hashtag used unusually often in the current window
+#[derive(Debug, Clone, Serialize)]
+pub struct Trend {
+    pub hashtag: String,
+    /// Distinct accounts that used it in the current window
+    pub authors: i64,
+    pub tweets: i64,
+    /// Average distinct accounts per window over the baseline
+    pub baseline: f64,
+    /// How many standard deviations the current window is above the baseline
+    pub score: f64,
+}
+
+/// The trends as of the last refresh
+#[derive(Debug, Clone, Serialize)]
+pub struct TrendSnapshot {
+    pub trends: Vec<Trend>,
+    pub computed_at: DateTime<Utc>,
+}
@@ -1,12 +1,73 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::models::media::Media;
+use crate::models::poll::{CreatePollRequest, NewPoll, Poll};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateTweetRequest {
     pub content: String,
+    #[serde(default)]
+    pub media_ids: Vec<i32>,
+    pub poll: Option<CreatePollRequest>,
+    pub in_reply_to: Option<u64>,
+    /// Publish later instead of now
+    pub scheduled_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TweetResponse {
     pub id: u64,
+    /// `None` for tweets written before authorship was recorded
+    pub author_id: Option<i32>,
+    pub content: String,
+    pub reply_to_id: Option<u64>,
+    pub urls: Vec<UrlEntity>,
+    pub media: Vec<Media>,
+    pub poll: Option<Poll>,
+}
+
+/// A link found in the content; offsets count Unicode code points
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrlEntity {
+    pub start: i32,
+    pub end: i32,
+    pub url: String,
+    pub expanded_url: String,
+    pub display_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ValidateTweetRequest {
     pub content: String,
 }
+
+#[derive(Debug, Serialize)]
+pub struct ValidateTweetResponse {
+    pub length: usize,
+    pub remaining: i64,
+    pub valid: bool,
+}
+
+/// A validated tweet ready to be stored
+#[derive(Debug)]
+pub struct NewTweet {
+    pub author_id: i32,
+    pub content: String,
+    pub urls: Vec<UrlEntity>,
+    /// Lowercased, without the `#`
+    pub hashtags: Vec<String>,
+    pub media_ids: Vec<i32>,
+    pub poll: Option<NewPoll>,
+    pub reply_to_id: Option<i32>,
+    /// Scheduled draft this tweet publishes; consumed in the same transaction
+    pub draft: Option<ClaimedDraft>,
+}
+
+/// The version of a scheduled draft a publisher claimed
+#[derive(Debug, Clone, Copy)]
+pub struct ClaimedDraft {
+    pub id: i32,
+    /// Publishing fails if the draft was edited after it was claimed
+    pub updated_at: DateTime<Utc>,
+}
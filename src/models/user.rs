@@ -1,12 +1,87 @@
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
 
+/// A user's public profile
 #[derive(Debug, Serialize)]
 pub struct User {
     pub id: i32,
     pub username: String,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub location: Option<String>,
+    pub website: Option<String>,
+    pub avatar_url: Option<String>,
+    pub banner_url: Option<String>,
+    pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct CreateUserRequest {
     pub username: String,
 }
+
+/// Account settings only the user themselves can see
+#[derive(Debug, Serialize)]
+pub struct UserSettings {
+    /// Only approved followers see the user's tweets
+    pub protected: bool,
+    /// Only accounts the user follows may message them
+    pub dms_from_following_only: bool,
+}
+
+/// Fields left out are not changed
+#[derive(Debug, Deserialize)]
+pub struct UpdateSettingsRequest {
+    pub protected: Option<bool>,
+    pub dms_from_following_only: Option<bool>,
+}
+
+/// Fields left out are not changed; `null` clears one
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateProfileRequest {
+    #[serde(default, deserialize_with = "present")]
+    pub display_name: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub bio: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub location: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub website: Option<Option<String>>,
+    /// One of the user's own uploads
+    #[serde(default, deserialize_with = "present")]
+    pub avatar_media_id: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "present")]
+    pub banner_media_id: Option<Option<i32>>,
+}
+
+/// Validated profile changes as stored; an empty string or media id 0
+/// clears a field
+#[derive(Debug, Default)]
+pub struct ProfileChanges {
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub location: Option<String>,
+    pub website: Option<String>,
+    pub avatar_media_id: Option<i32>,
+    pub banner_media_id: Option<i32>,
+}
+
+/// Tells a field sent as `null` apart from one left out
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// A user as listed in search results and suggestions
+#[derive(Debug, Serialize)]
+pub struct UserSummary {
+    pub id: i32,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub followers_count: i64,
+    /// Whether the viewer follows this user
+    pub followed_by_me: bool,
+}
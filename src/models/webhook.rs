@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Activity a webhook can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    /// The owner posted a tweet
+    TweetCreated,
+    /// The owner deleted a tweet
+    TweetDeleted,
+    /// Someone followed the owner
+    Follow,
+    /// Someone mentioned the owner
+    Mention,
+}
+
+impl WebhookEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WebhookEvent::TweetCreated => "tweet.created",
+            WebhookEvent::TweetDeleted => "tweet.deleted",
+            WebhookEvent::Follow => "follow",
+            WebhookEvent::Mention => "mention",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "tweet.created" => Some(WebhookEvent::TweetCreated),
+            "tweet.deleted" => Some(WebhookEvent::TweetDeleted),
+            "follow" => Some(WebhookEvent::Follow),
+            "mention" => Some(WebhookEvent::Mention),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Webhook {
+    pub id: i32,
+    pub url: String,
+    pub events: Vec<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A new webhook; the signing secret is only ever shown here
+#[derive(Debug, Serialize)]
+pub struct CreatedWebhook {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub events: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateWebhookRequest {
+    pub url: String,
+    pub events: Vec<String>,
+    #[serde(default = "default_active")]
+    pub active: bool,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+/// One event sent, or being sent, to one webhook
+#[derive(Debug, Serialize)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i32,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    /// `pending`, `succeeded` or `failed`
+    pub status: String,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// Where and how a pending delivery is to be sent
+#[derive(Debug)]
+pub struct DeliveryTarget {
+    pub delivery_id: i64,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub status: String,
+    pub attempts: i32,
+    pub created_at: DateTime<Utc>,
+    pub url: String,
+    pub secret: String,
+    pub active: bool,
+}
+
+/// Outcome of one delivery attempt
+#[derive(Debug)]
+pub struct DeliveryAttempt {
+    pub status: &'static str,
+    pub response_status: Option<i32>,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+}
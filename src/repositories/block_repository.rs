@@ -0,0 +1,96 @@
+use sqlx::PgPool;
+
+use crate::repositories::{follow_repository, list_repository};
+
+#[derive(Clone)]
+pub struct BlockRepository {
+    pool: PgPool,
+}
+
+impl BlockRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record the block and drop the follows between the two users in the
+    /// same transaction; returns the removed follows as (follower, following),
+    /// or `None` if the block already existed
+    pub async fn block(
+        &self,
+        blocker_id: i32,
+        blocked_id: i32,
+    ) -> Result<Option<Vec<(i32, i32)>>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO blocks (blocker_id, blocked_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            blocker_id,
+            blocked_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if inserted.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        let severed = follow_repository::sever(&mut tx, blocker_id, blocked_id).await?;
+        list_repository::remove_between(&mut tx, blocker_id, blocked_id).await?;
+
+        tx.commit().await?;
+
+        Ok(Some(severed))
+    }
+
+    /// False if there was no such block
+    pub async fn unblock(&self, blocker_id: i32, blocked_id: i32) -> Result<bool, sqlx::Error> {
+        let deleted = sqlx::query!(
+            r#"
+            DELETE FROM blocks
+            WHERE blocker_id = $1 AND blocked_id = $2
+            "#,
+            blocker_id,
+            blocked_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(deleted.rows_affected() > 0)
+    }
+
+    /// Whether either user has blocked the other
+    pub async fn is_blocked_either_way(&self, a: i32, b: i32) -> Result<bool, sqlx::Error> {
+        let record = sqlx::query!(
+            r#"
+            SELECT 1 AS exists
+            FROM blocks
+            WHERE (blocker_id = $1 AND blocked_id = $2)
+               OR (blocker_id = $2 AND blocked_id = $1)
+            "#,
+            a,
+            b
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record.is_some())
+    }
+
+    /// Everyone `user_id` has blocked or been blocked by
+    pub async fn blocked_either_way(&self, user_id: i32) -> Result<Vec<i32>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT blocked_id AS "id!" FROM blocks WHERE blocker_id = $1
+            UNION
+            SELECT blocker_id FROM blocks WHERE blocked_id = $1
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+}
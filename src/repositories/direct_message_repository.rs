@@ -0,0 +1,345 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::models::direct_message::{ConversationRow, DirectMessage, ParticipantRow};
+
+#[derive(Clone)]
+pub struct DirectMessageRepository {
+    pool: PgPool,
+}
+
+impl DirectMessageRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// The one-to-one conversation with this pair key, if started
+    pub async fn find_pair(&self, pair_key: &str) -> Result<Option<i32>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT id
+            FROM dm_conversations
+            WHERE pair_key = $1
+            "#,
+            pair_key
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Start a conversation between `participant_ids`, the creator included;
+    /// `None` if a one-to-one conversation with that pair key already exists
+    pub async fn create_conversation(
+        &self,
+        created_by: i32,
+        pair_key: Option<&str>,
+        participant_ids: &[i32],
+    ) -> Result<Option<i32>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO dm_conversations (created_by, pair_key)
+            VALUES ($1, $2)
+            ON CONFLICT (pair_key) DO NOTHING
+            RETURNING id
+            "#,
+            created_by,
+            pair_key
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(id) = id else {
+            return Ok(None);
+        };
+
+        sqlx::query!(
+            r#"
+            INSERT INTO dm_participants (conversation_id, user_id)
+            SELECT $1, user_id
+            FROM unnest($2::INTEGER[]) AS user_id
+            "#,
+            id,
+            participant_ids
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(id))
+    }
+
+    pub async fn participant_ids(&self, conversation_id: i32) -> Result<Vec<i32>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT user_id
+            FROM dm_participants
+            WHERE conversation_id = $1
+            ORDER BY joined_at, user_id
+            "#,
+            conversation_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// The user's conversations, most recently active first
+    pub async fn conversations(
+        &self,
+        user_id: i32,
+        limit: i64,
+    ) -> Result<Vec<ConversationRow>, sqlx::Error> {
+        sqlx::query_as!(
+            ConversationRow,
+            r#"
+            SELECT
+                c.id,
+                (c.pair_key IS NULL) AS "group!",
+                (
+                    SELECT COUNT(*)
+                    FROM direct_messages m
+                    WHERE m.conversation_id = c.id
+                      AND m.sender_id IS DISTINCT FROM $1
+                      AND m.id > COALESCE(me.last_read_message_id, 0)
+                ) AS "unread_count!",
+                c.created_at,
+                c.last_message_at,
+                last.id AS "last_message_id?",
+                last.sender_id AS "last_message_sender_id?",
+                last.content AS "last_message_content?",
+                last.created_at AS "last_message_created_at?"
+            FROM dm_participants me
+            JOIN dm_conversations c ON c.id = me.conversation_id
+            LEFT JOIN LATERAL (
+                SELECT id, sender_id, content, created_at
+                FROM direct_messages
+                WHERE conversation_id = c.id
+                ORDER BY created_at DESC, id DESC
+                LIMIT 1
+            ) last ON TRUE
+            WHERE me.user_id = $1
+            ORDER BY c.last_message_at DESC, c.id DESC
+            LIMIT $2
+            "#,
+            user_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// One of the user's conversations; `None` if they are not in it
+    pub async fn find_conversation(
+        &self,
+        id: i32,
+        user_id: i32,
+    ) -> Result<Option<ConversationRow>, sqlx::Error> {
+        sqlx::query_as!(
+            ConversationRow,
+            r#"
+            SELECT
+                c.id,
+                (c.pair_key IS NULL) AS "group!",
+                (
+                    SELECT COUNT(*)
+                    FROM direct_messages m
+                    WHERE m.conversation_id = c.id
+                      AND m.sender_id IS DISTINCT FROM $2
+                      AND m.id > COALESCE(me.last_read_message_id, 0)
+                ) AS "unread_count!",
+                c.created_at,
+                c.last_message_at,
+                last.id AS "last_message_id?",
+                last.sender_id AS "last_message_sender_id?",
+                last.content AS "last_message_content?",
+                last.created_at AS "last_message_created_at?"
+            FROM dm_participants me
+            JOIN dm_conversations c ON c.id = me.conversation_id
+            LEFT JOIN LATERAL (
+                SELECT id, sender_id, content, created_at
+                FROM direct_messages
+                WHERE conversation_id = c.id
+                ORDER BY created_at DESC, id DESC
+                LIMIT 1
+            ) last ON TRUE
+            WHERE c.id = $1 AND me.user_id = $2
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Everyone in the given conversations, in the order they joined
+    pub async fn participants(
+        &self,
+        conversation_ids: &[i32],
+    ) -> Result<Vec<ParticipantRow>, sqlx::Error> {
+        sqlx::query_as!(
+            ParticipantRow,
+            r#"
+            SELECT p.conversation_id, u.id, u.username, p.last_read_message_id
+            FROM dm_participants p
+            JOIN users u ON u.id = p.user_id
+            WHERE p.conversation_id = ANY($1)
+            ORDER BY p.joined_at, u.id
+            "#,
+            conversation_ids
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Store a message; the sender has read everything up to it
+    pub async fn send(
+        &self,
+        conversation_id: i32,
+        sender_id: i32,
+        content: &str,
+    ) -> Result<DirectMessage, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let message = sqlx::query_as!(
+            DirectMessage,
+            r#"
+            INSERT INTO direct_messages (conversation_id, sender_id, content)
+            VALUES ($1, $2, $3)
+            RETURNING id, conversation_id, sender_id, content, created_at
+            "#,
+            conversation_id,
+            sender_id,
+            content
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE dm_conversations
+            SET last_message_at = $2
+            WHERE id = $1
+            "#,
+            conversation_id,
+            message.created_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE dm_participants
+            SET last_read_message_id = $3
+            WHERE conversation_id = $1 AND user_id = $2
+            "#,
+            conversation_id,
+            sender_id,
+            message.id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(message)
+    }
+
+    /// Newest first, older than the `(created_at, id)` cursor when given
+    pub async fn messages_before(
+        &self,
+        conversation_id: i32,
+        limit: i64,
+        before: Option<(DateTime<Utc>, i32)>,
+    ) -> Result<Vec<DirectMessage>, sqlx::Error> {
+        match before {
+            Some((created_at, id)) => {
+                sqlx::query_as!(
+                    DirectMessage,
+                    r#"
+                    SELECT id, conversation_id, sender_id, content, created_at
+                    FROM direct_messages
+                    WHERE conversation_id = $1
+                      AND (created_at, id) < ($2, $3)
+                    ORDER BY created_at DESC, id DESC
+                    LIMIT $4
+                    "#,
+                    conversation_id,
+                    created_at,
+                    id,
+                    limit
+                )
+                .fetch_all(&self.pool)
+                .await
+            }
+            None => {
+                sqlx::query_as!(
+                    DirectMessage,
+                    r#"
+                    SELECT id, conversation_id, sender_id, content, created_at
+                    FROM direct_messages
+                    WHERE conversation_id = $1
+                    ORDER BY created_at DESC, id DESC
+                    LIMIT $2
+                    "#,
+                    conversation_id,
+                    limit
+                )
+                .fetch_all(&self.pool)
+                .await
+            }
+        }
+    }
+
+    /// Whether the message belongs to the conversation
+    pub async fn has_message(
+        &self,
+        conversation_id: i32,
+        message_id: i32,
+    ) -> Result<bool, sqlx::Error> {
+        let record = sqlx::query!(
+            r#"
+            SELECT 1 AS exists
+            FROM direct_messages
+            WHERE id = $1 AND conversation_id = $2
+            "#,
+            message_id,
+            conversation_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record.is_some())
+    }
+
+    /// Move the user's read receipt forward to `up_to`, or to the newest
+    /// message; `None` if it was already there
+    pub async fn mark_read(
+        &self,
+        conversation_id: i32,
+        user_id: i32,
+        up_to: Option<i32>,
+    ) -> Result<Option<i32>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            UPDATE dm_participants p
+            SET last_read_message_id = target.id
+            FROM (
+                SELECT MAX(id) AS id
+                FROM direct_messages
+                WHERE conversation_id = $1 AND ($3::INTEGER IS NULL OR id <= $3)
+            ) target
+            WHERE p.conversation_id = $1
+              AND p.user_id = $2
+              AND target.id > COALESCE(p.last_read_message_id, 0)
+            RETURNING p.last_read_message_id AS "last_read_message_id!"
+            "#,
+            conversation_id,
+            user_id,
+            up_to
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,223 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use sqlx::types::Json;
+
+use crate::models::draft::Draft;
+use crate::models::poll::CreatePollRequest;
+use crate::models::tweet::{ClaimedDraft, CreateTweetRequest};
+
+#[derive(Clone)]
+pub struct DraftRepository {
+    pool: PgPool,
+}
+
+/// Internal DB mapping struct (repository-only)
+struct DraftRow {
+    id: i32,
+    author_id: i32,
+    content: String,
+    media_ids: Vec<i32>,
+    poll: Option<Json<CreatePollRequest>>,
+    reply_to_id: Option<i32>,
+    scheduled_at: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<DraftRow> for Draft {
+    fn from(row: DraftRow) -> Self {
+        Draft {
+            id: row.id,
+            author_id: row.author_id,
+            content: row.content,
+            media_ids: row.media_ids,
+            poll: row.poll.map(|Json(poll)| poll),
+            reply_to_id: row.reply_to_id,
+            scheduled_at: row.scheduled_at,
+            last_error: row.last_error,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+impl DraftRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        author_id: i32,
+        draft: &CreateTweetRequest,
+    ) -> Result<Draft, sqlx::Error> {
+        let row = sqlx::query_as!(
+            DraftRow,
+            r#"
+            INSERT INTO drafts (author_id, content, media_ids, poll, reply_to_id, scheduled_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING
+                id, author_id, content, media_ids,
+                poll AS "poll: Json<CreatePollRequest>",
+                reply_to_id, scheduled_at, last_error, created_at, updated_at
+            "#,
+            author_id,
+            draft.content,
+            &draft.media_ids,
+            draft.poll.clone().map(Json) as Option<Json<CreatePollRequest>>,
+            draft.in_reply_to.map(|id| id as i32),
+            draft.scheduled_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.into())
+    }
+
+    /// Replace a draft's contents; a previous publish error is cleared
+    pub async fn update(
+        &self,
+        id: i32,
+        author_id: i32,
+        draft: &CreateTweetRequest,
+    ) -> Result<Option<Draft>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            DraftRow,
+            r#"
+            UPDATE drafts
+            SET content = $3,
+                media_ids = $4,
+                poll = $5,
+                reply_to_id = $6,
+                scheduled_at = $7,
+                last_error = NULL,
+                updated_at = now()
+            WHERE id = $1 AND author_id = $2
+            RETURNING
+                id, author_id, content, media_ids,
+                poll AS "poll: Json<CreatePollRequest>",
+                reply_to_id, scheduled_at, last_error, created_at, updated_at
+            "#,
+            id,
+            author_id,
+            draft.content,
+            &draft.media_ids,
+            draft.poll.clone().map(Json) as Option<Json<CreatePollRequest>>,
+            draft.in_reply_to.map(|id| id as i32),
+            draft.scheduled_at
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(Into::into))
+    }
+
+    pub async fn find(&self, id: i32, author_id: i32) -> Result<Option<Draft>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            DraftRow,
+            r#"
+            SELECT
+                id, author_id, content, media_ids,
+                poll AS "poll: Json<CreatePollRequest>",
+                reply_to_id, scheduled_at, last_error, created_at, updated_at
+            FROM drafts
+            WHERE id = $1 AND author_id = $2
+            "#,
+            id,
+            author_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(Into::into))
+    }
+
+    /// Most recently edited first
+    pub async fn list(&self, author_id: i32) -> Result<Vec<Draft>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            DraftRow,
+            r#"
+            SELECT
+                id, author_id, content, media_ids,
+                poll AS "poll: Json<CreatePollRequest>",
+                reply_to_id, scheduled_at, last_error, created_at, updated_at
+            FROM drafts
+            WHERE author_id = $1
+            ORDER BY updated_at DESC, id DESC
+            "#,
+            author_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn delete(&self, id: i32, author_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM drafts
+            WHERE id = $1 AND author_id = $2
+            "#,
+            id,
+            author_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Lease due scheduled drafts to this instance; SKIP LOCKED keeps other
+    /// instances from waiting on, or picking, the same rows
+    pub async fn claim_due(&self, limit: i64, lease_secs: f64) -> Result<Vec<Draft>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            DraftRow,
+            r#"
+            UPDATE drafts
+            SET claimed_until = now() + make_interval(secs => $2)
+            WHERE id IN (
+                SELECT id
+                FROM drafts
+                WHERE scheduled_at <= now()
+                  AND (claimed_until IS NULL OR claimed_until < now())
+                ORDER BY scheduled_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING
+                id, author_id, content, media_ids,
+                poll AS "poll: Json<CreatePollRequest>",
+                reply_to_id, scheduled_at, last_error, created_at, updated_at
+            "#,
+            limit,
+            lease_secs
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Turn a scheduled tweet that can no longer be published back into a plain draft
+    pub async fn mark_failed(&self, draft: ClaimedDraft, error: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE drafts
+            SET scheduled_at = NULL,
+                claimed_until = NULL,
+                last_error = $2,
+                updated_at = now()
+            WHERE id = $1 AND updated_at = $3
+            "#,
+            draft.id,
+            error,
+            draft.updated_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
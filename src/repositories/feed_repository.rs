@@ -0,0 +1,130 @@
+use sqlx::PgPool;
+
+use crate::models::feed::FeedCandidate;
+
+#[derive(Clone)]
+pub struct FeedRepository {
+    pool: PgPool,
+}
+
+impl FeedRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Recent tweets from the viewer's network, tweets the accounts they
+    /// follow liked, and the most liked tweets overall so a new account's
+    /// feed is not empty; newest first. Blocked accounts are left out either
+    /// way, and so are whatever the viewer muted and protected accounts they
+    /// do not follow.
+    pub async fn candidates(
+        &self,
+        user_id: i32,
+        window_secs: f64,
+        popular: i64,
+        limit: i64,
+    ) -> Result<Vec<FeedCandidate>, sqlx::Error> {
+        sqlx::query_as!(
+            FeedCandidate,
+            r#"
+            WITH network AS (
+                SELECT following_id AS id FROM follows WHERE follower_id = $1
+                UNION
+                SELECT $1
+            ),
+            candidates AS (
+                SELECT t.id
+                FROM tweets t
+                WHERE t.author_id IN (SELECT id FROM network)
+                  AND t.created_at > now() - make_interval(secs => $2)
+                UNION
+                SELECT l.tweet_id
+                FROM likes l
+                WHERE l.user_id IN (SELECT id FROM network WHERE id <> $1)
+                  AND l.created_at > now() - make_interval(secs => $2)
+                UNION
+                (
+                    SELECT l.tweet_id
+                    FROM likes l
+                    WHERE l.created_at > now() - make_interval(secs => $2)
+                    GROUP BY l.tweet_id
+                    ORDER BY COUNT(*) DESC
+                    LIMIT $3
+                )
+            )
+            SELECT
+                t.id AS tweet_id,
+                t.created_at AS "created_at!",
+                COALESCE(t.author_id IN (SELECT id FROM network), FALSE) AS "from_network!",
+                (
+                    SELECT COUNT(*)
+                    FROM likes l
+                    WHERE l.tweet_id = t.id AND l.user_id IN (SELECT id FROM network WHERE id <> $1)
+                ) AS "liked_by_followed!",
+                (SELECT COUNT(*) FROM likes l WHERE l.tweet_id = t.id) AS "likes!",
+                (SELECT COUNT(*) FROM tweets r WHERE r.reply_to_id = t.id) AS "replies!"
+            FROM candidates c
+            JOIN tweets t ON t.id = c.id
+            WHERE NOT is_hidden_from($1, t.author_id, COALESCE(t.conversation_id, t.id), t.content)
+            ORDER BY t.created_at DESC, t.id DESC
+            LIMIT $4
+            "#,
+            user_id,
+            window_secs,
+            popular,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Store a ranked feed; expired ones are swept at the same time
+    pub async fn create_session(
+        &self,
+        id: &str,
+        user_id: i32,
+        tweet_ids: &[i32],
+        ttl_secs: f64,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM feed_sessions
+            WHERE expires_at < now()
+            "#
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO feed_sessions (id, user_id, tweet_ids, expires_at)
+            VALUES ($1, $2, $3, now() + make_interval(secs => $4))
+            "#,
+            id,
+            user_id,
+            tweet_ids,
+            ttl_secs
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    /// The ranked tweet ids of one of the user's unexpired feeds
+    pub async fn session(&self, id: &str, user_id: i32) -> Result<Option<Vec<i32>>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT tweet_ids
+            FROM feed_sessions
+            WHERE id = $1 AND user_id = $2 AND expires_at > now()
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+}
@@ -1,16 +1,105 @@
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
+
+use crate::models::follow::PendingFollower;
+use crate::models::outbox::OutboxEvent;
+use crate::repositories::outbox_repository;
 
 #[derive(Clone)]
 pub struct FollowRepository {
     pool: PgPool,
 }
 
+/// Remove the follows and pending follow requests between two users in
+/// either direction inside the caller's transaction; returns the removed
+/// follows as (follower, following)
+pub async fn sever(
+    conn: &mut PgConnection,
+    a: i32,
+    b: i32,
+) -> Result<Vec<(i32, i32)>, sqlx::Error> {
+    sqlx::query!(
+        r#"
+        DELETE FROM follow_requests
+        WHERE (follower_id = $1 AND following_id = $2)
+           OR (follower_id = $2 AND following_id = $1)
+        "#,
+        a,
+        b
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let records = sqlx::query!(
+        r#"
+        DELETE FROM follows
+        WHERE (follower_id = $1 AND following_id = $2)
+           OR (follower_id = $2 AND following_id = $1)
+        RETURNING follower_id, following_id
+        "#,
+        a,
+        b
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(records
+        .into_iter()
+        .map(|row| (row.follower_id, row.following_id))
+        .collect())
+}
+
+/// Turn every pending request to follow `following_id` into a follow inside
+/// the caller's transaction, each with its outbox event; returns how many
+pub async fn approve_all_requests(
+    conn: &mut PgConnection,
+    following_id: i32,
+) -> Result<usize, sqlx::Error> {
+    let followers = sqlx::query_scalar!(
+        r#"
+        DELETE FROM follow_requests
+        WHERE following_id = $1
+        RETURNING follower_id
+        "#,
+        following_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for &follower_id in &followers {
+        sqlx::query!(
+            r#"
+            INSERT INTO follows (follower_id, following_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            follower_id,
+            following_id
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        outbox_repository::enqueue(
+            &mut *conn,
+            &OutboxEvent::Followed {
+                follower_id,
+                following_id,
+            },
+        )
+        .await?;
+    }
+
+    Ok(followers.len())
+}
+
 impl FollowRepository {
     pub fn new(pool: PgPool) -> Self {
         Self { pool }
     }
 
+    /// Insert the follow together with its outbox event
     pub async fn follow(&self, follower_id: i32, following_id: i32) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
         sqlx::query!(
             r#"
             INSERT INTO follows (follower_id, following_id)
@@ -19,10 +108,19 @@ impl FollowRepository {
             follower_id,
             following_id
         )
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
 
-        Ok(())
+        outbox_repository::enqueue(
+            &mut tx,
+            &OutboxEvent::Followed {
+                follower_id,
+                following_id,
+            },
+        )
+        .await?;
+
+        tx.commit().await
     }
 
     pub async fn unfollow(&self, follower_id: i32, following_id: i32) -> Result<(), sqlx::Error> {
@@ -59,4 +157,124 @@ impl FollowRepository {
 
         Ok(record.is_some())
     }
+
+    /// Ask to follow a protected account; false if already asked
+    pub async fn request(&self, follower_id: i32, following_id: i32) -> Result<bool, sqlx::Error> {
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO follow_requests (follower_id, following_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            follower_id,
+            following_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(inserted.rows_affected() > 0)
+    }
+
+    /// Withdraw or deny a request; false if there was none
+    pub async fn delete_request(
+        &self,
+        follower_id: i32,
+        following_id: i32,
+    ) -> Result<bool, sqlx::Error> {
+        let deleted = sqlx::query!(
+            r#"
+            DELETE FROM follow_requests
+            WHERE follower_id = $1 AND following_id = $2
+            "#,
+            follower_id,
+            following_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(deleted.rows_affected() > 0)
+    }
+
+    /// Replace a request with the follow and its outbox event; false if
+    /// there was no such request
+    pub async fn approve_request(
+        &self,
+        follower_id: i32,
+        following_id: i32,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let deleted = sqlx::query!(
+            r#"
+            DELETE FROM follow_requests
+            WHERE follower_id = $1 AND following_id = $2
+            "#,
+            follower_id,
+            following_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if deleted.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO follows (follower_id, following_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            follower_id,
+            following_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        outbox_repository::enqueue(
+            &mut tx,
+            &OutboxEvent::Followed {
+                follower_id,
+                following_id,
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Requests waiting for `following_id` to approve, newest first
+    pub async fn pending_followers(
+        &self,
+        following_id: i32,
+    ) -> Result<Vec<PendingFollower>, sqlx::Error> {
+        sqlx::query_as!(
+            PendingFollower,
+            r#"
+            SELECT u.id, u.username, r.created_at AS requested_at
+            FROM follow_requests r
+            JOIN users u ON u.id = r.follower_id
+            WHERE r.following_id = $1
+            ORDER BY r.created_at DESC, u.id DESC
+            "#,
+            following_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn following_ids(&self, follower_id: i32) -> Result<Vec<i32>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT following_id
+            FROM follows
+            WHERE follower_id = $1
+            "#,
+            follower_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
 }
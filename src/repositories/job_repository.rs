@@ -0,0 +1,130 @@
+use sqlx::PgPool;
+use sqlx::types::Json;
+
+use crate::models::job::Job;
+
+#[derive(Clone)]
+pub struct JobRepository {
+    pool: PgPool,
+}
+
+impl JobRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn enqueue(
+        &self,
+        job_type: &str,
+        payload: &serde_json::Value,
+        max_attempts: i32,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            INSERT INTO jobs (job_type, payload, max_attempts)
+            VALUES ($1, $2, $3)
+            RETURNING id
+            "#,
+            job_type,
+            Json(payload) as _,
+            max_attempts
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Lease due jobs of one type to this worker; SKIP LOCKED keeps workers
+    /// here and on other instances from taking the same rows
+    pub async fn claim(
+        &self,
+        job_type: &str,
+        limit: i64,
+        lease_secs: f64,
+    ) -> Result<Vec<Job>, sqlx::Error> {
+        sqlx::query_as!(
+            Job,
+            r#"
+            UPDATE jobs
+            SET locked_until = now() + make_interval(secs => $3),
+                attempts = attempts + 1
+            WHERE id IN (
+                SELECT id
+                FROM jobs
+                WHERE job_type = $1
+                  AND run_at <= now()
+                  AND (locked_until IS NULL OR locked_until < now())
+                ORDER BY run_at, id
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, job_type, payload, attempts, max_attempts
+            "#,
+            job_type,
+            limit,
+            lease_secs
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// A finished job leaves the queue
+    pub async fn complete(&self, id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM jobs
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn retry_later(
+        &self,
+        id: i64,
+        error: &str,
+        delay_secs: f64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE jobs
+            SET run_at = now() + make_interval(secs => $3),
+                locked_until = NULL,
+                last_error = $2
+            WHERE id = $1
+            "#,
+            id,
+            error,
+            delay_secs
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Move a job that will not be retried to the dead-letter table
+    pub async fn bury(&self, id: i64, error: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            WITH dead AS (
+                DELETE FROM jobs
+                WHERE id = $1
+                RETURNING id, job_type, payload, attempts, created_at
+            )
+            INSERT INTO dead_jobs (id, job_type, payload, attempts, last_error, created_at)
+            SELECT id, job_type, payload, attempts, $2, created_at
+            FROM dead
+            "#,
+            id,
+            error
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
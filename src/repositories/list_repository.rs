@@ -0,0 +1,322 @@
+use sqlx::{PgConnection, PgPool};
+
+use crate::models::list::List;
+use crate::models::user::User;
+
+#[derive(Clone)]
+pub struct ListRepository {
+    pool: PgPool,
+}
+
+/// Take each of two users off the other's lists inside the caller's transaction
+pub async fn remove_between(conn: &mut PgConnection, a: i32, b: i32) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        DELETE FROM list_members lm
+        USING lists l
+        WHERE l.id = lm.list_id
+          AND ((l.owner_id = $1 AND lm.user_id = $2)
+            OR (l.owner_id = $2 AND lm.user_id = $1))
+        "#,
+        a,
+        b
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+impl ListRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        owner_id: i32,
+        name: &str,
+        description: &str,
+        private: bool,
+    ) -> Result<List, sqlx::Error> {
+        sqlx::query_as!(
+            List,
+            r#"
+            INSERT INTO lists (owner_id, name, description, private)
+            VALUES ($1, $2, $3, $4)
+            RETURNING
+                id,
+                owner_id,
+                name,
+                description,
+                private,
+                0::BIGINT AS "member_count!",
+                0::BIGINT AS "subscriber_count!",
+                created_at,
+                updated_at
+            "#,
+            owner_id,
+            name,
+            description,
+            private
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// The list unless it is private and `viewer_id` is not its owner
+    pub async fn find_visible(
+        &self,
+        id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<Option<List>, sqlx::Error> {
+        sqlx::query_as!(
+            List,
+            r#"
+            SELECT
+                l.id,
+                l.owner_id,
+                l.name,
+                l.description,
+                l.private,
+                (SELECT COUNT(*) FROM list_members m WHERE m.list_id = l.id) AS "member_count!",
+                (SELECT COUNT(*) FROM list_subscriptions s WHERE s.list_id = l.id)
+                    AS "subscriber_count!",
+                l.created_at,
+                l.updated_at
+            FROM lists l
+            WHERE l.id = $1 AND (NOT l.private OR l.owner_id = $2)
+            "#,
+            id,
+            viewer_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Lists `owner_id` made that `viewer_id` may see, newest first
+    pub async fn owned_by(
+        &self,
+        owner_id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<Vec<List>, sqlx::Error> {
+        sqlx::query_as!(
+            List,
+            r#"
+            SELECT
+                l.id,
+                l.owner_id,
+                l.name,
+                l.description,
+                l.private,
+                (SELECT COUNT(*) FROM list_members m WHERE m.list_id = l.id) AS "member_count!",
+                (SELECT COUNT(*) FROM list_subscriptions s WHERE s.list_id = l.id)
+                    AS "subscriber_count!",
+                l.created_at,
+                l.updated_at
+            FROM lists l
+            WHERE l.owner_id = $1 AND (NOT l.private OR l.owner_id = $2)
+            ORDER BY l.created_at DESC, l.id DESC
+            "#,
+            owner_id,
+            viewer_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Lists the user subscribed to, most recently subscribed first
+    pub async fn subscribed_by(&self, user_id: i32) -> Result<Vec<List>, sqlx::Error> {
+        sqlx::query_as!(
+            List,
+            r#"
+            SELECT
+                l.id,
+                l.owner_id,
+                l.name,
+                l.description,
+                l.private,
+                (SELECT COUNT(*) FROM list_members m WHERE m.list_id = l.id) AS "member_count!",
+                (SELECT COUNT(*) FROM list_subscriptions s WHERE s.list_id = l.id)
+                    AS "subscriber_count!",
+                l.created_at,
+                l.updated_at
+            FROM list_subscriptions sub
+            JOIN lists l ON l.id = sub.list_id
+            WHERE sub.user_id = $1 AND NOT l.private
+            ORDER BY sub.created_at DESC, l.id DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn count_owned(&self, owner_id: i32) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM lists
+            WHERE owner_id = $1
+            "#,
+            owner_id
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Replace one of the owner's lists; making it private drops its
+    /// subscribers. `None` if they have no such list.
+    pub async fn update(
+        &self,
+        id: i32,
+        owner_id: i32,
+        name: &str,
+        description: &str,
+        private: bool,
+    ) -> Result<Option<List>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let updated = sqlx::query!(
+            r#"
+            UPDATE lists
+            SET name = $3, description = $4, private = $5, updated_at = now()
+            WHERE id = $1 AND owner_id = $2
+            "#,
+            id,
+            owner_id,
+            name,
+            description,
+            private
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        if private {
+            sqlx::query!(
+                r#"
+                DELETE FROM list_subscriptions
+                WHERE list_id = $1
+                "#,
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        self.find_visible(id, Some(owner_id)).await
+    }
+
+    pub async fn delete(&self, id: i32, owner_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM lists
+            WHERE id = $1 AND owner_id = $2
+            "#,
+            id,
+            owner_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// False if the user was already a member
+    pub async fn add_member(&self, list_id: i32, user_id: i32) -> Result<bool, sqlx::Error> {
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO list_members (list_id, user_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            list_id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(inserted.rows_affected() > 0)
+    }
+
+    /// False if the user was not a member
+    pub async fn remove_member(&self, list_id: i32, user_id: i32) -> Result<bool, sqlx::Error> {
+        let deleted = sqlx::query!(
+            r#"
+            DELETE FROM list_members
+            WHERE list_id = $1 AND user_id = $2
+            "#,
+            list_id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(deleted.rows_affected() > 0)
+    }
+
+    /// Most recently added first
+    pub async fn members(&self, list_id: i32) -> Result<Vec<User>, sqlx::Error> {
+        sqlx::query_as!(
+            User,
+            r#"
+            SELECT
+                u.id AS "id!",
+                u.username AS "username!",
+                u.display_name,
+                u.bio,
+                u.location,
+                u.website,
+                u.avatar_url,
+                u.banner_url,
+                u.created_at AS "created_at!"
+            FROM list_members m
+            JOIN user_profiles u ON u.id = m.user_id
+            WHERE m.list_id = $1
+            ORDER BY m.created_at DESC, u.id DESC
+            "#,
+            list_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// False if the user was already subscribed
+    pub async fn subscribe(&self, list_id: i32, user_id: i32) -> Result<bool, sqlx::Error> {
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO list_subscriptions (list_id, user_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            list_id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(inserted.rows_affected() > 0)
+    }
+
+    /// False if the user was not subscribed
+    pub async fn unsubscribe(&self, list_id: i32, user_id: i32) -> Result<bool, sqlx::Error> {
+        let deleted = sqlx::query!(
+            r#"
+            DELETE FROM list_subscriptions
+            WHERE list_id = $1 AND user_id = $2
+            "#,
+            list_id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(deleted.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,128 @@
+use sqlx::PgPool;
+
+use crate::models::media::{Media, NewMedia, NewMediaVariant};
+
+#[derive(Clone)]
+pub struct MediaRepository {
+    pool: PgPool,
+}
+
+impl MediaRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, media: NewMedia) -> Result<Media, sqlx::Error> {
+        let record = sqlx::query!(
+            r#"
+            INSERT INTO media
+                (owner_id, blob_key, url, content_type, size_bytes, width, height, alt_text)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, url, content_type, width, height, alt_text
+            "#,
+            media.owner_id,
+            media.blob_key,
+            media.url,
+            media.content_type,
+            media.size_bytes,
+            media.width,
+            media.height,
+            media.alt_text
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Media {
+            id: record.id,
+            url: record.url,
+            content_type: record.content_type,
+            width: record.width,
+            height: record.height,
+            alt_text: record.alt_text,
+            variants: Vec::new(),
+        })
+    }
+
+    /// Record a rendition; regenerating an existing one replaces it
+    pub async fn upsert_variant(&self, variant: NewMediaVariant) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO media_variants
+                (media_id, name, blob_key, url, content_type, width, height)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (media_id, name) DO UPDATE
+            SET blob_key = EXCLUDED.blob_key,
+                url = EXCLUDED.url,
+                content_type = EXCLUDED.content_type,
+                width = EXCLUDED.width,
+                height = EXCLUDED.height
+            "#,
+            variant.media_id,
+            variant.name,
+            variant.blob_key,
+            variant.url,
+            variant.content_type,
+            variant.width,
+            variant.height
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Content type of a stored blob, if any upload or rendition references it
+    pub async fn content_type_of(&self, blob_key: &str) -> Result<Option<String>, sqlx::Error> {
+        let record = sqlx::query!(
+            r#"
+            SELECT content_type AS "content_type!"
+            FROM media
+            WHERE blob_key = $1
+            UNION ALL
+            SELECT content_type
+            FROM media_variants
+            WHERE blob_key = $1
+            LIMIT 1
+            "#,
+            blob_key
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record.map(|row| row.content_type))
+    }
+
+    /// Whether the upload exists and belongs to `owner_id`
+    pub async fn is_owned_by(&self, id: i32, owner_id: i32) -> Result<bool, sqlx::Error> {
+        let record = sqlx::query!(
+            r#"
+            SELECT 1 AS exists
+            FROM media
+            WHERE id = $1 AND owner_id = $2
+            "#,
+            id,
+            owner_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record.is_some())
+    }
+
+    /// How many of the given uploads belong to `owner_id` and are not attached yet
+    pub async fn count_attachable(&self, ids: &[i32], owner_id: i32) -> Result<i64, sqlx::Error> {
+        let record = sqlx::query!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM media
+            WHERE id = ANY($1) AND owner_id = $2 AND tweet_id IS NULL
+            "#,
+            ids,
+            owner_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(record.count)
+    }
+}
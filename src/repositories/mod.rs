@@ -1,3 +1,17 @@
+pub mod block_repository;
+pub mod direct_message_repository;
+pub mod draft_repository;
+pub mod feed_repository;
 pub mod follow_repository;
+pub mod job_repository;
+pub mod list_repository;
+pub mod media_repository;
+pub mod mute_repository;
+pub mod notification_repository;
+pub mod outbox_repository;
+pub mod search_repository;
+pub mod suggestion_repository;
+pub mod trend_repository;
 pub mod tweet_repository;
 pub mod user_repository;
+pub mod webhook_repository;
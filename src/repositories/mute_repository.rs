@@ -0,0 +1,234 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::models::mute::KeywordMute;
+use crate::models::user::User;
+
+#[derive(Clone)]
+pub struct MuteRepository {
+    pool: PgPool,
+}
+
+impl MuteRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// False if the user was already muted
+    pub async fn mute_user(&self, user_id: i32, muted_id: i32) -> Result<bool, sqlx::Error> {
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO user_mutes (user_id, muted_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            user_id,
+            muted_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(inserted.rows_affected() > 0)
+    }
+
+    /// False if the user was not muted
+    pub async fn unmute_user(&self, user_id: i32, muted_id: i32) -> Result<bool, sqlx::Error> {
+        let deleted = sqlx::query!(
+            r#"
+            DELETE FROM user_mutes
+            WHERE user_id = $1 AND muted_id = $2
+            "#,
+            user_id,
+            muted_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(deleted.rows_affected() > 0)
+    }
+
+    /// Most recently muted first
+    pub async fn muted_users(&self, user_id: i32) -> Result<Vec<User>, sqlx::Error> {
+        sqlx::query_as!(
+            User,
+            r#"
+            SELECT
+                u.id AS "id!",
+                u.username AS "username!",
+                u.display_name,
+                u.bio,
+                u.location,
+                u.website,
+                u.avatar_url,
+                u.banner_url,
+                u.created_at AS "created_at!"
+            FROM user_mutes m
+            JOIN user_profiles u ON u.id = m.muted_id
+            WHERE m.user_id = $1
+            ORDER BY m.created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Store a keyword mute, first dropping the user's expired ones; `None`
+    /// if the phrase is already muted
+    pub async fn add_keyword(
+        &self,
+        user_id: i32,
+        phrase: &str,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<KeywordMute>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM keyword_mutes
+            WHERE user_id = $1 AND expires_at <= now()
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let keyword = sqlx::query_as!(
+            KeywordMute,
+            r#"
+            INSERT INTO keyword_mutes (user_id, phrase, expires_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            RETURNING id, phrase, expires_at, created_at
+            "#,
+            user_id,
+            phrase,
+            expires_at
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(keyword)
+    }
+
+    /// Keyword mutes that have not expired, newest first
+    pub async fn keywords(&self, user_id: i32) -> Result<Vec<KeywordMute>, sqlx::Error> {
+        sqlx::query_as!(
+            KeywordMute,
+            r#"
+            SELECT id, phrase, expires_at, created_at
+            FROM keyword_mutes
+            WHERE user_id = $1 AND (expires_at IS NULL OR expires_at > now())
+            ORDER BY created_at DESC, id DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// False if the user has no such keyword mute
+    pub async fn remove_keyword(&self, user_id: i32, id: i32) -> Result<bool, sqlx::Error> {
+        let deleted = sqlx::query!(
+            r#"
+            DELETE FROM keyword_mutes
+            WHERE id = $1 AND user_id = $2
+            "#,
+            id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(deleted.rows_affected() > 0)
+    }
+
+    /// False if the thread was already muted
+    pub async fn mute_conversation(
+        &self,
+        user_id: i32,
+        conversation_id: i32,
+    ) -> Result<bool, sqlx::Error> {
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO conversation_mutes (user_id, conversation_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            user_id,
+            conversation_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(inserted.rows_affected() > 0)
+    }
+
+    /// False if the thread was not muted
+    pub async fn unmute_conversation(
+        &self,
+        user_id: i32,
+        conversation_id: i32,
+    ) -> Result<bool, sqlx::Error> {
+        let deleted = sqlx::query!(
+            r#"
+            DELETE FROM conversation_mutes
+            WHERE user_id = $1 AND conversation_id = $2
+            "#,
+            user_id,
+            conversation_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(deleted.rows_affected() > 0)
+    }
+
+    /// Whether the recipient muted the actor or the tweet a notification is
+    /// about, by the same rules the notifications list applies
+    pub async fn hides_notification(
+        &self,
+        recipient_id: i32,
+        actor_id: i32,
+        tweet_id: Option<i32>,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT
+                is_muted($1, $2, NULL, NULL)
+                OR COALESCE(
+                    (
+                        SELECT is_muted($1, t.author_id, COALESCE(t.conversation_id, t.id), t.content)
+                        FROM tweets t
+                        WHERE t.id = $3
+                    ),
+                    FALSE
+                ) AS "muted!"
+            "#,
+            recipient_id,
+            actor_id,
+            tweet_id
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Whether the viewer muted the tweet's author, its thread or a phrase in
+    /// it, by the same rules timelines apply
+    pub async fn hides_tweet(&self, viewer_id: i32, tweet_id: i32) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT is_muted($1, author_id, COALESCE(conversation_id, id), content) AS "muted!"
+            FROM tweets
+            WHERE id = $2
+            "#,
+            viewer_id,
+            tweet_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map(|muted| muted.unwrap_or(false))
+    }
+}
@@ -0,0 +1,167 @@
+use sqlx::PgPool;
+
+use crate::models::notification::{NotificationGroupRow, NotificationKind};
+
+#[derive(Clone)]
+pub struct NotificationRepository {
+    pool: PgPool,
+}
+
+impl NotificationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Returns false if the same notification already exists
+    pub async fn create(
+        &self,
+        recipient_id: i32,
+        actor_id: i32,
+        kind: NotificationKind,
+        tweet_id: Option<i32>,
+        group_key: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO notifications (recipient_id, actor_id, kind, tweet_id, group_key)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT DO NOTHING
+            "#,
+            recipient_id,
+            actor_id,
+            kind.as_str(),
+            tweet_id,
+            group_key
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Withdraw an event that was undone, e.g. an unlike or unfollow
+    pub async fn delete(
+        &self,
+        recipient_id: i32,
+        actor_id: i32,
+        kind: NotificationKind,
+        tweet_id: Option<i32>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM notifications
+            WHERE recipient_id = $1
+              AND actor_id = $2
+              AND kind = $3
+              AND tweet_id IS NOT DISTINCT FROM $4
+            "#,
+            recipient_id,
+            actor_id,
+            kind.as_str(),
+            tweet_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Groups ordered by their newest notification, older than `before`;
+    /// notifications from muted users or about muted or protected tweets are
+    /// left out
+    pub async fn groups_before(
+        &self,
+        recipient_id: i32,
+        before: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<NotificationGroupRow>, sqlx::Error> {
+        sqlx::query_as!(
+            NotificationGroupRow,
+            r#"
+            SELECT
+                MIN(n.kind) AS "kind!",
+                MAX(n.tweet_id) AS tweet_id,
+                (array_agg(n.actor_id ORDER BY n.id DESC))[1:3] AS "actor_ids!",
+                COUNT(DISTINCT n.actor_id) AS "actor_count!",
+                MAX(n.id) AS "latest_id!",
+                MAX(n.created_at) AS "latest_at!"
+            FROM notifications n
+            LEFT JOIN tweets t ON t.id = n.tweet_id
+            WHERE n.recipient_id = $1
+              AND NOT is_muted($1, n.actor_id, NULL, NULL)
+              AND NOT is_muted($1, t.author_id, COALESCE(t.conversation_id, t.id), t.content)
+              AND NOT is_protected_from($1, t.author_id)
+            GROUP BY n.group_key
+            HAVING $2::BIGINT IS NULL OR MAX(n.id) < $2
+            ORDER BY MAX(n.id) DESC
+            LIMIT $3
+            "#,
+            recipient_id,
+            before,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn watermark(&self, user_id: i32) -> Result<i64, sqlx::Error> {
+        let record = sqlx::query!(
+            r#"
+            SELECT watermark
+            FROM notification_reads
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record.map(|row| row.watermark).unwrap_or(0))
+    }
+
+    /// The watermark only moves forward, so stale clients cannot unread anything,
+    /// and never past the newest notification, so future ones stay unread
+    pub async fn advance_watermark(&self, user_id: i32, up_to: i64) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            INSERT INTO notification_reads (user_id, watermark)
+            VALUES (
+                $1,
+                LEAST(
+                    $2,
+                    (SELECT COALESCE(MAX(id), 0) FROM notifications WHERE recipient_id = $1)
+                )
+            )
+            ON CONFLICT (user_id) DO UPDATE
+            SET watermark = GREATEST(notification_reads.watermark, EXCLUDED.watermark)
+            RETURNING watermark
+            "#,
+            user_id,
+            up_to
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Unread notifications, not counting muted or protected ones
+    pub async fn unread_count(&self, user_id: i32) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM notifications n
+            LEFT JOIN tweets t ON t.id = n.tweet_id
+            WHERE n.recipient_id = $1
+              AND NOT is_muted($1, n.actor_id, NULL, NULL)
+              AND NOT is_muted($1, t.author_id, COALESCE(t.conversation_id, t.id), t.content)
+              AND NOT is_protected_from($1, t.author_id)
+              AND n.id > COALESCE(
+                  (SELECT watermark FROM notification_reads WHERE user_id = $1),
+                  0
+              )
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+}
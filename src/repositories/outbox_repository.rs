@@ -0,0 +1,167 @@
+use sqlx::types::Json;
+use sqlx::{PgConnection, PgPool};
+
+use crate::models::outbox::{OutboxEvent, OutboxMessage};
+
+#[derive(Clone)]
+pub struct OutboxRepository {
+    pool: PgPool,
+}
+
+/// Record an event inside the caller's transaction, so it exists exactly
+/// when the write that caused it does
+pub async fn enqueue(conn: &mut PgConnection, event: &OutboxEvent) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO outbox (event)
+        VALUES ($1)
+        "#,
+        Json(event) as _
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Internal DB mapping struct (repository-only)
+struct OutboxRow {
+    id: i64,
+    event: Json<serde_json::Value>,
+    attempts: i32,
+    completed_handlers: Vec<String>,
+}
+
+impl OutboxRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Lease pending events to this instance, oldest first; SKIP LOCKED keeps
+    /// relays on other instances from taking the same rows
+    pub async fn claim_batch(
+        &self,
+        limit: i64,
+        lease_secs: f64,
+    ) -> Result<Vec<OutboxMessage>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            OutboxRow,
+            r#"
+            UPDATE outbox
+            SET claimed_until = now() + make_interval(secs => $2)
+            WHERE id IN (
+                SELECT id
+                FROM outbox
+                WHERE processed_at IS NULL
+                  AND abandoned_at IS NULL
+                  AND next_attempt_at <= now()
+                  AND (claimed_until IS NULL OR claimed_until < now())
+                ORDER BY id
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING
+                id,
+                event AS "event: Json<serde_json::Value>",
+                attempts,
+                completed_handlers
+            "#,
+            limit,
+            lease_secs
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut messages = Vec::with_capacity(rows.len());
+        for row in rows {
+            match serde_json::from_value::<OutboxEvent>(row.event.0) {
+                Ok(event) => messages.push(OutboxMessage {
+                    id: row.id,
+                    event,
+                    attempts: row.attempts,
+                    completed_handlers: row.completed_handlers,
+                }),
+                // Retrying cannot help, and it must not hold up the rest
+                Err(e) => self.mark_failed(row.id, &e.to_string(), None).await?,
+            }
+        }
+        messages.sort_by_key(|message| message.id);
+
+        Ok(messages)
+    }
+
+    /// Record that one handler is done with the event, whatever the others do
+    pub async fn mark_handled(&self, id: i64, handler: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE outbox
+            SET completed_handlers = array_append(completed_handlers, $2)
+            WHERE id = $1 AND NOT ($2 = ANY(completed_handlers))
+            "#,
+            id,
+            handler
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_processed(&self, id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE outbox
+            SET processed_at = now(), claimed_until = NULL
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Delete events every handler finished more than `retention_secs` ago;
+    /// abandoned events stay for inspection
+    pub async fn delete_processed(&self, retention_secs: f64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM outbox
+            WHERE processed_at < now() - make_interval(secs => $1)
+            "#,
+            retention_secs
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Schedule another attempt after `retry_in_secs`, or give up when `None`
+    pub async fn mark_failed(
+        &self,
+        id: i64,
+        error: &str,
+        retry_in_secs: Option<f64>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE outbox
+            SET attempts = attempts + 1,
+                last_error = $2,
+                claimed_until = NULL,
+                next_attempt_at = now() + make_interval(secs => COALESCE($3::float8, 0)),
+                abandoned_at = CASE WHEN $3::float8 IS NULL THEN now() END
+            WHERE id = $1
+            "#,
+            id,
+            error,
+            retry_in_secs
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::models::search::{TweetHit, TweetSearchQuery};
+
+#[derive(Clone)]
+pub struct SearchRepository {
+    pool: PgPool,
+}
+
+impl SearchRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Best matches first; tweets that tie on rank go newest first
+    pub async fn tweets_by_relevance(
+        &self,
+        query: &TweetSearchQuery,
+        after: Option<(f32, i32)>,
+        limit: i64,
+    ) -> Result<Vec<TweetHit>, sqlx::Error> {
+        let (after_rank, after_id) = after.unzip();
+
+        sqlx::query_as!(
+            TweetHit,
+            r#"
+            SELECT id AS "tweet_id!", created_at AS "created_at!", rank AS "rank!"
+            FROM (
+                SELECT
+                    t.id,
+                    t.created_at,
+                    CASE
+                        WHEN $1::TEXT IS NULL THEN 0::REAL
+                        ELSE ts_rank(t.search_vector, websearch_to_tsquery('english', $1))
+                    END AS rank
+                FROM tweets t
+                WHERE ($1::TEXT IS NULL OR t.search_vector @@ websearch_to_tsquery('english', $1))
+                  AND ($2::INTEGER IS NULL OR t.author_id = $2)
+                  AND NOT EXISTS (
+                      SELECT 1
+                      FROM unnest($3::TEXT[]) AS tag
+                      WHERE t.content !~* ('(^|[^[:alnum:]_])#' || tag || '($|[^[:alnum:]_])')
+                  )
+                  AND ($4::TIMESTAMPTZ IS NULL OR t.created_at >= $4)
+                  AND ($5::TIMESTAMPTZ IS NULL OR t.created_at < $5)
+                  AND COALESCE(t.author_id <> ALL($9), TRUE)
+                  AND NOT is_protected_from($10, t.author_id)
+            ) hits
+            WHERE $6::REAL IS NULL OR (rank, id) < ($6, $7)
+            ORDER BY rank DESC, id DESC
+            LIMIT $8
+            "#,
+            query.text,
+            query.author_id,
+            &query.hashtags,
+            query.since,
+            query.until,
+            after_rank,
+            after_id,
+            limit,
+            &query.excluded_authors,
+            query.viewer_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Newest first, in the same order as the cursor timeline
+    pub async fn tweets_by_recency(
+        &self,
+        query: &TweetSearchQuery,
+        before: Option<(DateTime<Utc>, i32)>,
+        limit: i64,
+    ) -> Result<Vec<TweetHit>, sqlx::Error> {
+        let (before_created_at, before_id) = before.unzip();
+
+        sqlx::query_as!(
+            TweetHit,
+            r#"
+            SELECT
+                t.id AS "tweet_id!",
+                t.created_at AS "created_at!",
+                0::REAL AS "rank!"
+            FROM tweets t
+            WHERE ($1::TEXT IS NULL OR t.search_vector @@ websearch_to_tsquery('english', $1))
+              AND ($2::INTEGER IS NULL OR t.author_id = $2)
+              AND NOT EXISTS (
+                  SELECT 1
+                  FROM unnest($3::TEXT[]) AS tag
+                  WHERE t.content !~* ('(^|[^[:alnum:]_])#' || tag || '($|[^[:alnum:]_])')
+              )
+              AND ($4::TIMESTAMPTZ IS NULL OR t.created_at >= $4)
+              AND ($5::TIMESTAMPTZ IS NULL OR t.created_at < $5)
+              AND ($6::TIMESTAMPTZ IS NULL OR (t.created_at, t.id) < ($6, $7))
+              AND COALESCE(t.author_id <> ALL($9), TRUE)
+              AND NOT is_protected_from($10, t.author_id)
+            ORDER BY t.created_at DESC, t.id DESC
+            LIMIT $8
+            "#,
+            query.text,
+            query.author_id,
+            &query.hashtags,
+            query.since,
+            query.until,
+            before_created_at,
+            before_id,
+            limit,
+            &query.excluded_authors,
+            query.viewer_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,213 @@
+use sqlx::PgPool;
+
+use crate::models::suggestion::SuggestedUser;
+
+#[derive(Clone)]
+pub struct SuggestionRepository {
+    pool: PgPool,
+}
+
+impl SuggestionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn following_count(&self, user_id: i32) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM follows
+            WHERE follower_id = $1
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Accounts followed by the user's followees that the user does not
+    /// follow yet, those with the most mutual followees first; accounts
+    /// blocked either way or muted by the user are left out
+    pub async fn friends_of_friends(
+        &self,
+        user_id: i32,
+        limit: i64,
+    ) -> Result<Vec<SuggestedUser>, sqlx::Error> {
+        sqlx::query_as!(
+            SuggestedUser,
+            r#"
+            WITH candidates AS (
+                SELECT
+                    f.following_id AS id,
+                    COUNT(*) AS mutual_count,
+                    (ARRAY_AGG(f.follower_id ORDER BY f.created_at DESC, f.follower_id))[1:2]
+                        AS mutual_ids
+                FROM follows mine
+                JOIN follows f ON f.follower_id = mine.following_id
+                WHERE mine.follower_id = $1
+                  AND f.following_id <> $1
+                  AND NOT EXISTS (
+                      SELECT 1 FROM follows already
+                      WHERE already.follower_id = $1 AND already.following_id = f.following_id
+                  )
+                  AND NOT EXISTS (
+                      SELECT 1 FROM blocks b
+                      WHERE (b.blocker_id = $1 AND b.blocked_id = f.following_id)
+                         OR (b.blocker_id = f.following_id AND b.blocked_id = $1)
+                  )
+                  AND NOT EXISTS (
+                      SELECT 1 FROM user_mutes m
+                      WHERE m.user_id = $1 AND m.muted_id = f.following_id
+                  )
+                GROUP BY f.following_id
+            )
+            SELECT
+                u.id,
+                u.username,
+                u.display_name,
+                followers.count AS "followers_count!",
+                c.mutual_count AS "mutual_count!",
+                c.mutual_ids AS "mutual_ids!"
+            FROM candidates c
+            JOIN users u ON u.id = c.id
+            CROSS JOIN LATERAL (
+                SELECT COUNT(*) AS count FROM follows WHERE following_id = u.id
+            ) followers
+            ORDER BY c.mutual_count DESC, followers.count DESC, u.id
+            LIMIT $2
+            "#,
+            user_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// The stored suggestions, minus anyone the user has followed, blocked or
+    /// muted since
+    pub async fn precomputed(
+        &self,
+        user_id: i32,
+        limit: i64,
+    ) -> Result<Vec<SuggestedUser>, sqlx::Error> {
+        sqlx::query_as!(
+            SuggestedUser,
+            r#"
+            SELECT
+                u.id,
+                u.username,
+                u.display_name,
+                followers.count AS "followers_count!",
+                s.mutual_count,
+                s.mutual_ids
+            FROM follow_suggestions s
+            JOIN users u ON u.id = s.suggested_id
+            CROSS JOIN LATERAL (
+                SELECT COUNT(*) AS count FROM follows WHERE following_id = u.id
+            ) followers
+            WHERE s.user_id = $1
+              AND NOT EXISTS (
+                  SELECT 1 FROM follows already
+                  WHERE already.follower_id = $1 AND already.following_id = s.suggested_id
+              )
+              AND NOT EXISTS (
+                  SELECT 1 FROM blocks b
+                  WHERE (b.blocker_id = $1 AND b.blocked_id = s.suggested_id)
+                     OR (b.blocker_id = s.suggested_id AND b.blocked_id = $1)
+              )
+              AND NOT EXISTS (
+                  SELECT 1 FROM user_mutes m
+                  WHERE m.user_id = $1 AND m.muted_id = s.suggested_id
+              )
+            ORDER BY s.mutual_count DESC, followers.count DESC, u.id
+            LIMIT $2
+            "#,
+            user_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Users following at least `min_following` accounts
+    pub async fn large_graph_users(&self, min_following: i64) -> Result<Vec<i32>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT follower_id
+            FROM follows
+            GROUP BY follower_id
+            HAVING COUNT(*) >= $1
+            "#,
+            min_following
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Replace the user's stored suggestions with the current top `limit`
+    pub async fn precompute(&self, user_id: i32, limit: i64) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM follow_suggestions
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO follow_suggestions (user_id, suggested_id, mutual_count, mutual_ids)
+            SELECT
+                $1,
+                f.following_id,
+                COUNT(*),
+                (ARRAY_AGG(f.follower_id ORDER BY f.created_at DESC, f.follower_id))[1:2]
+            FROM follows mine
+            JOIN follows f ON f.follower_id = mine.following_id
+            WHERE mine.follower_id = $1
+              AND f.following_id <> $1
+              AND NOT EXISTS (
+                  SELECT 1 FROM follows already
+                  WHERE already.follower_id = $1 AND already.following_id = f.following_id
+              )
+              AND NOT EXISTS (
+                  SELECT 1 FROM blocks b
+                  WHERE (b.blocker_id = $1 AND b.blocked_id = f.following_id)
+                     OR (b.blocker_id = f.following_id AND b.blocked_id = $1)
+              )
+              AND NOT EXISTS (
+                  SELECT 1 FROM user_mutes m
+                  WHERE m.user_id = $1 AND m.muted_id = f.following_id
+              )
+            GROUP BY f.following_id
+            ORDER BY COUNT(*) DESC, f.following_id
+            LIMIT $2
+            "#,
+            user_id,
+            limit
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    /// Drop stored suggestions of everyone not in `user_ids`
+    pub async fn retain_precomputed(&self, user_ids: &[i32]) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM follow_suggestions
+            WHERE user_id <> ALL($1)
+            "#,
+            user_ids
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,86 @@
+use sqlx::PgPool;
+
+use crate::models::trend::Trend;
+
+#[derive(Clone)]
+pub struct TrendRepository {
+    pool: PgPool,
+}
+
+impl TrendRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Score every hashtag by how far its distinct authors in the current
+    /// window sit above the mean of the `baseline_windows` before it, in
+    /// standard deviations. Windows without the tag count as zero. Accounts
+    /// that posted more than `max_tweets_per_window` tweets in the current
+    /// window are left out altogether.
+    pub async fn trending(
+        &self,
+        window_secs: f64,
+        baseline_windows: i32,
+        min_authors: i64,
+        max_tweets_per_window: i64,
+        limit: i64,
+    ) -> Result<Vec<Trend>, sqlx::Error> {
+        sqlx::query_as!(
+            Trend,
+            r#"
+            WITH flooders AS (
+                SELECT author_id
+                FROM tweets
+                WHERE created_at > now() - make_interval(secs => $1)
+                  AND author_id IS NOT NULL
+                GROUP BY author_id
+                HAVING COUNT(*) > $4
+            ),
+            -- `age` 0 is the current window, 1 the one before it, and so on
+            windows AS (
+                SELECT
+                    h.tag,
+                    floor(extract(epoch FROM now() - h.created_at)::FLOAT8 / $1)::INTEGER AS age,
+                    COUNT(DISTINCT h.author_id) AS authors,
+                    COUNT(*) AS tweets
+                FROM tweet_hashtags h
+                WHERE h.created_at > now() - make_interval(secs => $1 * ($2 + 1))
+                  AND h.author_id IS NOT NULL
+                  AND h.author_id NOT IN (SELECT author_id FROM flooders)
+                GROUP BY h.tag, age
+            ),
+            stats AS (
+                SELECT
+                    tag,
+                    COALESCE(SUM(authors) FILTER (WHERE age = 0), 0)::BIGINT AS authors,
+                    COALESCE(SUM(tweets) FILTER (WHERE age = 0), 0)::BIGINT AS tweets,
+                    COALESCE(SUM(authors) FILTER (WHERE age > 0), 0)::FLOAT8 / $2 AS mean,
+                    COALESCE(SUM(authors * authors) FILTER (WHERE age > 0), 0)::FLOAT8 / $2
+                        AS mean_square
+                FROM windows
+                GROUP BY tag
+            )
+            SELECT
+                tag AS "hashtag!",
+                authors AS "authors!",
+                tweets AS "tweets!",
+                mean AS "baseline!",
+                -- A floor of 1 keeps brand-new tags with a couple of uses from
+                -- dividing by zero and topping the list
+                (authors - mean) / GREATEST(sqrt(GREATEST(mean_square - mean * mean, 0)), 1)
+                    AS "score!"
+            FROM stats
+            WHERE authors >= $3 AND authors > mean
+            ORDER BY 5 DESC, authors DESC, tag
+            LIMIT $5
+            "#,
+            window_secs,
+            baseline_windows,
+            min_authors,
+            max_tweets_per_window,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -1,16 +1,42 @@
-use crate::models::tweet::TweetResponse;
+use crate::models::media::{Media, MediaVariant};
+use crate::models::outbox::OutboxEvent;
+use crate::models::poll::{Poll, PollOption};
+use crate::models::search::IndexedTweet;
+use crate::models::tweet::{NewTweet, TweetResponse, UrlEntity};
+use crate::repositories::outbox_repository;
 use chrono::{DateTime, Utc};
 use sqlx::PgPool;
+use std::collections::HashMap;
 
 #[derive(Clone)]
 pub struct TweetRepository {
     pool: PgPool,
 }
 
+/// A tweet without entities; `attach_entities` fills those in
+fn bare_tweet(
+    id: i32,
+    author_id: Option<i32>,
+    content: String,
+    reply_to_id: Option<i32>,
+) -> TweetResponse {
+    TweetResponse {
+        id: id as u64,
+        author_id,
+        content,
+        reply_to_id: reply_to_id.map(|id| id as u64),
+        urls: Vec::new(),
+        media: Vec::new(),
+        poll: None,
+    }
+}
+
 /// Internal DB mapping struct (repository-only)
 struct TweetRow {
     id: i32,
+    author_id: Option<i32>,
     content: String,
+    reply_to_id: Option<i32>,
     created_at: DateTime<Utc>,
 }
 
@@ -20,30 +46,164 @@ impl TweetRepository {
         Self { pool }
     }
 
-    /// Insert a new tweet
-    pub async fn create(&self, content: String) -> Result<TweetResponse, sqlx::Error> {
+    /// Insert a new tweet together with its URL entities, attachments, poll and
+    /// outbox event
+    pub async fn create(&self, tweet: NewTweet) -> Result<TweetResponse, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // Consuming the draft here is what makes scheduled publishing exactly-once:
+        // a second publisher blocks on the row lock, then finds nothing to delete.
+        // A draft edited since it was claimed is left for the next claim instead
+        if let Some(draft) = tweet.draft {
+            let consumed = sqlx::query!(
+                r#"
+                DELETE FROM drafts
+                WHERE id = $1 AND updated_at = $2 AND scheduled_at <= now()
+                "#,
+                draft.id,
+                draft.updated_at
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            if consumed.rows_affected() != 1 {
+                return Err(sqlx::Error::RowNotFound);
+            }
+        }
+
         let record = sqlx::query!(
             r#"
-            INSERT INTO tweets (content)
-            VALUES ($1)
-            RETURNING id, content
+            INSERT INTO tweets (author_id, content, reply_to_id, conversation_id)
+            VALUES (
+                $1,
+                $2,
+                $3,
+                (SELECT COALESCE(conversation_id, id) FROM tweets WHERE id = $3)
+            )
+            RETURNING id, author_id, content, reply_to_id, created_at AS "created_at!"
             "#,
-            content
+            tweet.author_id,
+            tweet.content,
+            tweet.reply_to_id
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
 
-        Ok(TweetResponse {
-            id: record.id as u64,
-            content: record.content,
-        })
+        for url in &tweet.urls {
+            sqlx::query!(
+                r#"
+                INSERT INTO tweet_urls
+                    (tweet_id, start_offset, end_offset, url, expanded_url, display_url)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#,
+                record.id,
+                url.start,
+                url.end,
+                url.url,
+                url.expanded_url,
+                url.display_url
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO tweet_hashtags (tweet_id, tag, author_id, created_at)
+            SELECT $1, tag, $2, $3
+            FROM unnest($4::TEXT[]) AS tag
+            "#,
+            record.id,
+            tweet.author_id,
+            record.created_at,
+            &tweet.hashtags
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // Only the author's unattached uploads can be claimed; anything else aborts
+        let attached = sqlx::query!(
+            r#"
+            UPDATE media
+            SET tweet_id = $1
+            WHERE id = ANY($2) AND owner_id = $3 AND tweet_id IS NULL
+            "#,
+            record.id,
+            &tweet.media_ids,
+            tweet.author_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if attached.rows_affected() != tweet.media_ids.len() as u64 {
+            return Err(sqlx::Error::RowNotFound);
+        }
+
+        if let Some(poll) = tweet.poll {
+            let poll_id = sqlx::query_scalar!(
+                r#"
+                INSERT INTO polls (tweet_id, closes_at)
+                VALUES ($1, $2)
+                RETURNING id
+                "#,
+                record.id,
+                poll.closes_at
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+
+            for (position, label) in poll.options.iter().enumerate() {
+                sqlx::query!(
+                    r#"
+                    INSERT INTO poll_options (poll_id, position, label)
+                    VALUES ($1, $2, $3)
+                    "#,
+                    poll_id,
+                    position as i32,
+                    label
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        outbox_repository::enqueue(
+            &mut tx,
+            &OutboxEvent::TweetCreated {
+                tweet_id: record.id,
+                author_id: tweet.author_id,
+                reply_to_id: record.reply_to_id,
+                created_at: record.created_at,
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        let tweet = bare_tweet(
+            record.id,
+            record.author_id,
+            record.content,
+            record.reply_to_id,
+        );
+
+        // Attachments may already have renditions from background processing
+        Ok(self
+            .attach_entities(vec![tweet], None)
+            .await?
+            .pop()
+            .expect("one tweet in, one tweet out"))
     }
 
     /// Find a tweet by id
-    pub async fn find_by_id(&self, id: i32) -> Result<Option<TweetResponse>, sqlx::Error> {
+    pub async fn find_by_id(
+        &self,
+        id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<Option<TweetResponse>, sqlx::Error> {
         let record = sqlx::query!(
             r#"
-            SELECT id, content
+            SELECT id, author_id, content, reply_to_id
             FROM tweets
             WHERE id = $1
             "#,
@@ -52,45 +212,182 @@ impl TweetRepository {
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(record.map(|row| TweetResponse {
-            id: row.id as u64,
-            content: row.content,
-        }))
+        let Some(row) = record else {
+            return Ok(None);
+        };
+
+        let tweet = bare_tweet(row.id, row.author_id, row.content, row.reply_to_id);
+
+        Ok(self.attach_entities(vec![tweet], viewer_id).await?.pop())
+    }
+
+    /// Find a tweet by id unless its author is protected from the viewer
+    pub async fn find_visible(
+        &self,
+        id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<Option<TweetResponse>, sqlx::Error> {
+        if self.is_protected_from(id, viewer_id).await? {
+            return Ok(None);
+        }
+
+        self.find_by_id(id, viewer_id).await
+    }
+
+    /// Whether the tweet's author is protected and the viewer is not one of
+    /// their approved followers; a `None` viewer is anonymous
+    pub async fn is_protected_from(
+        &self,
+        id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT is_protected_from($2, author_id) AS "protected!"
+            FROM tweets
+            WHERE id = $1
+            "#,
+            id,
+            viewer_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map(|protected| protected.unwrap_or(false))
+    }
+
+    /// Find tweets by id, in the order given; missing ones, and protected ones
+    /// the viewer may not read, are left out
+    pub async fn find_many(
+        &self,
+        ids: &[i32],
+        viewer_id: Option<i32>,
+    ) -> Result<Vec<TweetResponse>, sqlx::Error> {
+        let records = sqlx::query!(
+            r#"
+            SELECT t.id, t.author_id, t.content, t.reply_to_id
+            FROM unnest($1::INTEGER[]) WITH ORDINALITY AS wanted (id, position)
+            JOIN tweets t ON t.id = wanted.id
+            WHERE NOT is_protected_from($2, t.author_id)
+            ORDER BY wanted.position
+            "#,
+            ids,
+            viewer_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let tweets = records
+            .into_iter()
+            .map(|row| bare_tweet(row.id, row.author_id, row.content, row.reply_to_id))
+            .collect();
+
+        self.attach_entities(tweets, viewer_id).await
+    }
+
+    /// Like `find_many`, but leaving out tweets hidden from the viewer
+    pub async fn find_many_visible(
+        &self,
+        ids: &[i32],
+        viewer_id: i32,
+    ) -> Result<Vec<TweetResponse>, sqlx::Error> {
+        let records = sqlx::query!(
+            r#"
+            SELECT t.id, t.author_id, t.content, t.reply_to_id
+            FROM unnest($1::INTEGER[]) WITH ORDINALITY AS wanted (id, position)
+            JOIN tweets t ON t.id = wanted.id
+            WHERE NOT is_hidden_from($2, t.author_id, COALESCE(t.conversation_id, t.id), t.content)
+            ORDER BY wanted.position
+            "#,
+            ids,
+            viewer_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let tweets = records
+            .into_iter()
+            .map(|row| bare_tweet(row.id, row.author_id, row.content, row.reply_to_id))
+            .collect();
+
+        self.attach_entities(tweets, Some(viewer_id)).await
     }
 
-    /// OFFSET-based timeline (kept for learning / comparison)
+    /// A tweet as a search index stores it
+    pub async fn find_indexable(&self, id: i32) -> Result<Option<IndexedTweet>, sqlx::Error> {
+        sqlx::query_as!(
+            IndexedTweet,
+            r#"
+            SELECT id, author_id, content, created_at AS "created_at!"
+            FROM tweets
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Every tweet in id order, a page at a time, for rebuilding a search index
+    pub async fn indexable_after(
+        &self,
+        after_id: i32,
+        limit: i64,
+    ) -> Result<Vec<IndexedTweet>, sqlx::Error> {
+        sqlx::query_as!(
+            IndexedTweet,
+            r#"
+            SELECT id, author_id, content, created_at AS "created_at!"
+            FROM tweets
+            WHERE id > $1
+            ORDER BY id
+            LIMIT $2
+            "#,
+            after_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// OFFSET-based timeline (kept for learning / comparison); tweets by
+    /// anyone the viewer blocked or was blocked by, tweets they muted, and
+    /// protected accounts they do not follow are left out
     pub async fn timeline(
         &self,
         limit: i64,
         offset: i64,
+        viewer_id: Option<i32>,
     ) -> Result<Vec<TweetResponse>, sqlx::Error> {
         let records = sqlx::query!(
             r#"
-            SELECT id, content
+            SELECT id, author_id, content, reply_to_id
             FROM tweets
+            WHERE NOT is_hidden_from($3, author_id, COALESCE(conversation_id, id), content)
             ORDER BY created_at DESC
             LIMIT $1 OFFSET $2
             "#,
             limit,
-            offset
+            offset,
+            viewer_id
         )
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(records
+        let tweets = records
             .into_iter()
-            .map(|row| TweetResponse {
-                id: row.id as u64,
-                content: row.content,
-            })
-            .collect())
+            .map(|row| bare_tweet(row.id, row.author_id, row.content, row.reply_to_id))
+            .collect();
+
+        self.attach_entities(tweets, viewer_id).await
     }
 
-    /// Cursor-based timeline (PRODUCTION-GRADE)
+    /// Cursor-based timeline (PRODUCTION-GRADE); blocks, mutes and protected
+    /// accounts are filtered as in `timeline`
     pub async fn timeline_before(
         &self,
         limit: i64,
         before: Option<(DateTime<Utc>, i32)>,
+        viewer_id: Option<i32>,
     ) -> Result<Vec<(TweetResponse, DateTime<Utc>)>, sqlx::Error> {
         let rows: Vec<TweetRow> = match before {
             Some((created_at, id)) => {
@@ -99,17 +396,96 @@ impl TweetRepository {
                     r#"
                     SELECT
                         id,
+                        author_id,
                         content,
+                        reply_to_id,
                         created_at AS "created_at!"
                     FROM tweets
 
                     WHERE (created_at, id) < ($1, $2)
+                      AND NOT is_hidden_from($4, author_id, COALESCE(conversation_id, id), content)
+                    ORDER BY created_at DESC, id DESC
+                    LIMIT $3
+                    "#,
+                    created_at,
+                    id,
+                    limit,
+                    viewer_id
+                )
+                .fetch_all(&self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query_as!(
+                    TweetRow,
+                    r#"
+                    SELECT
+                        id,
+                        author_id,
+                        content,
+                        reply_to_id,
+                        created_at AS "created_at!"
+                    FROM tweets
+
+                    WHERE NOT is_hidden_from($2, author_id, COALESCE(conversation_id, id), content)
+                    ORDER BY created_at DESC, id DESC
+                    LIMIT $1
+                    "#,
+                    limit,
+                    viewer_id
+                )
+                .fetch_all(&self.pool)
+                .await?
+            }
+        };
+
+        let (tweets, timestamps): (Vec<_>, Vec<_>) = rows
+            .into_iter()
+            .map(|row| {
+                (
+                    bare_tweet(row.id, row.author_id, row.content, row.reply_to_id),
+                    row.created_at,
+                )
+            })
+            .unzip();
+
+        let tweets = self.attach_entities(tweets, viewer_id).await?;
+
+        Ok(tweets.into_iter().zip(timestamps).collect())
+    }
+
+    /// `timeline_before` restricted to tweets by members of a list
+    pub async fn list_timeline_before(
+        &self,
+        list_id: i32,
+        limit: i64,
+        before: Option<(DateTime<Utc>, i32)>,
+        viewer_id: Option<i32>,
+    ) -> Result<Vec<(TweetResponse, DateTime<Utc>)>, sqlx::Error> {
+        let rows: Vec<TweetRow> = match before {
+            Some((created_at, id)) => {
+                sqlx::query_as!(
+                    TweetRow,
+                    r#"
+                    SELECT
+                        id,
+                        author_id,
+                        content,
+                        reply_to_id,
+                        created_at AS "created_at!"
+                    FROM tweets
+
+                    WHERE author_id IN (SELECT user_id FROM list_members WHERE list_id = $5)
+                      AND (created_at, id) < ($1, $2)
+                      AND NOT is_hidden_from($4, author_id, COALESCE(conversation_id, id), content)
                     ORDER BY created_at DESC, id DESC
                     LIMIT $3
                     "#,
                     created_at,
                     id,
-                    limit
+                    limit,
+                    viewer_id,
+                    list_id
                 )
                 .fetch_all(&self.pool)
                 .await?
@@ -120,31 +496,410 @@ impl TweetRepository {
                     r#"
                     SELECT
                         id,
+                        author_id,
                         content,
+                        reply_to_id,
                         created_at AS "created_at!"
                     FROM tweets
 
+                    WHERE author_id IN (SELECT user_id FROM list_members WHERE list_id = $3)
+                      AND NOT is_hidden_from($2, author_id, COALESCE(conversation_id, id), content)
                     ORDER BY created_at DESC, id DESC
                     LIMIT $1
                     "#,
-                    limit
+                    limit,
+                    viewer_id,
+                    list_id
                 )
                 .fetch_all(&self.pool)
                 .await?
             }
         };
 
-        Ok(rows
+        let (tweets, timestamps): (Vec<_>, Vec<_>) = rows
             .into_iter()
             .map(|row| {
                 (
-                    TweetResponse {
-                        id: row.id as u64,
-                        content: row.content,
-                    },
+                    bare_tweet(row.id, row.author_id, row.content, row.reply_to_id),
                     row.created_at,
                 )
             })
-            .collect())
+            .unzip();
+
+        let tweets = self.attach_entities(tweets, viewer_id).await?;
+
+        Ok(tweets.into_iter().zip(timestamps).collect())
+    }
+
+    /// Delete one of the author's tweets together with its outbox event;
+    /// false if they have no such tweet
+    pub async fn delete(&self, id: i32, author_id: i32) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let deleted = sqlx::query!(
+            r#"
+            DELETE FROM tweets
+            WHERE id = $1 AND author_id = $2
+            "#,
+            id,
+            author_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if deleted.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        outbox_repository::enqueue(
+            &mut tx,
+            &OutboxEvent::TweetDeleted {
+                tweet_id: id,
+                author_id,
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Tweets newer than the cursor, oldest first; used to replay missed
+    /// events. The stream is public, so protected accounts are left out
+    pub async fn timeline_after(
+        &self,
+        limit: i64,
+        after: (DateTime<Utc>, i32),
+    ) -> Result<Vec<(TweetResponse, DateTime<Utc>)>, sqlx::Error> {
+        let (created_at, id) = after;
+
+        let rows = sqlx::query_as!(
+            TweetRow,
+            r#"
+            SELECT
+                id,
+                author_id,
+                content,
+                reply_to_id,
+                created_at AS "created_at!"
+            FROM tweets
+
+            WHERE (created_at, id) > ($1, $2)
+              AND NOT is_protected_from(NULL, author_id)
+            ORDER BY created_at ASC, id ASC
+            LIMIT $3
+            "#,
+            created_at,
+            id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let (tweets, timestamps): (Vec<_>, Vec<_>) = rows
+            .into_iter()
+            .map(|row| {
+                (
+                    bare_tweet(row.id, row.author_id, row.content, row.reply_to_id),
+                    row.created_at,
+                )
+            })
+            .unzip();
+
+        let tweets = self.attach_entities(tweets, None).await?;
+
+        Ok(tweets.into_iter().zip(timestamps).collect())
+    }
+
+    /// The first tweet of the thread a tweet belongs to; `None` if the tweet
+    /// does not exist
+    pub async fn conversation_of(&self, id: i32) -> Result<Option<i32>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE(conversation_id, id) AS "conversation_id!"
+            FROM tweets
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Author of a tweet: `None` if the tweet does not exist, `Some(None)` if
+    /// it predates authorship
+    pub async fn author_of(&self, id: i32) -> Result<Option<Option<i32>>, sqlx::Error> {
+        let record = sqlx::query!(
+            r#"
+            SELECT author_id
+            FROM tweets
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record.map(|row| row.author_id))
+    }
+
+    /// Like and reply totals of a tweet
+    pub async fn counters(&self, tweet_id: i32) -> Result<(i64, i64), sqlx::Error> {
+        let record = sqlx::query!(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM likes WHERE tweet_id = $1) AS "likes!",
+                (SELECT COUNT(*) FROM tweets WHERE reply_to_id = $1) AS "replies!"
+            "#,
+            tweet_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok((record.likes, record.replies))
+    }
+
+    /// False if the user already liked the tweet
+    pub async fn like(&self, user_id: i32, tweet_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO likes (user_id, tweet_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            user_id,
+            tweet_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// False if there was no like to remove
+    pub async fn unlike(&self, user_id: i32, tweet_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM likes
+            WHERE user_id = $1 AND tweet_id = $2
+            "#,
+            user_id,
+            tweet_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Poll attached to a tweet, as seen by `viewer_id`
+    pub async fn find_poll(
+        &self,
+        tweet_id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<Option<Poll>, sqlx::Error> {
+        Ok(self
+            .load_polls(&[tweet_id], viewer_id)
+            .await?
+            .remove(&tweet_id))
+    }
+
+    /// Record a vote; false if the user already voted or the poll is closed
+    pub async fn record_vote(
+        &self,
+        tweet_id: i32,
+        user_id: i32,
+        position: i32,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO poll_votes (poll_id, user_id, position)
+            SELECT id, $2, $3
+            FROM polls
+            WHERE tweet_id = $1 AND closes_at > now()
+            ON CONFLICT (poll_id, user_id) DO NOTHING
+            "#,
+            tweet_id,
+            user_id,
+            position
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Polls keyed by tweet id, with tallies hidden where the viewer may not see them
+    async fn load_polls(
+        &self,
+        tweet_ids: &[i32],
+        viewer_id: Option<i32>,
+    ) -> Result<HashMap<i32, Poll>, sqlx::Error> {
+        let records = sqlx::query!(
+            r#"
+            SELECT
+                p.id,
+                p.tweet_id,
+                p.closes_at,
+                p.closes_at <= now() AS "closed!",
+                (
+                    SELECT v.position
+                    FROM poll_votes v
+                    WHERE v.poll_id = p.id AND v.user_id = $2
+                ) AS voted_option
+            FROM polls p
+            WHERE p.tweet_id = ANY($1)
+            "#,
+            tweet_ids,
+            viewer_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let poll_ids: Vec<i32> = records.iter().map(|row| row.id).collect();
+
+        let options = sqlx::query!(
+            r#"
+            SELECT o.poll_id, o.position, o.label, COUNT(v.user_id) AS "votes!"
+            FROM poll_options o
+            LEFT JOIN poll_votes v ON v.poll_id = o.poll_id AND v.position = o.position
+            WHERE o.poll_id = ANY($1)
+            GROUP BY o.poll_id, o.position, o.label
+            ORDER BY o.poll_id, o.position
+            "#,
+            &poll_ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut polls = HashMap::new();
+        for row in records {
+            let reveal = row.closed || row.voted_option.is_some();
+
+            let options: Vec<PollOption> = options
+                .iter()
+                .filter(|option| option.poll_id == row.id)
+                .map(|option| PollOption {
+                    position: option.position,
+                    label: option.label.clone(),
+                    votes: reveal.then_some(option.votes),
+                })
+                .collect();
+
+            let total_votes = reveal.then(|| options.iter().filter_map(|o| o.votes).sum());
+
+            polls.insert(
+                row.tweet_id,
+                Poll {
+                    options,
+                    total_votes,
+                    closes_at: row.closes_at,
+                    closed: row.closed,
+                    voted_option: row.voted_option,
+                },
+            );
+        }
+
+        Ok(polls)
+    }
+
+    /// Load entities for a page of tweets in one query per entity type
+    async fn attach_entities(
+        &self,
+        mut tweets: Vec<TweetResponse>,
+        viewer_id: Option<i32>,
+    ) -> Result<Vec<TweetResponse>, sqlx::Error> {
+        let ids: Vec<i32> = tweets.iter().map(|t| t.id as i32).collect();
+
+        let records = sqlx::query!(
+            r#"
+            SELECT tweet_id, start_offset, end_offset, url, expanded_url, display_url
+            FROM tweet_urls
+            WHERE tweet_id = ANY($1)
+            ORDER BY tweet_id, start_offset
+            "#,
+            &ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut urls: HashMap<i32, Vec<UrlEntity>> = HashMap::new();
+        for row in records {
+            urls.entry(row.tweet_id).or_default().push(UrlEntity {
+                start: row.start_offset,
+                end: row.end_offset,
+                url: row.url,
+                expanded_url: row.expanded_url,
+                display_url: row.display_url,
+            });
+        }
+
+        let records = sqlx::query!(
+            r#"
+            SELECT id, tweet_id AS "tweet_id!", url, content_type, width, height, alt_text
+            FROM media
+            WHERE tweet_id = ANY($1)
+            ORDER BY tweet_id, id
+            "#,
+            &ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut media: HashMap<i32, Vec<Media>> = HashMap::new();
+        for row in records {
+            media.entry(row.tweet_id).or_default().push(Media {
+                id: row.id,
+                url: row.url,
+                content_type: row.content_type,
+                width: row.width,
+                height: row.height,
+                alt_text: row.alt_text,
+                variants: Vec::new(),
+            });
+        }
+
+        let media_ids: Vec<i32> = media.values().flatten().map(|m| m.id).collect();
+
+        let records = sqlx::query!(
+            r#"
+            SELECT media_id, name, url, width, height
+            FROM media_variants
+            WHERE media_id = ANY($1)
+            ORDER BY media_id, width
+            "#,
+            &media_ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut variants: HashMap<i32, Vec<MediaVariant>> = HashMap::new();
+        for row in records {
+            variants
+                .entry(row.media_id)
+                .or_default()
+                .push(MediaVariant {
+                    name: row.name,
+                    url: row.url,
+                    width: row.width,
+                    height: row.height,
+                });
+        }
+
+        for item in media.values_mut().flatten() {
+            item.variants = variants.remove(&item.id).unwrap_or_default();
+        }
+
+        let mut polls = self.load_polls(&ids, viewer_id).await?;
+
+        for tweet in &mut tweets {
+            let id = tweet.id as i32;
+            tweet.urls = urls.remove(&id).unwrap_or_default();
+            tweet.media = media.remove(&id).unwrap_or_default();
+            tweet.poll = polls.remove(&id);
+        }
+
+        Ok(tweets)
     }
 }
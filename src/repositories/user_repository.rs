@@ -1,6 +1,7 @@
 use sqlx::PgPool;
 
-use crate::models::user::User;
+use crate::models::user::{ProfileChanges, User, UserSettings, UserSummary};
+use crate::repositories::follow_repository;
 
 #[derive(Clone)]
 pub struct UserRepository {
@@ -14,38 +15,315 @@ impl UserRepository {
 
     // CREATE USER (must be async)
     pub async fn create(&self, username: String) -> Result<User, sqlx::Error> {
-        let record = sqlx::query!(
+        let id = sqlx::query_scalar!(
             r#"
             INSERT INTO users (username)
             VALUES ($1)
-            RETURNING id, username
+            RETURNING id
             "#,
             username
         )
         .fetch_one(&self.pool)
         .await?;
 
-        Ok(User {
-            id: record.id,
-            username: record.username,
-        })
+        self.find_by_id(id).await?.ok_or(sqlx::Error::RowNotFound)
     }
 
     pub async fn find_by_id(&self, id: i32) -> Result<Option<User>, sqlx::Error> {
-        let record = sqlx::query!(
+        sqlx::query_as!(
+            User,
             r#"
-            SELECT id, username
+            SELECT
+                u.id AS "id!",
+                u.username AS "username!",
+                u.display_name,
+                u.bio,
+                u.location,
+                u.website,
+                u.avatar_url,
+                u.banner_url,
+                u.created_at AS "created_at!"
+            FROM user_profiles u
+            WHERE u.id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Case-insensitive, like @mentions
+    pub async fn find_by_username(&self, username: &str) -> Result<Option<User>, sqlx::Error> {
+        sqlx::query_as!(
+            User,
+            r#"
+            SELECT
+                u.id AS "id!",
+                u.username AS "username!",
+                u.display_name,
+                u.bio,
+                u.location,
+                u.website,
+                u.avatar_url,
+                u.banner_url,
+                u.created_at AS "created_at!"
+            FROM user_profiles u
+            WHERE lower(u.username) = lower($1)
+            "#,
+            username
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn find_by_ids(&self, ids: &[i32]) -> Result<Vec<User>, sqlx::Error> {
+        sqlx::query_as!(
+            User,
+            r#"
+            SELECT
+                u.id AS "id!",
+                u.username AS "username!",
+                u.display_name,
+                u.bio,
+                u.location,
+                u.website,
+                u.avatar_url,
+                u.banner_url,
+                u.created_at AS "created_at!"
+            FROM user_profiles u
+            WHERE u.id = ANY($1)
+            "#,
+            ids
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Case-insensitive lookup, as used for @mentions
+    pub async fn find_by_usernames(&self, usernames: &[String]) -> Result<Vec<User>, sqlx::Error> {
+        let lowered: Vec<String> = usernames.iter().map(|name| name.to_lowercase()).collect();
+
+        sqlx::query_as!(
+            User,
+            r#"
+            SELECT
+                u.id AS "id!",
+                u.username AS "username!",
+                u.display_name,
+                u.bio,
+                u.location,
+                u.website,
+                u.avatar_url,
+                u.banner_url,
+                u.created_at AS "created_at!"
+            FROM user_profiles u
+            WHERE lower(u.username) = ANY($1)
+            "#,
+            &lowered
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Apply the given profile changes; `None` leaves a field as it is, while
+    /// an empty string or media id 0 clears it. `false` if there is no such
+    /// user.
+    pub async fn update_profile(
+        &self,
+        id: i32,
+        changes: &ProfileChanges,
+    ) -> Result<bool, sqlx::Error> {
+        let updated = sqlx::query!(
+            r#"
+            UPDATE users
+            SET
+                display_name = NULLIF(COALESCE($2, display_name), ''),
+                bio = NULLIF(COALESCE($3, bio), ''),
+                location = NULLIF(COALESCE($4, location), ''),
+                website = NULLIF(COALESCE($5, website), ''),
+                avatar_media_id = NULLIF(COALESCE($6, avatar_media_id), 0),
+                banner_media_id = NULLIF(COALESCE($7, banner_media_id), 0)
+            WHERE id = $1
+            "#,
+            id,
+            changes.display_name,
+            changes.bio,
+            changes.location,
+            changes.website,
+            changes.avatar_media_id,
+            changes.banner_media_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(updated.rows_affected() == 1)
+    }
+
+    /// Prefix or trigram matches on username and display name: exact matches
+    /// first, then by follower count, then users the viewer follows
+    pub async fn search(
+        &self,
+        query: &str,
+        viewer_id: Option<i32>,
+        limit: i64,
+    ) -> Result<Vec<UserSummary>, sqlx::Error> {
+        let query = query.to_lowercase();
+
+        sqlx::query_as!(
+            UserSummary,
+            r#"
+            SELECT
+                id AS "id!",
+                username AS "username!",
+                display_name,
+                followers_count AS "followers_count!",
+                followed_by_me AS "followed_by_me!"
+            FROM (
+                SELECT
+                    u.id,
+                    u.username,
+                    u.display_name,
+                    lower(u.username) = $1 OR COALESCE(lower(u.display_name) = $1, FALSE) AS exact,
+                    (SELECT COUNT(*) FROM follows f WHERE f.following_id = u.id) AS followers_count,
+                    EXISTS (
+                        SELECT 1 FROM follows f WHERE f.follower_id = $2 AND f.following_id = u.id
+                    ) AS followed_by_me,
+                    GREATEST(
+                        similarity(lower(u.username), $1),
+                        word_similarity($1, COALESCE(lower(u.display_name), ''))
+                    ) AS score
+                FROM users u
+                WHERE lower(u.username) LIKE $3 ESCAPE '\'
+                   OR lower(u.username) % $1
+                   OR $1 <% lower(u.display_name)
+            ) matches
+            ORDER BY exact DESC, followers_count DESC, followed_by_me DESC, score DESC, id
+            LIMIT $4
+            "#,
+            query,
+            viewer_id,
+            prefix_pattern(&query),
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Username or display name prefix matches for @mention autocompletion.
+    /// Only the first candidates in index order are ranked, which keeps
+    /// one-letter prefixes cheap.
+    pub async fn typeahead(
+        &self,
+        prefix: &str,
+        viewer_id: Option<i32>,
+        limit: i64,
+    ) -> Result<Vec<UserSummary>, sqlx::Error> {
+        let prefix = prefix.to_lowercase();
+
+        sqlx::query_as!(
+            UserSummary,
+            r#"
+            SELECT
+                u.id,
+                u.username,
+                u.display_name,
+                (SELECT COUNT(*) FROM follows f WHERE f.following_id = u.id) AS "followers_count!",
+                EXISTS (
+                    SELECT 1 FROM follows f WHERE f.follower_id = $2 AND f.following_id = u.id
+                ) AS "followed_by_me!"
+            FROM (
+                (
+                    SELECT id FROM users
+                    WHERE lower(username) LIKE $3 ESCAPE '\'
+                    ORDER BY lower(username)
+                    LIMIT 200
+                )
+                UNION
+                (
+                    SELECT id FROM users
+                    WHERE lower(display_name) LIKE $3 ESCAPE '\'
+                    ORDER BY lower(display_name)
+                    LIMIT 200
+                )
+            ) candidates
+            JOIN users u ON u.id = candidates.id
+            ORDER BY
+                lower(u.username) = $1 DESC,
+                "followed_by_me!" DESC,
+                "followers_count!" DESC,
+                length(u.username),
+                u.id
+            LIMIT $4
+            "#,
+            prefix,
+            viewer_id,
+            prefix_pattern(&prefix),
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn settings(&self, id: i32) -> Result<Option<UserSettings>, sqlx::Error> {
+        sqlx::query_as!(
+            UserSettings,
+            r#"
+            SELECT protected, dms_from_following_only
             FROM users
             WHERE id = $1
             "#,
             id
         )
         .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Apply the given changes; an account that stops being protected has its
+    /// pending follow requests approved in the same transaction
+    pub async fn update_settings(
+        &self,
+        id: i32,
+        protected: Option<bool>,
+        dms_from_following_only: Option<bool>,
+    ) -> Result<Option<UserSettings>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let settings = sqlx::query_as!(
+            UserSettings,
+            r#"
+            UPDATE users
+            SET
+                protected = COALESCE($2, protected),
+                dms_from_following_only = COALESCE($3, dms_from_following_only)
+            WHERE id = $1
+            RETURNING protected, dms_from_following_only
+            "#,
+            id,
+            protected,
+            dms_from_following_only
+        )
+        .fetch_optional(&mut *tx)
         .await?;
 
-        Ok(record.map(|row| User {
-            id: row.id,
-            username: row.username,
-        }))
+        if settings
+            .as_ref()
+            .is_some_and(|settings| !settings.protected)
+        {
+            follow_repository::approve_all_requests(&mut tx, id).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(settings)
     }
 }
+
+/// A LIKE pattern matching values that start with `value`
+fn prefix_pattern(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+
+    format!("{escaped}%")
+}
@@ -0,0 +1,256 @@
+use sqlx::PgPool;
+use sqlx::types::Json;
+
+use crate::models::webhook::{DeliveryAttempt, DeliveryTarget, Webhook, WebhookDelivery};
+
+#[derive(Clone)]
+pub struct WebhookRepository {
+    pool: PgPool,
+}
+
+impl WebhookRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        owner_id: i32,
+        url: &str,
+        secret: &str,
+        events: &[String],
+    ) -> Result<Webhook, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"
+            INSERT INTO webhooks (owner_id, url, secret, events)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, url, events, active, created_at, updated_at
+            "#,
+            owner_id,
+            url,
+            secret,
+            events
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn list(&self, owner_id: i32) -> Result<Vec<Webhook>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"
+            SELECT id, url, events, active, created_at, updated_at
+            FROM webhooks
+            WHERE owner_id = $1
+            ORDER BY id
+            "#,
+            owner_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn find(&self, id: i32, owner_id: i32) -> Result<Option<Webhook>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"
+            SELECT id, url, events, active, created_at, updated_at
+            FROM webhooks
+            WHERE id = $1 AND owner_id = $2
+            "#,
+            id,
+            owner_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn update(
+        &self,
+        id: i32,
+        owner_id: i32,
+        url: &str,
+        events: &[String],
+        active: bool,
+    ) -> Result<Option<Webhook>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"
+            UPDATE webhooks
+            SET url = $3, events = $4, active = $5, updated_at = now()
+            WHERE id = $1 AND owner_id = $2
+            RETURNING id, url, events, active, created_at, updated_at
+            "#,
+            id,
+            owner_id,
+            url,
+            events,
+            active
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn delete(&self, id: i32, owner_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM webhooks
+            WHERE id = $1 AND owner_id = $2
+            "#,
+            id,
+            owner_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Ids of the owner's active webhooks subscribed to an event type
+    pub async fn subscribed(
+        &self,
+        owner_id: i32,
+        event_type: &str,
+    ) -> Result<Vec<i32>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT id
+            FROM webhooks
+            WHERE owner_id = $1 AND active AND $2 = ANY(events)
+            "#,
+            owner_id,
+            event_type
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Record an event for a webhook; `None` if it was already recorded
+    pub async fn create_delivery(
+        &self,
+        webhook_id: i32,
+        event_type: &str,
+        event_key: &str,
+        payload: &serde_json::Value,
+    ) -> Result<Option<i64>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            INSERT INTO webhook_deliveries (webhook_id, event_type, event_key, payload)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (webhook_id, event_key) DO NOTHING
+            RETURNING id
+            "#,
+            webhook_id,
+            event_type,
+            event_key,
+            Json(payload) as _
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn delivery_target(
+        &self,
+        delivery_id: i64,
+    ) -> Result<Option<DeliveryTarget>, sqlx::Error> {
+        sqlx::query_as!(
+            DeliveryTarget,
+            r#"
+            SELECT
+                d.id AS delivery_id,
+                d.event_type,
+                d.payload,
+                d.status,
+                d.attempts,
+                d.created_at,
+                w.url,
+                w.secret,
+                w.active
+            FROM webhook_deliveries d
+            JOIN webhooks w ON w.id = d.webhook_id
+            WHERE d.id = $1
+            "#,
+            delivery_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn record_attempt(
+        &self,
+        delivery_id: i64,
+        attempt: &DeliveryAttempt,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = $2,
+                attempts = attempts + 1,
+                response_status = $3,
+                response_body = $4,
+                error = $5,
+                last_attempt_at = now(),
+                delivered_at = CASE WHEN $2 = 'succeeded' THEN now() END
+            WHERE id = $1
+            "#,
+            delivery_id,
+            attempt.status,
+            attempt.response_status,
+            attempt.response_body,
+            attempt.error
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Newest first
+    pub async fn list_deliveries(
+        &self,
+        webhook_id: i32,
+        before: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"
+            SELECT
+                id, webhook_id, event_type, payload, status, attempts,
+                response_status, response_body, error,
+                created_at, last_attempt_at, delivered_at
+            FROM webhook_deliveries
+            WHERE webhook_id = $1 AND ($2::BIGINT IS NULL OR id < $2)
+            ORDER BY id DESC
+            LIMIT $3
+            "#,
+            webhook_id,
+            before,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Put a delivery back in line with a fresh set of attempts
+    pub async fn reset_delivery(
+        &self,
+        delivery_id: i64,
+        webhook_id: i32,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = 'pending', attempts = 0, delivered_at = NULL
+            WHERE id = $1 AND webhook_id = $2
+            "#,
+            delivery_id,
+            webhook_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
@@ -0,0 +1,59 @@
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Json},
+    http::{StatusCode, request::Parts},
+    response::{IntoResponse, Response},
+};
+
+use serde::Serialize;
+
+use crate::{app::AppState, models::user::User, services::user_service::UserServiceError};
+
+/// Header carrying the id of the user making the request
+const USER_ID_HEADER: &str = "x-user-id";
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// The user making the request, resolved from the `X-User-Id` header
+pub struct CurrentUser(pub User);
+
+#[async_trait]
+impl FromRequestParts<AppState> for CurrentUser {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Response> {
+        let unauthorized = || {
+            (
+                StatusCode::UNAUTHORIZED,
+                Json(ErrorResponse {
+                    error: "Authentication required".into(),
+                }),
+            )
+                .into_response()
+        };
+
+        let id = parts
+            .headers
+            .get(USER_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<i32>().ok())
+            .ok_or_else(unauthorized)?;
+
+        match state.user_service.get_user(id).await {
+            Ok(user) => Ok(CurrentUser(user)),
+
+            Err(UserServiceError::NotFound) => Err(unauthorized()),
+
+            Err(_) => Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Internal server error".into(),
+                }),
+            )
+                .into_response()),
+        }
+    }
+}
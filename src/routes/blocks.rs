@@ -0,0 +1,82 @@
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use serde::Serialize;
+
+use crate::{app::AppState, routes::auth::CurrentUser, services::block_service::BlockServiceError};
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+pub async fn block_user(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(user_id): Path<i32>,
+) -> Response {
+    match state.block_service.block(user.id, user_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+
+        Err(BlockServiceError::CannotBlockSelf) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Cannot block yourself".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(BlockServiceError::UserNotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "User not found".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(BlockServiceError::AlreadyBlocked) => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "Already blocking this user".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn unblock_user(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(user_id): Path<i32>,
+) -> Response {
+    match state.block_service.unblock(user.id, user_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+
+        Err(BlockServiceError::NotBlocked) => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "You are not blocking this user".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,172 @@
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::AppState,
+    models::direct_message::{CreateConversationRequest, MarkReadRequest, SendMessageRequest},
+    routes::{
+        auth::CurrentUser,
+        tweets::{CursorTimelineParams, parse_cursor},
+    },
+    services::direct_message_service::DirectMessageServiceError,
+};
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+#[derive(Deserialize)]
+pub struct ConversationListParams {
+    limit: Option<i64>,
+}
+
+fn direct_message_error_response(error: DirectMessageServiceError) -> Response {
+    let (status, message) = match error {
+        DirectMessageServiceError::NoParticipants => (
+            StatusCode::BAD_REQUEST,
+            "A conversation needs someone else in it",
+        ),
+        DirectMessageServiceError::TooManyParticipants => (
+            StatusCode::BAD_REQUEST,
+            "A conversation can have at most 50 participants",
+        ),
+        DirectMessageServiceError::UserNotFound => (StatusCode::NOT_FOUND, "User not found"),
+        DirectMessageServiceError::Blocked => {
+            (StatusCode::FORBIDDEN, "You cannot message this user")
+        }
+        DirectMessageServiceError::NotAccepting => (
+            StatusCode::FORBIDDEN,
+            "This user only accepts messages from people they follow",
+        ),
+        DirectMessageServiceError::NotFound => (StatusCode::NOT_FOUND, "Conversation not found"),
+        DirectMessageServiceError::EmptyMessage => {
+            (StatusCode::BAD_REQUEST, "Message cannot be empty")
+        }
+        DirectMessageServiceError::MessageTooLong => {
+            (StatusCode::BAD_REQUEST, "Message exceeds 1000 characters")
+        }
+        DirectMessageServiceError::MessageNotFound => (StatusCode::NOT_FOUND, "Message not found"),
+        DirectMessageServiceError::DatabaseError => {
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+        }
+    };
+
+    (
+        status,
+        Json(ErrorResponse {
+            error: message.into(),
+        }),
+    )
+        .into_response()
+}
+
+pub async fn create_conversation(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Json(payload): Json<CreateConversationRequest>,
+) -> Response {
+    match state
+        .direct_message_service
+        .create_conversation(user.id, payload)
+        .await
+    {
+        Ok((conversation, true)) => (StatusCode::CREATED, Json(conversation)).into_response(),
+        Ok((conversation, false)) => (StatusCode::OK, Json(conversation)).into_response(),
+        Err(e) => direct_message_error_response(e),
+    }
+}
+
+pub async fn list_conversations(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Query(params): Query<ConversationListParams>,
+) -> Response {
+    let limit = params.limit.unwrap_or(20).clamp(1, 50);
+
+    match state
+        .direct_message_service
+        .conversations(user.id, limit)
+        .await
+    {
+        Ok(conversations) => (StatusCode::OK, Json(conversations)).into_response(),
+        Err(e) => direct_message_error_response(e),
+    }
+}
+
+pub async fn get_conversation(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+) -> Response {
+    match state.direct_message_service.conversation(id, user.id).await {
+        Ok(conversation) => (StatusCode::OK, Json(conversation)).into_response(),
+        Err(e) => direct_message_error_response(e),
+    }
+}
+
+pub async fn send_message(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+    Json(payload): Json<SendMessageRequest>,
+) -> Response {
+    match state
+        .direct_message_service
+        .send_message(id, user.id, payload)
+        .await
+    {
+        Ok(message) => (StatusCode::CREATED, Json(message)).into_response(),
+        Err(e) => direct_message_error_response(e),
+    }
+}
+
+pub async fn list_messages(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+    Query(params): Query<CursorTimelineParams>,
+) -> Response {
+    let limit = params.limit.unwrap_or(20).clamp(1, 50);
+    let before = params.before.as_deref().and_then(parse_cursor);
+
+    match state
+        .direct_message_service
+        .messages(id, user.id, limit, before)
+        .await
+    {
+        Ok((items, next_cursor)) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "items": items,
+                "next_cursor": next_cursor
+            })),
+        )
+            .into_response(),
+        Err(e) => direct_message_error_response(e),
+    }
+}
+
+/// The body is optional; without one everything is marked read
+pub async fn mark_conversation_read(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+    payload: Option<Json<MarkReadRequest>>,
+) -> Response {
+    let request = payload.map(|Json(request)| request).unwrap_or_default();
+
+    match state
+        .direct_message_service
+        .mark_read(id, user.id, request)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => direct_message_error_response(e),
+    }
+}
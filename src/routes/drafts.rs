@@ -0,0 +1,121 @@
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use serde::Serialize;
+
+use crate::{
+    app::AppState,
+    models::tweet::CreateTweetRequest,
+    routes::auth::CurrentUser,
+    services::{draft_service::DraftServiceError, tweet_service::TweetServiceError},
+};
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// Shared by every handler here and by scheduled `POST /tweets`
+pub fn draft_error_response(error: DraftServiceError) -> Response {
+    let (status, message) = match error {
+        DraftServiceError::DraftTooLong => (StatusCode::BAD_REQUEST, "Draft is too long"),
+        DraftServiceError::ScheduleInPast => (
+            StatusCode::BAD_REQUEST,
+            "Scheduled time must be in the future",
+        ),
+        DraftServiceError::ScheduleTooFar => (
+            StatusCode::BAD_REQUEST,
+            "Tweets can be scheduled at most 540 days ahead",
+        ),
+        DraftServiceError::InvalidTweet(e) => (StatusCode::BAD_REQUEST, invalid_tweet_message(e)),
+        DraftServiceError::NotFound => (StatusCode::NOT_FOUND, "Draft not found"),
+        DraftServiceError::DatabaseError => {
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+        }
+    };
+
+    (
+        status,
+        Json(ErrorResponse {
+            error: message.into(),
+        }),
+    )
+        .into_response()
+}
+
+fn invalid_tweet_message(error: TweetServiceError) -> &'static str {
+    match error {
+        TweetServiceError::EmptyContent => "Tweet content cannot be empty",
+        TweetServiceError::ContentTooLong => "Tweet exceeds 280 characters",
+        TweetServiceError::InvalidCharacters => "Tweet contains control characters",
+        TweetServiceError::TooManyMedia => "A tweet can have at most 4 attachments",
+        TweetServiceError::InvalidMedia => "Unknown or already attached media",
+        TweetServiceError::InvalidPollOptions => {
+            "A poll needs 2 to 4 distinct options of at most 25 characters"
+        }
+        TweetServiceError::InvalidPollDuration => {
+            "Poll duration must be between 5 minutes and 7 days"
+        }
+        TweetServiceError::ReplyTargetNotFound => "Replied-to tweet does not exist",
+        TweetServiceError::Blocked => "You cannot reply to this user",
+        _ => "Scheduled tweet is not valid",
+    }
+}
+
+pub async fn create_draft(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Json(payload): Json<CreateTweetRequest>,
+) -> Response {
+    match state.draft_service.create_draft(user.id, payload).await {
+        Ok(draft) => (StatusCode::CREATED, Json(draft)).into_response(),
+        Err(e) => draft_error_response(e),
+    }
+}
+
+pub async fn list_drafts(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> Response {
+    match state.draft_service.list_drafts(user.id).await {
+        Ok(drafts) => (StatusCode::OK, Json(drafts)).into_response(),
+        Err(e) => draft_error_response(e),
+    }
+}
+
+pub async fn get_draft(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+) -> Response {
+    match state.draft_service.get_draft(id, user.id).await {
+        Ok(draft) => (StatusCode::OK, Json(draft)).into_response(),
+        Err(e) => draft_error_response(e),
+    }
+}
+
+pub async fn update_draft(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+    Json(payload): Json<CreateTweetRequest>,
+) -> Response {
+    match state.draft_service.update_draft(id, user.id, payload).await {
+        Ok(draft) => (StatusCode::OK, Json(draft)).into_response(),
+        Err(e) => draft_error_response(e),
+    }
+}
+
+pub async fn delete_draft(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+) -> Response {
+    match state.draft_service.delete_draft(id, user.id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => draft_error_response(e),
+    }
+}
@@ -0,0 +1,83 @@
+use axum::{
+    extract::{Json, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{app::AppState, routes::auth::CurrentUser, services::feed_service::FeedServiceError};
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+#[derive(Deserialize)]
+pub struct ForYouParams {
+    limit: Option<usize>,
+    cursor: Option<String>,
+}
+
+/// Cursor format: "<session id>|<offset>"
+fn parse_feed_cursor(cursor: &str) -> Option<(String, usize)> {
+    let (session_id, offset) = cursor.split_once('|')?;
+    Some((session_id.to_string(), offset.parse().ok()?))
+}
+
+pub async fn for_you_timeline(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Query(params): Query<ForYouParams>,
+) -> Response {
+    let limit = params.limit.unwrap_or(20).clamp(1, 50);
+
+    let cursor = match params.cursor.as_deref().map(parse_feed_cursor) {
+        None => None,
+        Some(Some(cursor)) => Some(cursor),
+        Some(None) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "Invalid cursor".into(),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    match state.feed_service.for_you(user.id, cursor, limit).await {
+        Ok((items, next_cursor)) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "items": items,
+                "next_cursor": next_cursor
+            })),
+        )
+            .into_response(),
+
+        Err(FeedServiceError::SessionExpired) => (
+            StatusCode::GONE,
+            Json(ErrorResponse {
+                error: "This feed has expired; load it again from the top".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(FeedServiceError::InvalidCursor) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Invalid cursor".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(FeedServiceError::DatabaseError) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
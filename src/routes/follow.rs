@@ -1,12 +1,15 @@
 use axum::{
-    extract::{Json, State},
+    extract::{Json, Path, State},
     http::StatusCode,
     response::{IntoResponse, Response},
 };
 
 use serde::{Deserialize, Serialize};
 
-use crate::{app::AppState, services::follow_service::FollowServiceError};
+use crate::{
+    app::AppState, models::follow::FollowStatus, routes::auth::CurrentUser,
+    services::follow_service::FollowServiceError,
+};
 
 #[derive(Deserialize)]
 pub struct FollowRequest {
@@ -25,7 +28,13 @@ pub async fn follow(State(state): State<AppState>, Json(payload): Json<FollowReq
         .follow(payload.follower_id, payload.following_id)
         .await
     {
-        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Ok(FollowStatus::Following) => StatusCode::NO_CONTENT.into_response(),
+
+        Ok(FollowStatus::Pending) => (
+            StatusCode::ACCEPTED,
+            Json(serde_json::json!({ "status": FollowStatus::Pending })),
+        )
+            .into_response(),
 
         Err(FollowServiceError::CannotFollowSelf) => (
             StatusCode::BAD_REQUEST,
@@ -35,6 +44,22 @@ pub async fn follow(State(state): State<AppState>, Json(payload): Json<FollowReq
         )
             .into_response(),
 
+        Err(FollowServiceError::Blocked) => (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: "You cannot follow this user".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(FollowServiceError::UserNotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "User not found".into(),
+            }),
+        )
+            .into_response(),
+
         Err(FollowServiceError::AlreadyFollowing) => (
             StatusCode::CONFLICT,
             Json(ErrorResponse {
@@ -43,6 +68,14 @@ pub async fn follow(State(state): State<AppState>, Json(payload): Json<FollowReq
         )
             .into_response(),
 
+        Err(FollowServiceError::AlreadyRequested) => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "Follow request already pending".into(),
+            }),
+        )
+            .into_response(),
+
         Err(_) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
@@ -81,3 +114,80 @@ pub async fn unfollow(
             .into_response(),
     }
 }
+
+pub async fn list_follow_requests(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> Response {
+    match state.follow_service.pending_followers(user.id).await {
+        Ok(requests) => (StatusCode::OK, Json(requests)).into_response(),
+
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn approve_follow_request(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(follower_id): Path<i32>,
+) -> Response {
+    match state
+        .follow_service
+        .approve_request(user.id, follower_id)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+
+        Err(FollowServiceError::RequestNotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Follow request not found".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn deny_follow_request(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(follower_id): Path<i32>,
+) -> Response {
+    match state
+        .follow_service
+        .deny_request(user.id, follower_id)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+
+        Err(FollowServiceError::RequestNotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Follow request not found".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
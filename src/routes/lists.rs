@@ -0,0 +1,227 @@
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use serde::Serialize;
+
+use crate::{
+    app::AppState,
+    models::list::ListRequest,
+    routes::{
+        auth::CurrentUser,
+        tweets::{CursorTimelineParams, parse_cursor},
+    },
+    services::list_service::ListServiceError,
+};
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+fn list_error_response(error: ListServiceError) -> Response {
+    let (status, message) = match error {
+        ListServiceError::EmptyName => (StatusCode::BAD_REQUEST, "List name cannot be empty"),
+        ListServiceError::NameTooLong => {
+            (StatusCode::BAD_REQUEST, "List name exceeds 25 characters")
+        }
+        ListServiceError::DescriptionTooLong => (
+            StatusCode::BAD_REQUEST,
+            "List description exceeds 100 characters",
+        ),
+        ListServiceError::TooManyLists => (StatusCode::CONFLICT, "At most 1000 lists can be made"),
+        ListServiceError::NotFound => (StatusCode::NOT_FOUND, "List not found"),
+        ListServiceError::NotOwner => (StatusCode::FORBIDDEN, "Only the owner can change a list"),
+        ListServiceError::UserNotFound => (StatusCode::NOT_FOUND, "User not found"),
+        ListServiceError::Blocked => (StatusCode::FORBIDDEN, "You cannot add this user"),
+        ListServiceError::AlreadyMember => (StatusCode::CONFLICT, "Already a member of this list"),
+        ListServiceError::NotMember => (StatusCode::NOT_FOUND, "Not a member of this list"),
+        ListServiceError::TooManyMembers => {
+            (StatusCode::CONFLICT, "A list can have at most 5000 members")
+        }
+        ListServiceError::CannotSubscribeOwnList => {
+            (StatusCode::BAD_REQUEST, "Cannot subscribe to your own list")
+        }
+        ListServiceError::AlreadySubscribed => {
+            (StatusCode::CONFLICT, "Already subscribed to this list")
+        }
+        ListServiceError::NotSubscribed => (StatusCode::NOT_FOUND, "Not subscribed to this list"),
+        ListServiceError::DatabaseError => {
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+        }
+    };
+
+    (
+        status,
+        Json(ErrorResponse {
+            error: message.into(),
+        }),
+    )
+        .into_response()
+}
+
+pub async fn create_list(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Json(payload): Json<ListRequest>,
+) -> Response {
+    match state.list_service.create_list(user.id, payload).await {
+        Ok(list) => (StatusCode::CREATED, Json(list)).into_response(),
+        Err(e) => list_error_response(e),
+    }
+}
+
+/// The caller's own lists and those they subscribed to
+pub async fn my_lists(State(state): State<AppState>, CurrentUser(user): CurrentUser) -> Response {
+    let owned = match state.list_service.lists_of(user.id, Some(user.id)).await {
+        Ok(lists) => lists,
+        Err(e) => return list_error_response(e),
+    };
+
+    match state.list_service.subscribed_lists(user.id).await {
+        Ok(subscribed) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "owned": owned,
+                "subscribed": subscribed
+            })),
+        )
+            .into_response(),
+        Err(e) => list_error_response(e),
+    }
+}
+
+pub async fn user_lists(
+    State(state): State<AppState>,
+    viewer: Option<CurrentUser>,
+    Path(user_id): Path<i32>,
+) -> Response {
+    let viewer_id = viewer.map(|CurrentUser(user)| user.id);
+
+    match state.list_service.lists_of(user_id, viewer_id).await {
+        Ok(lists) => (StatusCode::OK, Json(lists)).into_response(),
+        Err(e) => list_error_response(e),
+    }
+}
+
+pub async fn get_list(
+    State(state): State<AppState>,
+    viewer: Option<CurrentUser>,
+    Path(id): Path<i32>,
+) -> Response {
+    let viewer_id = viewer.map(|CurrentUser(user)| user.id);
+
+    match state.list_service.get_list(id, viewer_id).await {
+        Ok(list) => (StatusCode::OK, Json(list)).into_response(),
+        Err(e) => list_error_response(e),
+    }
+}
+
+pub async fn update_list(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+    Json(payload): Json<ListRequest>,
+) -> Response {
+    match state.list_service.update_list(id, user.id, payload).await {
+        Ok(list) => (StatusCode::OK, Json(list)).into_response(),
+        Err(e) => list_error_response(e),
+    }
+}
+
+pub async fn delete_list(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+) -> Response {
+    match state.list_service.delete_list(id, user.id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => list_error_response(e),
+    }
+}
+
+pub async fn list_members(
+    State(state): State<AppState>,
+    viewer: Option<CurrentUser>,
+    Path(id): Path<i32>,
+) -> Response {
+    let viewer_id = viewer.map(|CurrentUser(user)| user.id);
+
+    match state.list_service.members(id, viewer_id).await {
+        Ok(members) => (StatusCode::OK, Json(members)).into_response(),
+        Err(e) => list_error_response(e),
+    }
+}
+
+pub async fn add_list_member(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path((id, user_id)): Path<(i32, i32)>,
+) -> Response {
+    match state.list_service.add_member(id, user.id, user_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => list_error_response(e),
+    }
+}
+
+pub async fn remove_list_member(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path((id, user_id)): Path<(i32, i32)>,
+) -> Response {
+    match state.list_service.remove_member(id, user.id, user_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => list_error_response(e),
+    }
+}
+
+pub async fn subscribe_list(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+) -> Response {
+    match state.list_service.subscribe(id, user.id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => list_error_response(e),
+    }
+}
+
+pub async fn unsubscribe_list(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+) -> Response {
+    match state.list_service.unsubscribe(id, user.id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => list_error_response(e),
+    }
+}
+
+pub async fn list_timeline(
+    State(state): State<AppState>,
+    viewer: Option<CurrentUser>,
+    Path(id): Path<i32>,
+    Query(params): Query<CursorTimelineParams>,
+) -> Response {
+    let viewer_id = viewer.map(|CurrentUser(user)| user.id);
+    let limit = params.limit.unwrap_or(20).clamp(1, 50);
+    let before = params.before.as_deref().and_then(parse_cursor);
+
+    match state
+        .list_service
+        .timeline(id, limit, before, viewer_id)
+        .await
+    {
+        Ok((items, next_cursor)) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "items": items,
+                "next_cursor": next_cursor
+            })),
+        )
+            .into_response(),
+        Err(e) => list_error_response(e),
+    }
+}
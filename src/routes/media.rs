@@ -0,0 +1,126 @@
+use axum::{
+    extract::{Json, Multipart, Path, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+
+use serde::Serialize;
+
+use crate::{app::AppState, routes::auth::CurrentUser, services::media_service::MediaServiceError};
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+fn bad_request(error: &str) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse {
+            error: error.into(),
+        }),
+    )
+        .into_response()
+}
+
+/// Multipart form: a `file` part and an optional `alt_text` part
+pub async fn upload_media(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    mut multipart: Multipart,
+) -> Response {
+    let mut file = None;
+    let mut alt_text = None;
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(_) => return bad_request("Malformed multipart body"),
+        };
+
+        match field.name() {
+            Some("file") => match field.bytes().await {
+                Ok(bytes) => file = Some(bytes),
+                Err(_) => return bad_request("Malformed multipart body"),
+            },
+            Some("alt_text") => match field.text().await {
+                Ok(text) => alt_text = Some(text),
+                Err(_) => return bad_request("Malformed multipart body"),
+            },
+            _ => {}
+        }
+    }
+
+    let Some(file) = file else {
+        return bad_request("Missing file part");
+    };
+
+    match state.media_service.upload(user.id, &file, alt_text).await {
+        Ok(media) => (StatusCode::CREATED, Json(media)).into_response(),
+
+        Err(MediaServiceError::EmptyFile) => bad_request("File is empty"),
+
+        Err(MediaServiceError::FileTooLarge) => (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Json(ErrorResponse {
+                error: "File is too large".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(MediaServiceError::UnsupportedType) => (
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Json(ErrorResponse {
+                error: "Only PNG, JPEG, GIF and WebP images are supported".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(MediaServiceError::InvalidImage) => bad_request("Image could not be decoded"),
+
+        Err(MediaServiceError::AltTextTooLong) => bad_request("Alt text is too long"),
+
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn get_media_file(State(state): State<AppState>, Path(key): Path<String>) -> Response {
+    match state.media_service.open(&key).await {
+        Ok((bytes, content_type)) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, content_type),
+                // Keys are content hashes, so a blob never changes
+                (
+                    header::CACHE_CONTROL,
+                    "public, max-age=31536000, immutable".to_string(),
+                ),
+            ],
+            bytes,
+        )
+            .into_response(),
+
+        Err(MediaServiceError::NotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Media not found".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
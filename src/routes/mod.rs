@@ -1,3 +1,18 @@
+pub mod auth;
+pub mod blocks;
+pub mod direct_messages;
+pub mod drafts;
+pub mod feed;
 pub mod follow;
+pub mod lists;
+pub mod media;
+pub mod mutes;
+pub mod notifications;
+pub mod search;
+pub mod stream;
+pub mod suggestions;
+pub mod trends;
 pub mod tweets;
 pub mod users;
+pub mod webhooks;
+pub mod ws;
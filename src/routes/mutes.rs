@@ -0,0 +1,141 @@
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use serde::Serialize;
+
+use crate::{
+    app::AppState, models::mute::CreateKeywordMuteRequest, routes::auth::CurrentUser,
+    services::mute_service::MuteServiceError,
+};
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+fn mute_error_response(error: MuteServiceError) -> Response {
+    let (status, message) = match error {
+        MuteServiceError::CannotMuteSelf => (StatusCode::BAD_REQUEST, "Cannot mute yourself"),
+        MuteServiceError::UserNotFound => (StatusCode::NOT_FOUND, "User not found"),
+        MuteServiceError::TweetNotFound => (StatusCode::NOT_FOUND, "Tweet not found"),
+        MuteServiceError::AlreadyMuted => (StatusCode::CONFLICT, "Already muted"),
+        MuteServiceError::NotMuted => (StatusCode::NOT_FOUND, "Mute not found"),
+        MuteServiceError::EmptyKeyword => (StatusCode::BAD_REQUEST, "Muted phrase cannot be empty"),
+        MuteServiceError::KeywordTooLong => (
+            StatusCode::BAD_REQUEST,
+            "Muted phrase exceeds 100 characters",
+        ),
+        MuteServiceError::TooManyKeywords => {
+            (StatusCode::CONFLICT, "At most 200 phrases can be muted")
+        }
+        MuteServiceError::ExpiryInPast => (StatusCode::BAD_REQUEST, "Expiry must be in the future"),
+        MuteServiceError::DatabaseError => {
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+        }
+    };
+
+    (
+        status,
+        Json(ErrorResponse {
+            error: message.into(),
+        }),
+    )
+        .into_response()
+}
+
+pub async fn list_muted_users(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> Response {
+    match state.mute_service.muted_users(user.id).await {
+        Ok(users) => (StatusCode::OK, Json(users)).into_response(),
+        Err(e) => mute_error_response(e),
+    }
+}
+
+pub async fn mute_user(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(user_id): Path<i32>,
+) -> Response {
+    match state.mute_service.mute_user(user.id, user_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => mute_error_response(e),
+    }
+}
+
+pub async fn unmute_user(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(user_id): Path<i32>,
+) -> Response {
+    match state.mute_service.unmute_user(user.id, user_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => mute_error_response(e),
+    }
+}
+
+pub async fn list_muted_keywords(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> Response {
+    match state.mute_service.muted_keywords(user.id).await {
+        Ok(keywords) => (StatusCode::OK, Json(keywords)).into_response(),
+        Err(e) => mute_error_response(e),
+    }
+}
+
+pub async fn mute_keyword(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Json(payload): Json<CreateKeywordMuteRequest>,
+) -> Response {
+    match state.mute_service.mute_keyword(user.id, payload).await {
+        Ok(keyword) => (StatusCode::CREATED, Json(keyword)).into_response(),
+        Err(e) => mute_error_response(e),
+    }
+}
+
+pub async fn unmute_keyword(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+) -> Response {
+    match state.mute_service.unmute_keyword(user.id, id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => mute_error_response(e),
+    }
+}
+
+pub async fn mute_conversation(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(tweet_id): Path<i32>,
+) -> Response {
+    match state
+        .mute_service
+        .mute_conversation(user.id, tweet_id)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => mute_error_response(e),
+    }
+}
+
+pub async fn unmute_conversation(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(tweet_id): Path<i32>,
+) -> Response {
+    match state
+        .mute_service
+        .unmute_conversation(user.id, tweet_id)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => mute_error_response(e),
+    }
+}
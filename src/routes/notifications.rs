@@ -0,0 +1,90 @@
+use axum::{
+    extract::{Json, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{app::AppState, models::notification::MarkReadRequest, routes::auth::CurrentUser};
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+#[derive(Deserialize)]
+pub struct NotificationParams {
+    limit: Option<i64>,
+    before: Option<i64>,
+}
+
+fn internal_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Internal server error".into(),
+        }),
+    )
+        .into_response()
+}
+
+pub async fn list_notifications(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Query(params): Query<NotificationParams>,
+) -> Response {
+    let limit = params.limit.unwrap_or(20).clamp(1, 50);
+
+    match state
+        .notification_service
+        .list(user.id, params.before, limit)
+        .await
+    {
+        Ok((items, next_cursor)) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "items": items,
+                "next_cursor": next_cursor
+            })),
+        )
+            .into_response(),
+
+        Err(_) => internal_error(),
+    }
+}
+
+pub async fn unread_count(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> Response {
+    match state.notification_service.unread_count(user.id).await {
+        Ok(unread) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "unread": unread })),
+        )
+            .into_response(),
+
+        Err(_) => internal_error(),
+    }
+}
+
+pub async fn mark_read(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Json(payload): Json<MarkReadRequest>,
+) -> Response {
+    match state
+        .notification_service
+        .mark_read(user.id, payload.up_to)
+        .await
+    {
+        Ok(watermark) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "watermark": watermark })),
+        )
+            .into_response(),
+
+        Err(_) => internal_error(),
+    }
+}
@@ -0,0 +1,94 @@
+use axum::{
+    extract::{Json, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::AppState,
+    models::search::{SearchCursor, SearchSort},
+    routes::{auth::CurrentUser, tweets::parse_cursor},
+    services::search_service::SearchServiceError,
+};
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+#[derive(Deserialize)]
+pub struct SearchParams {
+    q: String,
+    #[serde(default)]
+    sort: SearchSort,
+    limit: Option<i64>,
+    before: Option<String>,
+}
+
+/// Relevance cursor format: "<rank>|<tweet_id>"
+fn parse_relevance_cursor(cursor: &str) -> Option<(f32, i32)> {
+    let (rank, id) = cursor.split_once('|')?;
+    Some((rank.parse().ok()?, id.parse().ok()?))
+}
+
+fn search_error_response(error: SearchServiceError) -> Response {
+    let (status, message) = match error {
+        SearchServiceError::EmptyQuery => (
+            StatusCode::BAD_REQUEST,
+            "Search needs words, a #hashtag or from:username".to_string(),
+        ),
+        SearchServiceError::QueryTooLong => (
+            StatusCode::BAD_REQUEST,
+            "Search query exceeds 500 characters".to_string(),
+        ),
+        SearchServiceError::InvalidOperator(token) => (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid search operator: {token}"),
+        ),
+        SearchServiceError::IndexError | SearchServiceError::DatabaseError => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Internal server error".to_string(),
+        ),
+    };
+
+    (status, Json(ErrorResponse { error: message })).into_response()
+}
+
+pub async fn search_tweets(
+    State(state): State<AppState>,
+    viewer: Option<CurrentUser>,
+    Query(params): Query<SearchParams>,
+) -> Response {
+    let viewer_id = viewer.map(|CurrentUser(user)| user.id);
+    let limit = params.limit.unwrap_or(20).clamp(1, 50);
+
+    let cursor =
+        params
+            .before
+            .as_deref()
+            .and_then(|cursor| match params.sort {
+                SearchSort::Relevance => parse_relevance_cursor(cursor)
+                    .map(|(rank, id)| SearchCursor::Relevance(rank, id)),
+                SearchSort::Recent => parse_cursor(cursor)
+                    .map(|(created_at, id)| SearchCursor::Recent(created_at, id)),
+            });
+
+    match state
+        .search_service
+        .search_tweets(&params.q, params.sort, cursor, limit, viewer_id)
+        .await
+    {
+        Ok((items, next_cursor)) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "items": items,
+                "next_cursor": next_cursor
+            })),
+        )
+            .into_response(),
+
+        Err(e) => search_error_response(e),
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::time::Duration;
+
+use async_stream::stream;
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::Stream;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+    app::AppState,
+    events::{DomainEvent, TimelineEvent},
+    routes::tweets::parse_cursor,
+};
+
+/// Most missed tweets replayed on reconnect; beyond that the client must refetch
+const MAX_REPLAY: i64 = 200;
+
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+fn tweet_event(event: &TimelineEvent) -> Event {
+    Event::default()
+        .event("tweet")
+        .id(event.cursor())
+        .json_data(&event.tweet)
+        .unwrap_or_else(|_| Event::default().event("error"))
+}
+
+/// New tweets as they are posted; a `Last-Event-ID` header replays what was
+/// missed since that tweet. Clients that fall too far behind are disconnected
+/// and catch up by reconnecting.
+pub async fn stream_timeline(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    // Subscribe before replaying so nothing posted in between is lost
+    let mut receiver = state.events.subscribe();
+    let resume = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_cursor);
+    let tweet_service = state.tweet_service.clone();
+
+    let events = stream! {
+        let mut replayed = HashSet::new();
+
+        if let Some(after) = resume {
+            match tweet_service.timeline_since(MAX_REPLAY, after).await {
+                Ok(missed) => {
+                    if missed.len() as i64 == MAX_REPLAY {
+                        yield Ok(Event::default().event("reset").data("too far behind"));
+                    } else {
+                        for event in &missed {
+                            replayed.insert(event.tweet.id);
+                            yield Ok(tweet_event(event));
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(error = ?e, "timeline replay failed");
+                    return;
+                }
+            }
+        }
+
+        loop {
+            match receiver.recv().await {
+                // Tweets posted during the replay arrive here a second time
+                Ok(DomainEvent::TweetCreated(event)) if replayed.remove(&event.tweet.id) => continue,
+                // The stream is anonymous
+                Ok(DomainEvent::TweetCreated(event)) if event.protected => continue,
+                Ok(DomainEvent::TweetCreated(event)) => yield Ok(tweet_event(&event)),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "dropping slow timeline subscriber");
+                    break;
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    };
+
+    Sse::new(events).keep_alive(
+        KeepAlive::new()
+            .interval(HEARTBEAT_INTERVAL)
+            .event(Event::default().event("heartbeat")),
+    )
+}
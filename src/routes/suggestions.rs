@@ -0,0 +1,61 @@
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::AppState, routes::auth::CurrentUser, services::suggestion_service::SuggestionServiceError,
+};
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+#[derive(Deserialize)]
+pub struct SuggestionParams {
+    limit: Option<i64>,
+}
+
+pub async fn follow_suggestions(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+    Query(params): Query<SuggestionParams>,
+) -> Response {
+    // Suggestions are built from the user's own graph
+    if user.id != id {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: "You can only see your own suggestions".into(),
+            }),
+        )
+            .into_response();
+    }
+
+    let limit = params.limit.unwrap_or(20).clamp(1, 50);
+
+    match state.suggestion_service.suggestions(id, limit).await {
+        Ok(suggestions) => (StatusCode::OK, Json(suggestions)).into_response(),
+
+        Err(SuggestionServiceError::UserNotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "User not found".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(SuggestionServiceError::DatabaseError) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,35 @@
+use axum::{
+    extract::{Json, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppState;
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+#[derive(Deserialize)]
+pub struct TrendParams {
+    limit: Option<usize>,
+}
+
+pub async fn trends(State(state): State<AppState>, Query(params): Query<TrendParams>) -> Response {
+    let limit = params.limit.unwrap_or(10).clamp(1, 50);
+
+    match state.trend_service.trends(limit).await {
+        Ok(snapshot) => (StatusCode::OK, Json(snapshot)).into_response(),
+
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
@@ -8,7 +8,13 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    app::AppState, models::tweet::CreateTweetRequest, services::tweet_service::TweetServiceError,
+    app::AppState,
+    models::{
+        poll::VoteRequest,
+        tweet::{CreateTweetRequest, ValidateTweetRequest},
+    },
+    routes::{auth::CurrentUser, drafts::draft_error_response},
+    services::tweet_service::TweetServiceError,
 };
 
 #[derive(Serialize)]
@@ -24,15 +30,24 @@ pub struct TimelineParams {
 
 #[derive(Deserialize)]
 pub struct CursorTimelineParams {
-    limit: Option<i64>,
-    before: Option<String>,
+    pub limit: Option<i64>,
+    pub before: Option<String>,
 }
 
 pub async fn create_tweet(
     State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
     Json(payload): Json<CreateTweetRequest>,
 ) -> Response {
-    match state.tweet_service.create_tweet(payload.content).await {
+    // Scheduled tweets are stored as drafts until the scheduler publishes them
+    if payload.scheduled_at.is_some() {
+        return match state.draft_service.create_draft(user.id, payload).await {
+            Ok(draft) => (StatusCode::ACCEPTED, Json(draft)).into_response(),
+            Err(e) => draft_error_response(e),
+        };
+    }
+
+    match state.tweet_service.create_tweet(user.id, payload).await {
         Ok(tweet) => (StatusCode::CREATED, Json(tweet)).into_response(),
 
         Err(TweetServiceError::EmptyContent) => (
@@ -51,6 +66,62 @@ pub async fn create_tweet(
         )
             .into_response(),
 
+        Err(TweetServiceError::InvalidCharacters) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Tweet contains control characters".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(TweetServiceError::TooManyMedia) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "A tweet can have at most 4 attachments".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(TweetServiceError::InvalidMedia) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Unknown or already attached media".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(TweetServiceError::InvalidPollOptions) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "A poll needs 2 to 4 distinct options of at most 25 characters".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(TweetServiceError::InvalidPollDuration) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Poll duration must be between 5 minutes and 7 days".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(TweetServiceError::ReplyTargetNotFound) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Replied-to tweet does not exist".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(TweetServiceError::Blocked) => (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: "You cannot reply to this user".into(),
+            }),
+        )
+            .into_response(),
+
         Err(TweetServiceError::NotFound) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
@@ -59,7 +130,7 @@ pub async fn create_tweet(
         )
             .into_response(),
 
-        Err(TweetServiceError::DatabaseError) => (
+        Err(_) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
                 error: "Internal server error".into(),
@@ -69,8 +140,39 @@ pub async fn create_tweet(
     }
 }
 
-pub async fn get_tweet(State(state): State<AppState>, Path(id): Path<u64>) -> Response {
-    match state.tweet_service.get_tweet(id).await {
+pub async fn validate_tweet(
+    State(state): State<AppState>,
+    Json(payload): Json<ValidateTweetRequest>,
+) -> Response {
+    match state.tweet_service.validate_content(&payload.content) {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+
+        Err(TweetServiceError::InvalidCharacters) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Tweet contains control characters".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn get_tweet(
+    State(state): State<AppState>,
+    viewer: Option<CurrentUser>,
+    Path(id): Path<u64>,
+) -> Response {
+    let viewer_id = viewer.map(|CurrentUser(user)| user.id);
+
+    match state.tweet_service.get_tweet(id, viewer_id).await {
         Ok(tweet) => (StatusCode::OK, Json(tweet)).into_response(),
 
         Err(TweetServiceError::NotFound) => (
@@ -91,10 +193,159 @@ pub async fn get_tweet(State(state): State<AppState>, Path(id): Path<u64>) -> Re
     }
 }
 
+pub async fn vote_in_poll(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<u64>,
+    Json(payload): Json<VoteRequest>,
+) -> Response {
+    match state.tweet_service.vote(id, user.id, payload.option).await {
+        Ok(poll) => (StatusCode::OK, Json(poll)).into_response(),
+
+        Err(TweetServiceError::PollNotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Tweet has no poll".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(TweetServiceError::PollClosed) => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "Poll is closed".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(TweetServiceError::AlreadyVoted) => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "You already voted in this poll".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(TweetServiceError::InvalidPollOption) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "No such poll option".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn delete_tweet(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<u64>,
+) -> Response {
+    match state.tweet_service.delete_tweet(user.id, id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+
+        Err(TweetServiceError::NotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Tweet not found".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn like_tweet(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<u64>,
+) -> Response {
+    match state.tweet_service.like(user.id, id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+
+        Err(TweetServiceError::NotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Tweet not found".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(TweetServiceError::AlreadyLiked) => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "Already liked this tweet".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn unlike_tweet(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<u64>,
+) -> Response {
+    match state.tweet_service.unlike(user.id, id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+
+        Err(TweetServiceError::NotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Tweet not found".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(TweetServiceError::NotLiked) => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "You have not liked this tweet".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+#[allow(clippy::manual_clamp)]
 pub async fn timeline(
     State(state): State<AppState>,
+    viewer: Option<CurrentUser>,
     Query(params): Query<TimelineParams>,
 ) -> Response {
+    let viewer_id = viewer.map(|CurrentUser(user)| user.id);
+
     let mut limit = params.limit.unwrap_or(20);
     let mut offset = params.offset.unwrap_or(0);
 
@@ -109,7 +360,7 @@ pub async fn timeline(
         offset = 0;
     }
 
-    match state.tweet_service.timeline(limit, offset).await {
+    match state.tweet_service.timeline(limit, offset, viewer_id).await {
         Ok(tweets) => (StatusCode::OK, Json(tweets)).into_response(),
 
         Err(_) => (
@@ -123,17 +374,21 @@ pub async fn timeline(
 }
 
 /// Cursor format: "<RFC3339 timestamp>|<tweet_id>"
-fn parse_cursor(cursor: &str) -> Option<(DateTime<Utc>, i32)> {
+pub fn parse_cursor(cursor: &str) -> Option<(DateTime<Utc>, i32)> {
     let (ts, id) = cursor.split_once('|')?;
     let ts = DateTime::parse_from_rfc3339(ts).ok()?.with_timezone(&Utc);
     let id = id.parse::<i32>().ok()?;
     Some((ts, id))
 }
 
+#[allow(clippy::manual_clamp)]
 pub async fn timeline_cursor(
     State(state): State<AppState>,
+    viewer: Option<CurrentUser>,
     Query(params): Query<CursorTimelineParams>,
 ) -> Response {
+    let viewer_id = viewer.map(|CurrentUser(user)| user.id);
+
     let mut limit = params.limit.unwrap_or(20);
 
     // HARD CLAMP (this is mandatory)
@@ -146,7 +401,11 @@ pub async fn timeline_cursor(
 
     let before = params.before.as_deref().and_then(parse_cursor);
 
-    match state.tweet_service.timeline_cursor(limit, before).await {
+    match state
+        .tweet_service
+        .timeline_cursor(limit, before, viewer_id)
+        .await
+    {
         Ok((items, next_cursor)) => (
             StatusCode::OK,
             Json(serde_json::json!({
@@ -1,20 +1,67 @@
 use axum::{
-    extract::{Json, State},
+    extract::{Json, Path, Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
 };
 
 use crate::{
-    app::AppState, models::user::CreateUserRequest, services::user_service::UserServiceError,
+    app::AppState,
+    models::user::{CreateUserRequest, UpdateProfileRequest, UpdateSettingsRequest},
+    routes::auth::CurrentUser,
+    services::user_service::UserServiceError,
 };
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Serialize)]
 struct ErrorResponse {
     error: String,
 }
 
+#[derive(Deserialize)]
+pub struct UserSearchParams {
+    q: String,
+    limit: Option<i64>,
+}
+
+/// Shared by the profile handlers
+fn profile_error_response(error: UserServiceError) -> Response {
+    let (status, message) = match error {
+        UserServiceError::EmptyUsername => (StatusCode::BAD_REQUEST, "Username cannot be empty"),
+        UserServiceError::DisplayNameTooLong => (
+            StatusCode::BAD_REQUEST,
+            "Display name exceeds 50 characters",
+        ),
+        UserServiceError::BioTooLong => (StatusCode::BAD_REQUEST, "Bio exceeds 160 characters"),
+        UserServiceError::LocationTooLong => {
+            (StatusCode::BAD_REQUEST, "Location exceeds 30 characters")
+        }
+        UserServiceError::WebsiteTooLong => {
+            (StatusCode::BAD_REQUEST, "Website exceeds 100 characters")
+        }
+        UserServiceError::InvalidWebsite => {
+            (StatusCode::BAD_REQUEST, "Website must be an http(s) URL")
+        }
+        UserServiceError::InvalidCharacters => (
+            StatusCode::BAD_REQUEST,
+            "Profile contains control characters",
+        ),
+        UserServiceError::InvalidMedia => (StatusCode::BAD_REQUEST, "Unknown media"),
+        UserServiceError::NotFound => (StatusCode::NOT_FOUND, "User not found"),
+        UserServiceError::EmptyQuery | UserServiceError::DatabaseError => {
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+        }
+    };
+
+    (
+        status,
+        Json(ErrorResponse {
+            error: message.into(),
+        }),
+    )
+        .into_response()
+}
+
 pub async fn create_user(
     State(state): State<AppState>,
     Json(payload): Json<CreateUserRequest>,
@@ -39,3 +86,152 @@ pub async fn create_user(
             .into_response(),
     }
 }
+
+pub async fn search_users(
+    State(state): State<AppState>,
+    viewer: Option<CurrentUser>,
+    Query(params): Query<UserSearchParams>,
+) -> Response {
+    let viewer_id = viewer.map(|CurrentUser(user)| user.id);
+    let limit = params.limit.unwrap_or(20).clamp(1, 50);
+
+    match state
+        .user_service
+        .search_users(&params.q, viewer_id, limit)
+        .await
+    {
+        Ok(users) => (StatusCode::OK, Json(users)).into_response(),
+
+        Err(UserServiceError::EmptyQuery) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Search query cannot be empty".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn typeahead(
+    State(state): State<AppState>,
+    viewer: Option<CurrentUser>,
+    Query(params): Query<UserSearchParams>,
+) -> Response {
+    let viewer_id = viewer.map(|CurrentUser(user)| user.id);
+    let limit = params.limit.unwrap_or(8).clamp(1, 20);
+
+    match state
+        .user_service
+        .typeahead(&params.q, viewer_id, limit)
+        .await
+    {
+        Ok(users) => (StatusCode::OK, Json(users)).into_response(),
+
+        Err(UserServiceError::EmptyQuery) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Prefix cannot be empty".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn get_settings(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> Response {
+    match state.user_service.settings(user.id).await {
+        Ok(settings) => (StatusCode::OK, Json(settings)).into_response(),
+
+        Err(UserServiceError::NotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "User not found".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn update_settings(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Json(payload): Json<UpdateSettingsRequest>,
+) -> Response {
+    match state.user_service.update_settings(user.id, payload).await {
+        Ok(settings) => (StatusCode::OK, Json(settings)).into_response(),
+
+        Err(UserServiceError::NotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "User not found".into(),
+            }),
+        )
+            .into_response(),
+
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Internal server error".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn get_user(State(state): State<AppState>, Path(id): Path<i32>) -> Response {
+    match state.user_service.get_user(id).await {
+        Ok(user) => (StatusCode::OK, Json(user)).into_response(),
+        Err(e) => profile_error_response(e),
+    }
+}
+
+pub async fn get_user_by_username(
+    State(state): State<AppState>,
+    Path(username): Path<String>,
+) -> Response {
+    match state.user_service.get_user_by_username(&username).await {
+        Ok(user) => (StatusCode::OK, Json(user)).into_response(),
+        Err(e) => profile_error_response(e),
+    }
+}
+
+pub async fn get_me(CurrentUser(user): CurrentUser) -> Response {
+    (StatusCode::OK, Json(user)).into_response()
+}
+
+pub async fn update_profile(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Json(payload): Json<UpdateProfileRequest>,
+) -> Response {
+    match state.user_service.update_profile(user.id, payload).await {
+        Ok(user) => (StatusCode::OK, Json(user)).into_response(),
+        Err(e) => profile_error_response(e),
+    }
+}
@@ -0,0 +1,161 @@
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::AppState,
+    models::webhook::{CreateWebhookRequest, UpdateWebhookRequest},
+    routes::auth::CurrentUser,
+    services::webhook_service::WebhookServiceError,
+};
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+#[derive(Deserialize)]
+pub struct DeliveryParams {
+    limit: Option<i64>,
+    before: Option<i64>,
+}
+
+fn webhook_error_response(error: WebhookServiceError) -> Response {
+    let (status, message) = match error {
+        WebhookServiceError::InvalidUrl => (
+            StatusCode::BAD_REQUEST,
+            "Webhook URL must be an http(s) URL",
+        ),
+        WebhookServiceError::PrivateAddress => (
+            StatusCode::BAD_REQUEST,
+            "Webhook URL must not point to a private or local address",
+        ),
+        WebhookServiceError::InvalidEvents => (
+            StatusCode::BAD_REQUEST,
+            "Events must be one or more of tweet.created, tweet.deleted, follow, mention",
+        ),
+        WebhookServiceError::TooManyWebhooks => (
+            StatusCode::CONFLICT,
+            "At most 10 webhooks can be registered",
+        ),
+        WebhookServiceError::NotFound => (StatusCode::NOT_FOUND, "Webhook not found"),
+        WebhookServiceError::DatabaseError => {
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+        }
+    };
+
+    (
+        status,
+        Json(ErrorResponse {
+            error: message.into(),
+        }),
+    )
+        .into_response()
+}
+
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> Response {
+    match state.webhook_service.create_webhook(user.id, payload).await {
+        Ok(webhook) => (StatusCode::CREATED, Json(webhook)).into_response(),
+        Err(e) => webhook_error_response(e),
+    }
+}
+
+pub async fn list_webhooks(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> Response {
+    match state.webhook_service.list_webhooks(user.id).await {
+        Ok(webhooks) => (StatusCode::OK, Json(webhooks)).into_response(),
+        Err(e) => webhook_error_response(e),
+    }
+}
+
+pub async fn get_webhook(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+) -> Response {
+    match state.webhook_service.get_webhook(id, user.id).await {
+        Ok(webhook) => (StatusCode::OK, Json(webhook)).into_response(),
+        Err(e) => webhook_error_response(e),
+    }
+}
+
+pub async fn update_webhook(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdateWebhookRequest>,
+) -> Response {
+    match state
+        .webhook_service
+        .update_webhook(id, user.id, payload)
+        .await
+    {
+        Ok(webhook) => (StatusCode::OK, Json(webhook)).into_response(),
+        Err(e) => webhook_error_response(e),
+    }
+}
+
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+) -> Response {
+    match state.webhook_service.delete_webhook(id, user.id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => webhook_error_response(e),
+    }
+}
+
+pub async fn list_deliveries(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+    Query(params): Query<DeliveryParams>,
+) -> Response {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+
+    match state
+        .webhook_service
+        .list_deliveries(id, user.id, params.before, limit)
+        .await
+    {
+        Ok(deliveries) => {
+            let next_cursor = deliveries.last().map(|delivery| delivery.id.to_string());
+
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "items": deliveries,
+                    "next_cursor": next_cursor
+                })),
+            )
+                .into_response()
+        }
+        Err(e) => webhook_error_response(e),
+    }
+}
+
+pub async fn replay_delivery(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path((id, delivery_id)): Path<(i32, i64)>,
+) -> Response {
+    match state
+        .webhook_service
+        .replay_delivery(id, user.id, delivery_id)
+        .await
+    {
+        Ok(()) => StatusCode::ACCEPTED.into_response(),
+        Err(e) => webhook_error_response(e),
+    }
+}
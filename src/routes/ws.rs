@@ -0,0 +1,292 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use axum::{
+    extract::{
+        State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    response::Response,
+};
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+    app::AppState,
+    events::{CountersEvent, DomainEvent, NotificationEvent},
+    models::{direct_message::DirectMessage, tweet::TweetResponse},
+    routes::auth::CurrentUser,
+    services::tweet_service::TweetServiceError,
+};
+
+/// Server pings keep idle connections from being cut by proxies
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Something a client can subscribe to
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "channel", rename_all = "snake_case")]
+enum Channel {
+    /// New tweets by the user and everyone they follow
+    Home,
+    Notifications,
+    /// Like and reply counts of one tweet
+    Tweet {
+        tweet_id: u64,
+    },
+    /// Direct messages and read receipts in the user's conversations
+    Messages,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum ClientMessage {
+    Subscribe {
+        #[serde(flatten)]
+        channel: Channel,
+    },
+    Unsubscribe {
+        #[serde(flatten)]
+        channel: Channel,
+    },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    Subscribed {
+        #[serde(flatten)]
+        channel: Channel,
+    },
+    Unsubscribed {
+        #[serde(flatten)]
+        channel: Channel,
+    },
+    Tweet {
+        tweet: TweetResponse,
+    },
+    Notification(NotificationEvent),
+    Counters(CountersEvent),
+    DirectMessage {
+        message: DirectMessage,
+    },
+    MessagesRead {
+        conversation_id: i32,
+        user_id: i32,
+        message_id: i32,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// What one connection is subscribed to
+struct Session {
+    user_id: i32,
+    /// Authors shown on the home channel; `None` while not subscribed
+    home: Option<HashSet<i32>>,
+    notifications: bool,
+    messages: bool,
+    tweets: HashSet<u64>,
+}
+
+impl Session {
+    /// The message, if any, this connection should get for an event
+    fn route(&mut self, event: DomainEvent) -> Option<ServerMessage> {
+        match event {
+            DomainEvent::TweetCreated(event) => {
+                let authors = self.home.as_ref()?;
+                let author_id = event.tweet.author_id?;
+
+                (author_id == self.user_id || authors.contains(&author_id))
+                    .then_some(ServerMessage::Tweet { tweet: event.tweet })
+            }
+            DomainEvent::Notification(event) => (self.notifications
+                && event.recipient_id == self.user_id)
+                .then_some(ServerMessage::Notification(event)),
+            DomainEvent::Counters(event) => self
+                .tweets
+                .contains(&event.tweet_id)
+                .then_some(ServerMessage::Counters(event)),
+            DomainEvent::DirectMessage(event) => (self.messages
+                && event.participant_ids.contains(&self.user_id))
+            .then_some(ServerMessage::DirectMessage {
+                message: event.message,
+            }),
+            DomainEvent::MessagesRead(event) => (self.messages
+                && event.participant_ids.contains(&self.user_id))
+            .then_some(ServerMessage::MessagesRead {
+                conversation_id: event.conversation_id,
+                user_id: event.user_id,
+                message_id: event.message_id,
+            }),
+            // Keep the home channel in step with follows made elsewhere
+            DomainEvent::Followed {
+                follower_id,
+                following_id,
+            } => {
+                if follower_id == self.user_id
+                    && let Some(authors) = self.home.as_mut()
+                {
+                    authors.insert(following_id);
+                }
+                None
+            }
+            DomainEvent::Unfollowed {
+                follower_id,
+                following_id,
+            } => {
+                if follower_id == self.user_id
+                    && let Some(authors) = self.home.as_mut()
+                {
+                    authors.remove(&following_id);
+                }
+                None
+            }
+            DomainEvent::UserCreated { .. } => None,
+        }
+    }
+}
+
+/// One socket multiplexing live channels; the user is authenticated on connect
+pub async fn ws_gateway(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    upgrade.on_upgrade(move |socket| run_session(socket, state, user.id))
+}
+
+async fn run_session(socket: WebSocket, state: AppState, user_id: i32) {
+    let (mut sender, mut receiver) = socket.split();
+    let mut events = state.events.subscribe();
+    let mut ping = tokio::time::interval(PING_INTERVAL);
+
+    let mut session = Session {
+        user_id,
+        home: None,
+        notifications: false,
+        messages: false,
+        tweets: HashSet::new(),
+    };
+
+    loop {
+        let reply = tokio::select! {
+            message = receiver.next() => match message {
+                Some(Ok(Message::Text(text))) => handle_message(&state, &mut session, &text).await,
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => None,
+            },
+
+            event = events.recv() => match event {
+                Ok(event) => match session.route(event) {
+                    // Mutes change at any time, so each pushed tweet is checked
+                    Some(ServerMessage::Tweet { tweet }) => {
+                        match state.mute_service.hides_tweet(user_id, tweet.id).await {
+                            Ok(false) => Some(ServerMessage::Tweet { tweet }),
+                            Ok(true) => None,
+                            Err(_) => {
+                                tracing::warn!(user_id, tweet_id = tweet.id, "mute check failed");
+                                None
+                            }
+                        }
+                    }
+                    reply => reply,
+                },
+                // Too slow to keep up: disconnect and let the client resubscribe
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(user_id, skipped, "dropping slow websocket client");
+                    break;
+                }
+                Err(RecvError::Closed) => break,
+            },
+
+            _ = ping.tick() => {
+                if sender.send(Message::Ping(Vec::new())).await.is_err() {
+                    break;
+                }
+                None
+            }
+        };
+
+        if let Some(reply) = reply {
+            let Ok(text) = serde_json::to_string(&reply) else {
+                continue;
+            };
+
+            if sender.send(Message::Text(text)).await.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+async fn handle_message(
+    state: &AppState,
+    session: &mut Session,
+    text: &str,
+) -> Option<ServerMessage> {
+    let message = match serde_json::from_str::<ClientMessage>(text) {
+        Ok(message) => message,
+        Err(_) => {
+            return Some(ServerMessage::Error {
+                message: "Unrecognized message".into(),
+            });
+        }
+    };
+
+    match message {
+        ClientMessage::Subscribe { channel } => {
+            match &channel {
+                Channel::Home => {
+                    let Ok(following) = state.follow_service.following_ids(session.user_id).await
+                    else {
+                        return Some(ServerMessage::Error {
+                            message: "Internal server error".into(),
+                        });
+                    };
+                    session.home = Some(following.into_iter().collect());
+                }
+                Channel::Notifications => session.notifications = true,
+                Channel::Messages => session.messages = true,
+                // Counters are only for tweets the user could open over HTTP
+                Channel::Tweet { tweet_id } => {
+                    match state
+                        .tweet_service
+                        .get_unblocked_tweet(*tweet_id, session.user_id)
+                        .await
+                    {
+                        Ok(_) => {
+                            session.tweets.insert(*tweet_id);
+                        }
+                        Err(TweetServiceError::NotFound) => {
+                            return Some(ServerMessage::Error {
+                                message: "Tweet not found".into(),
+                            });
+                        }
+                        Err(_) => {
+                            return Some(ServerMessage::Error {
+                                message: "Internal server error".into(),
+                            });
+                        }
+                    }
+                }
+            }
+
+            Some(ServerMessage::Subscribed { channel })
+        }
+
+        ClientMessage::Unsubscribe { channel } => {
+            match &channel {
+                Channel::Home => session.home = None,
+                Channel::Notifications => session.notifications = false,
+                Channel::Messages => session.messages = false,
+                Channel::Tweet { tweet_id } => {
+                    session.tweets.remove(tweet_id);
+                }
+            }
+
+            Some(ServerMessage::Unsubscribed { channel })
+        }
+    }
+}
@@ -0,0 +1,383 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::Bound;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tantivy::collector::TopDocs;
+use tantivy::directory::MmapDirectory;
+use tantivy::query::{
+    AllQuery, BooleanQuery, ConstScoreQuery, Occur, Query, QueryParser, RangeQuery, TermQuery,
+};
+use tantivy::schema::{
+    DateOptions, FAST, Field, INDEXED, IndexRecordOption, STRING, Schema, TextFieldIndexing,
+    TextOptions,
+};
+use tantivy::{
+    DateTimePrecision, Index, IndexReader, IndexWriter, ReloadPolicy, SegmentReader,
+    TantivyDocument, TantivyError, Term,
+};
+
+use crate::models::search::{IndexedTweet, SearchCursor, SearchSort, TweetHit, TweetSearchQuery};
+use crate::search::{SearchIndex, SearchIndexError};
+use crate::services::tweet_text;
+
+/// Memory the writer may fill before it flushes a segment to disk
+const WRITER_MEMORY_BYTES: usize = 50_000_000;
+
+/// An on-disk tantivy index inside this process. Only one process can
+/// write to a directory, and the outbox feeding it is shared, so this only
+/// suits a single instance.
+pub struct EmbeddedSearchIndex {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    index: Index,
+    reader: IndexReader,
+    writer: Mutex<IndexWriter>,
+    fields: Fields,
+}
+
+#[derive(Clone, Copy)]
+struct Fields {
+    id: Field,
+    author_id: Field,
+    content: Field,
+    hashtags: Field,
+    created_at: Field,
+}
+
+fn index_error(e: impl fmt::Display) -> SearchIndexError {
+    SearchIndexError::Index(e.to_string())
+}
+
+fn schema() -> (Schema, Fields) {
+    let mut builder = Schema::builder();
+
+    // Phrase queries need positions
+    let content = TextOptions::default().set_indexing_options(
+        TextFieldIndexing::default()
+            .set_tokenizer("en_stem")
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+    );
+
+    // Microseconds, like Postgres, so recency cursors work the same
+    let created_at = DateOptions::default()
+        .set_indexed()
+        .set_fast()
+        .set_precision(DateTimePrecision::Microseconds);
+
+    let fields = Fields {
+        id: builder.add_i64_field("id", INDEXED | FAST),
+        author_id: builder.add_i64_field("author_id", INDEXED),
+        content: builder.add_text_field("content", content),
+        hashtags: builder.add_text_field("hashtags", STRING),
+        created_at: builder.add_date_field("created_at", created_at),
+    };
+
+    (builder.build(), fields)
+}
+
+fn to_index_date(value: DateTime<Utc>) -> tantivy::DateTime {
+    tantivy::DateTime::from_timestamp_micros(value.timestamp_micros())
+}
+
+fn from_index_micros(micros: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp_micros(micros).unwrap_or_default()
+}
+
+impl EmbeddedSearchIndex {
+    /// Open the index in `dir`, creating it if it does not exist yet
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, SearchIndexError> {
+        std::fs::create_dir_all(&dir).map_err(index_error)?;
+
+        let (schema, fields) = schema();
+        let directory = MmapDirectory::open(dir).map_err(index_error)?;
+        let index = Index::open_or_create(directory, schema).map_err(index_error)?;
+        let writer = index.writer(WRITER_MEMORY_BYTES).map_err(|e| match e {
+            TantivyError::LockFailure(..) => SearchIndexError::Locked,
+            e => index_error(e),
+        })?;
+
+        // Reloaded after every commit, so a search sees the writes before it
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()
+            .map_err(index_error)?;
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                index,
+                reader,
+                writer: Mutex::new(writer),
+                fields,
+            }),
+        })
+    }
+
+    /// tantivy blocks, so its work runs off the async runtime
+    async fn blocking<T, F>(&self, work: F) -> Result<T, SearchIndexError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Inner) -> Result<T, SearchIndexError> + Send + 'static,
+    {
+        let inner = self.inner.clone();
+
+        tokio::task::spawn_blocking(move || work(&inner))
+            .await
+            .map_err(index_error)?
+    }
+}
+
+impl Inner {
+    fn query(&self, query: &TweetSearchQuery) -> BooleanQuery {
+        let fields = self.fields;
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+
+        if let Some(text) = &query.text {
+            let mut parser = QueryParser::for_index(&self.index, vec![fields.content]);
+            parser.set_conjunction_by_default();
+
+            // Whatever the parser cannot read is dropped instead of failing the search
+            let (text_query, _) = parser.parse_query_lenient(text);
+            clauses.push((Occur::Must, text_query));
+        }
+
+        let mut filters: Vec<Box<dyn Query>> = Vec::new();
+
+        if let Some(author_id) = query.author_id {
+            filters.push(Box::new(TermQuery::new(
+                Term::from_field_i64(fields.author_id, author_id.into()),
+                IndexRecordOption::Basic,
+            )));
+        }
+
+        for tag in &query.hashtags {
+            filters.push(Box::new(TermQuery::new(
+                Term::from_field_text(fields.hashtags, tag),
+                IndexRecordOption::Basic,
+            )));
+        }
+
+        if query.since.is_some() || query.until.is_some() {
+            filters.push(Box::new(RangeQuery::new_date_bounds(
+                "created_at".into(),
+                query.since.map_or(Bound::Unbounded, |since| {
+                    Bound::Included(to_index_date(since))
+                }),
+                query.until.map_or(Bound::Unbounded, |until| {
+                    Bound::Excluded(to_index_date(until))
+                }),
+            )));
+        }
+
+        // Filters have to match but do not make a tweet more relevant
+        for filter in filters {
+            clauses.push((Occur::Must, Box::new(ConstScoreQuery::new(filter, 0.0))));
+        }
+
+        if clauses.is_empty() {
+            clauses.push((Occur::Must, Box::new(AllQuery)));
+        }
+
+        for &author_id in &query.excluded_authors {
+            clauses.push((
+                Occur::MustNot,
+                Box::new(TermQuery::new(
+                    Term::from_field_i64(fields.author_id, author_id.into()),
+                    IndexRecordOption::Basic,
+                )),
+            ));
+        }
+
+        BooleanQuery::new(clauses)
+    }
+
+    /// Hits are ranked by a key ending in the tweet id; those at or before the
+    /// cursor get no key, so they sort last and are dropped
+    fn search(
+        &self,
+        query: &TweetSearchQuery,
+        sort: SearchSort,
+        cursor: Option<SearchCursor>,
+        limit: usize,
+    ) -> Result<Vec<TweetHit>, SearchIndexError> {
+        let query = self.query(query);
+        let searcher = self.reader.searcher();
+
+        let columns = |segment: &SegmentReader| {
+            let fast_fields = segment.fast_fields();
+            let ids = fast_fields
+                .i64("id")
+                .expect("id is a fast field")
+                .first_or_default_col(0);
+            let created_at = fast_fields
+                .date("created_at")
+                .expect("created_at is a fast field")
+                .first_or_default_col(tantivy::DateTime::MIN);
+            (ids, created_at)
+        };
+
+        let hits = match sort {
+            SearchSort::Relevance => {
+                let after = match cursor {
+                    Some(SearchCursor::Relevance(rank, id)) => Some((rank, i64::from(id))),
+                    _ => None,
+                };
+
+                let collector =
+                    TopDocs::with_limit(limit).tweak_score(move |segment: &SegmentReader| {
+                        let (ids, created_at) = columns(segment);
+
+                        move |doc, score| {
+                            let key = (score, ids.get_val(doc));
+                            if after.is_some_and(|after| {
+                                key.partial_cmp(&after) != Some(Ordering::Less)
+                            }) {
+                                return None;
+                            }
+                            Some((key, created_at.get_val(doc).into_timestamp_micros()))
+                        }
+                    });
+
+                searcher
+                    .search(&query, &collector)
+                    .map_err(index_error)?
+                    .into_iter()
+                    .filter_map(|(key, _)| key)
+                    .map(|((rank, id), created_at)| TweetHit {
+                        tweet_id: id as i32,
+                        created_at: from_index_micros(created_at),
+                        rank,
+                    })
+                    .collect()
+            }
+
+            SearchSort::Recent => {
+                let before = match cursor {
+                    Some(SearchCursor::Recent(created_at, id)) => {
+                        Some((created_at.timestamp_micros(), i64::from(id)))
+                    }
+                    _ => None,
+                };
+
+                let collector =
+                    TopDocs::with_limit(limit).tweak_score(move |segment: &SegmentReader| {
+                        let (ids, created_at) = columns(segment);
+
+                        move |doc, _score| {
+                            let key = (
+                                created_at.get_val(doc).into_timestamp_micros(),
+                                ids.get_val(doc),
+                            );
+                            if before.is_some_and(|before| key >= before) {
+                                return None;
+                            }
+                            Some(key)
+                        }
+                    });
+
+                searcher
+                    .search(&query, &collector)
+                    .map_err(index_error)?
+                    .into_iter()
+                    .filter_map(|(key, _)| key)
+                    .map(|(created_at, id)| TweetHit {
+                        tweet_id: id as i32,
+                        created_at: from_index_micros(created_at),
+                        rank: 0.0,
+                    })
+                    .collect()
+            }
+        };
+
+        Ok(hits)
+    }
+
+    /// Run `change` against the writer, then make the result searchable
+    fn write(
+        &self,
+        change: impl FnOnce(&mut IndexWriter) -> tantivy::Result<()>,
+    ) -> Result<(), SearchIndexError> {
+        let mut writer = self
+            .writer
+            .lock()
+            .map_err(|_| SearchIndexError::Index("index writer lock poisoned".into()))?;
+
+        change(&mut writer).map_err(index_error)?;
+        writer.commit().map_err(index_error)?;
+        self.reader.reload().map_err(index_error)
+    }
+
+    fn document(&self, tweet: &IndexedTweet) -> TantivyDocument {
+        let fields = self.fields;
+        let mut document = TantivyDocument::default();
+
+        document.add_i64(fields.id, tweet.id.into());
+        if let Some(author_id) = tweet.author_id {
+            document.add_i64(fields.author_id, author_id.into());
+        }
+        document.add_text(fields.content, &tweet.content);
+        for tag in tweet_text::extract_hashtags(&tweet.content) {
+            document.add_text(fields.hashtags, tag);
+        }
+        document.add_date(fields.created_at, to_index_date(tweet.created_at));
+
+        document
+    }
+}
+
+#[async_trait]
+impl SearchIndex for EmbeddedSearchIndex {
+    async fn search(
+        &self,
+        query: &TweetSearchQuery,
+        sort: SearchSort,
+        cursor: Option<SearchCursor>,
+        limit: i64,
+    ) -> Result<Vec<TweetHit>, SearchIndexError> {
+        let query = query.clone();
+
+        self.blocking(move |inner| inner.search(&query, sort, cursor, limit as usize))
+            .await
+    }
+
+    async fn upsert(&self, tweets: &[IndexedTweet]) -> Result<(), SearchIndexError> {
+        let tweets = tweets.to_vec();
+
+        self.blocking(move |inner| {
+            inner.write(|writer| {
+                for tweet in &tweets {
+                    writer.delete_term(Term::from_field_i64(inner.fields.id, tweet.id.into()));
+                    writer.add_document(inner.document(tweet))?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+
+    async fn remove(&self, tweet_id: i32) -> Result<(), SearchIndexError> {
+        self.blocking(move |inner| {
+            inner.write(|writer| {
+                writer.delete_term(Term::from_field_i64(inner.fields.id, tweet_id.into()));
+                Ok(())
+            })
+        })
+        .await
+    }
+
+    async fn clear(&self) -> Result<(), SearchIndexError> {
+        self.blocking(|inner| {
+            inner.write(|writer| {
+                writer.delete_all_documents()?;
+                Ok(())
+            })
+        })
+        .await
+    }
+}
@@ -0,0 +1,50 @@
+pub mod embedded;
+pub mod postgres;
+
+use std::fmt;
+
+use async_trait::async_trait;
+
+use crate::models::search::{IndexedTweet, SearchCursor, SearchSort, TweetHit, TweetSearchQuery};
+
+#[derive(Debug)]
+pub enum SearchIndexError {
+    Database(sqlx::Error),
+    Index(String),
+    /// Another process holds the index's writer lock
+    Locked,
+}
+
+impl fmt::Display for SearchIndexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchIndexError::Database(e) => write!(f, "search database error: {e}"),
+            SearchIndexError::Index(e) => write!(f, "search index error: {e}"),
+            SearchIndexError::Locked => write!(f, "search index is locked by another process"),
+        }
+    }
+}
+
+impl std::error::Error for SearchIndexError {}
+
+/// Answers tweet searches. Tweets are the source of truth; an index only
+/// holds what it needs to find them and can be rebuilt from them at any time.
+#[async_trait]
+pub trait SearchIndex: Send + Sync {
+    /// A page of hits in `sort` order, starting after `cursor`
+    async fn search(
+        &self,
+        query: &TweetSearchQuery,
+        sort: SearchSort,
+        cursor: Option<SearchCursor>,
+        limit: i64,
+    ) -> Result<Vec<TweetHit>, SearchIndexError>;
+
+    /// Add tweets, replacing any already indexed under the same id
+    async fn upsert(&self, tweets: &[IndexedTweet]) -> Result<(), SearchIndexError>;
+
+    async fn remove(&self, tweet_id: i32) -> Result<(), SearchIndexError>;
+
+    /// Forget everything, ahead of a rebuild
+    async fn clear(&self) -> Result<(), SearchIndexError>;
+}
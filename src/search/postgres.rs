@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+
+use crate::models::search::{IndexedTweet, SearchCursor, SearchSort, TweetHit, TweetSearchQuery};
+use crate::repositories::search_repository::SearchRepository;
+use crate::search::{SearchIndex, SearchIndexError};
+
+/// Searches the `tweets` table itself. Its `tsvector` column is generated
+/// by Postgres, so there is nothing to keep up to date.
+pub struct PostgresSearchIndex {
+    repository: SearchRepository,
+}
+
+impl PostgresSearchIndex {
+    pub fn new(repository: SearchRepository) -> Self {
+        Self { repository }
+    }
+}
+
+#[async_trait]
+impl SearchIndex for PostgresSearchIndex {
+    async fn search(
+        &self,
+        query: &TweetSearchQuery,
+        sort: SearchSort,
+        cursor: Option<SearchCursor>,
+        limit: i64,
+    ) -> Result<Vec<TweetHit>, SearchIndexError> {
+        // A cursor from the other sort order means nothing here
+        match sort {
+            SearchSort::Relevance => {
+                let after = match cursor {
+                    Some(SearchCursor::Relevance(rank, id)) => Some((rank, id)),
+                    _ => None,
+                };
+                self.repository
+                    .tweets_by_relevance(query, after, limit)
+                    .await
+            }
+            SearchSort::Recent => {
+                let before = match cursor {
+                    Some(SearchCursor::Recent(created_at, id)) => Some((created_at, id)),
+                    _ => None,
+                };
+                self.repository
+                    .tweets_by_recency(query, before, limit)
+                    .await
+            }
+        }
+        .map_err(SearchIndexError::Database)
+    }
+
+    async fn upsert(&self, _tweets: &[IndexedTweet]) -> Result<(), SearchIndexError> {
+        Ok(())
+    }
+
+    async fn remove(&self, _tweet_id: i32) -> Result<(), SearchIndexError> {
+        Ok(())
+    }
+
+    async fn clear(&self) -> Result<(), SearchIndexError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use crate::events::{DomainEvent, EventBus};
+use crate::repositories::block_repository::BlockRepository;
+use crate::repositories::user_repository::UserRepository;
+use crate::services::notification_service::NotificationService;
+
+#[derive(Debug)]
+pub enum BlockServiceError {
+    CannotBlockSelf,
+    UserNotFound,
+    AlreadyBlocked,
+    NotBlocked,
+    DatabaseError,
+}
+
+#[derive(Clone)]
+pub struct BlockService {
+    repository: BlockRepository,
+    user_repository: UserRepository,
+    notification_service: NotificationService,
+    events: Arc<dyn EventBus>,
+}
+
+impl BlockService {
+    pub fn new(
+        repository: BlockRepository,
+        user_repository: UserRepository,
+        notification_service: NotificationService,
+        events: Arc<dyn EventBus>,
+    ) -> Self {
+        Self {
+            repository,
+            user_repository,
+            notification_service,
+            events,
+        }
+    }
+
+    /// Block a user; any follows between the two are removed, and each is
+    /// taken off the other's lists
+    pub async fn block(&self, blocker_id: i32, blocked_id: i32) -> Result<(), BlockServiceError> {
+        if blocker_id == blocked_id {
+            return Err(BlockServiceError::CannotBlockSelf);
+        }
+
+        self.user_repository
+            .find_by_id(blocked_id)
+            .await
+            .map_err(|_| BlockServiceError::DatabaseError)?
+            .ok_or(BlockServiceError::UserNotFound)?;
+
+        let severed = self
+            .repository
+            .block(blocker_id, blocked_id)
+            .await
+            .map_err(|_| BlockServiceError::DatabaseError)?
+            .ok_or(BlockServiceError::AlreadyBlocked)?;
+
+        for (follower_id, following_id) in severed {
+            self.events
+                .publish(DomainEvent::Unfollowed {
+                    follower_id,
+                    following_id,
+                })
+                .await;
+
+            if let Err(e) = self
+                .notification_service
+                .withdraw_follow(follower_id, following_id)
+                .await
+            {
+                tracing::warn!(follower_id, following_id, error = ?e, "withdrawing follow notification failed");
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn unblock(&self, blocker_id: i32, blocked_id: i32) -> Result<(), BlockServiceError> {
+        let unblocked = self
+            .repository
+            .unblock(blocker_id, blocked_id)
+            .await
+            .map_err(|_| BlockServiceError::DatabaseError)?;
+
+        if !unblocked {
+            return Err(BlockServiceError::NotBlocked);
+        }
+
+        Ok(())
+    }
+}
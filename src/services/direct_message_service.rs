@@ -0,0 +1,413 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+use crate::events::{DomainEvent, EventBus, MessageEvent, ReadReceiptEvent};
+use crate::models::direct_message::{
+    Conversation, ConversationRow, CreateConversationRequest, DirectMessage, MarkReadRequest,
+    Participant, SendMessageRequest,
+};
+use crate::repositories::block_repository::BlockRepository;
+use crate::repositories::direct_message_repository::DirectMessageRepository;
+use crate::repositories::follow_repository::FollowRepository;
+use crate::repositories::user_repository::UserRepository;
+
+/// Longest message, in characters; keeps message events small enough to
+/// relay between instances
+const MAX_MESSAGE_LENGTH: usize = 1000;
+
+/// People in one conversation, the creator included
+const MAX_PARTICIPANTS: usize = 50;
+
+#[derive(Debug)]
+pub enum DirectMessageServiceError {
+    NoParticipants,
+    TooManyParticipants,
+    UserNotFound,
+    Blocked,
+    /// The recipient only accepts messages from accounts they follow
+    NotAccepting,
+    /// No such conversation, or the caller is not in it
+    NotFound,
+    EmptyMessage,
+    MessageTooLong,
+    MessageNotFound,
+    DatabaseError,
+}
+
+#[derive(Clone)]
+pub struct DirectMessageService {
+    repository: DirectMessageRepository,
+    user_repository: UserRepository,
+    follow_repository: FollowRepository,
+    block_repository: BlockRepository,
+    events: Arc<dyn EventBus>,
+}
+
+impl DirectMessageService {
+    pub fn new(
+        repository: DirectMessageRepository,
+        user_repository: UserRepository,
+        follow_repository: FollowRepository,
+        block_repository: BlockRepository,
+        events: Arc<dyn EventBus>,
+    ) -> Self {
+        Self {
+            repository,
+            user_repository,
+            follow_repository,
+            block_repository,
+            events,
+        }
+    }
+
+    /// Start a conversation; a one-to-one conversation that already exists is
+    /// returned instead, with `false`
+    pub async fn create_conversation(
+        &self,
+        user_id: i32,
+        request: CreateConversationRequest,
+    ) -> Result<(Conversation, bool), DirectMessageServiceError> {
+        let mut others = request.participant_ids;
+        others.retain(|&id| id != user_id);
+        others.sort_unstable();
+        others.dedup();
+
+        if others.is_empty() {
+            return Err(DirectMessageServiceError::NoParticipants);
+        }
+
+        if others.len() + 1 > MAX_PARTICIPANTS {
+            return Err(DirectMessageServiceError::TooManyParticipants);
+        }
+
+        let found = self
+            .user_repository
+            .find_by_ids(&others)
+            .await
+            .map_err(|_| DirectMessageServiceError::DatabaseError)?;
+
+        if found.len() != others.len() {
+            return Err(DirectMessageServiceError::UserNotFound);
+        }
+
+        for &recipient_id in &others {
+            self.check_can_message(user_id, recipient_id).await?;
+        }
+
+        let pair_key = match others.as_slice() {
+            [other] => Some(format!("{}:{}", user_id.min(*other), user_id.max(*other))),
+            _ => None,
+        };
+
+        if let Some(pair_key) = &pair_key
+            && let Some(id) = self
+                .repository
+                .find_pair(pair_key)
+                .await
+                .map_err(|_| DirectMessageServiceError::DatabaseError)?
+        {
+            return Ok((self.conversation(id, user_id).await?, false));
+        }
+
+        let mut participant_ids = vec![user_id];
+        participant_ids.extend(&others);
+
+        let created = self
+            .repository
+            .create_conversation(user_id, pair_key.as_deref(), &participant_ids)
+            .await
+            .map_err(|_| DirectMessageServiceError::DatabaseError)?;
+
+        match (created, pair_key) {
+            (Some(id), _) => Ok((self.conversation(id, user_id).await?, true)),
+
+            // Someone started the same pair's conversation in the meantime
+            (None, Some(pair_key)) => {
+                let id = self
+                    .repository
+                    .find_pair(&pair_key)
+                    .await
+                    .map_err(|_| DirectMessageServiceError::DatabaseError)?
+                    .ok_or(DirectMessageServiceError::NotFound)?;
+
+                Ok((self.conversation(id, user_id).await?, false))
+            }
+
+            (None, None) => Err(DirectMessageServiceError::DatabaseError),
+        }
+    }
+
+    pub async fn conversation(
+        &self,
+        id: i32,
+        user_id: i32,
+    ) -> Result<Conversation, DirectMessageServiceError> {
+        let row = self
+            .repository
+            .find_conversation(id, user_id)
+            .await
+            .map_err(|_| DirectMessageServiceError::DatabaseError)?
+            .ok_or(DirectMessageServiceError::NotFound)?;
+
+        Ok(self
+            .with_participants(vec![row])
+            .await?
+            .pop()
+            .expect("one conversation in, one conversation out"))
+    }
+
+    /// Most recently active first
+    pub async fn conversations(
+        &self,
+        user_id: i32,
+        limit: i64,
+    ) -> Result<Vec<Conversation>, DirectMessageServiceError> {
+        let rows = self
+            .repository
+            .conversations(user_id, limit)
+            .await
+            .map_err(|_| DirectMessageServiceError::DatabaseError)?;
+
+        self.with_participants(rows).await
+    }
+
+    /// Blocks are checked again on every message, against everyone in the
+    /// conversation. The recipient's message setting is too for one-to-one
+    /// conversations; group members accepted it when the group was started.
+    pub async fn send_message(
+        &self,
+        conversation_id: i32,
+        sender_id: i32,
+        request: SendMessageRequest,
+    ) -> Result<DirectMessage, DirectMessageServiceError> {
+        let content = request.content.trim();
+
+        if content.is_empty() {
+            return Err(DirectMessageServiceError::EmptyMessage);
+        }
+
+        if content.chars().count() > MAX_MESSAGE_LENGTH {
+            return Err(DirectMessageServiceError::MessageTooLong);
+        }
+
+        let conversation = self
+            .repository
+            .find_conversation(conversation_id, sender_id)
+            .await
+            .map_err(|_| DirectMessageServiceError::DatabaseError)?
+            .ok_or(DirectMessageServiceError::NotFound)?;
+
+        let participant_ids = self
+            .repository
+            .participant_ids(conversation_id)
+            .await
+            .map_err(|_| DirectMessageServiceError::DatabaseError)?;
+
+        if conversation.group {
+            let blocked = self
+                .block_repository
+                .blocked_either_way(sender_id)
+                .await
+                .map_err(|_| DirectMessageServiceError::DatabaseError)?;
+
+            if participant_ids.iter().any(|id| blocked.contains(id)) {
+                return Err(DirectMessageServiceError::Blocked);
+            }
+        } else {
+            for &recipient_id in participant_ids.iter().filter(|&&id| id != sender_id) {
+                self.check_can_message(sender_id, recipient_id).await?;
+            }
+        }
+
+        let message = self
+            .repository
+            .send(conversation_id, sender_id, content)
+            .await
+            .map_err(|_| DirectMessageServiceError::DatabaseError)?;
+
+        self.events
+            .publish(DomainEvent::DirectMessage(MessageEvent {
+                participant_ids,
+                message: message.clone(),
+            }))
+            .await;
+
+        Ok(message)
+    }
+
+    /// Newest first, with the same `created_at|id` cursor as timelines
+    pub async fn messages(
+        &self,
+        conversation_id: i32,
+        user_id: i32,
+        limit: i64,
+        before: Option<(DateTime<Utc>, i32)>,
+    ) -> Result<(Vec<DirectMessage>, Option<String>), DirectMessageServiceError> {
+        self.check_participant(conversation_id, user_id).await?;
+
+        let messages = self
+            .repository
+            .messages_before(conversation_id, limit, before)
+            .await
+            .map_err(|_| DirectMessageServiceError::DatabaseError)?;
+
+        let next_cursor = messages
+            .last()
+            .map(|message| format!("{}|{}", message.created_at.to_rfc3339(), message.id));
+
+        Ok((messages, next_cursor))
+    }
+
+    /// Move the caller's read receipt forward and tell the other participants
+    pub async fn mark_read(
+        &self,
+        conversation_id: i32,
+        user_id: i32,
+        request: MarkReadRequest,
+    ) -> Result<(), DirectMessageServiceError> {
+        let participant_ids = self.check_participant(conversation_id, user_id).await?;
+
+        if let Some(up_to) = request.up_to
+            && !self
+                .repository
+                .has_message(conversation_id, up_to)
+                .await
+                .map_err(|_| DirectMessageServiceError::DatabaseError)?
+        {
+            return Err(DirectMessageServiceError::MessageNotFound);
+        }
+
+        let read = self
+            .repository
+            .mark_read(conversation_id, user_id, request.up_to)
+            .await
+            .map_err(|_| DirectMessageServiceError::DatabaseError)?;
+
+        // Already read that far: nothing new to tell anyone
+        let Some(message_id) = read else {
+            return Ok(());
+        };
+
+        self.events
+            .publish(DomainEvent::MessagesRead(ReadReceiptEvent {
+                participant_ids,
+                conversation_id,
+                user_id,
+                message_id,
+            }))
+            .await;
+
+        Ok(())
+    }
+
+    /// Blocked either way, or not followed by a recipient who only accepts
+    /// messages from accounts they follow
+    async fn check_can_message(
+        &self,
+        sender_id: i32,
+        recipient_id: i32,
+    ) -> Result<(), DirectMessageServiceError> {
+        if self
+            .block_repository
+            .is_blocked_either_way(sender_id, recipient_id)
+            .await
+            .map_err(|_| DirectMessageServiceError::DatabaseError)?
+        {
+            return Err(DirectMessageServiceError::Blocked);
+        }
+
+        let settings = self
+            .user_repository
+            .settings(recipient_id)
+            .await
+            .map_err(|_| DirectMessageServiceError::DatabaseError)?
+            .ok_or(DirectMessageServiceError::UserNotFound)?;
+
+        if settings.dms_from_following_only
+            && !self
+                .follow_repository
+                .is_following(recipient_id, sender_id)
+                .await
+                .map_err(|_| DirectMessageServiceError::DatabaseError)?
+        {
+            return Err(DirectMessageServiceError::NotAccepting);
+        }
+
+        Ok(())
+    }
+
+    /// Everyone in the conversation, provided `user_id` is
+    async fn check_participant(
+        &self,
+        conversation_id: i32,
+        user_id: i32,
+    ) -> Result<Vec<i32>, DirectMessageServiceError> {
+        let participant_ids = self
+            .repository
+            .participant_ids(conversation_id)
+            .await
+            .map_err(|_| DirectMessageServiceError::DatabaseError)?;
+
+        if !participant_ids.contains(&user_id) {
+            return Err(DirectMessageServiceError::NotFound);
+        }
+
+        Ok(participant_ids)
+    }
+
+    async fn with_participants(
+        &self,
+        rows: Vec<ConversationRow>,
+    ) -> Result<Vec<Conversation>, DirectMessageServiceError> {
+        let ids: Vec<i32> = rows.iter().map(|row| row.id).collect();
+
+        let mut participants: HashMap<i32, Vec<Participant>> = HashMap::new();
+        for row in self
+            .repository
+            .participants(&ids)
+            .await
+            .map_err(|_| DirectMessageServiceError::DatabaseError)?
+        {
+            participants
+                .entry(row.conversation_id)
+                .or_default()
+                .push(Participant {
+                    id: row.id,
+                    username: row.username,
+                    last_read_message_id: row.last_read_message_id,
+                });
+        }
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let last_message = match (
+                    row.last_message_id,
+                    row.last_message_content,
+                    row.last_message_created_at,
+                ) {
+                    (Some(id), Some(content), Some(created_at)) => Some(DirectMessage {
+                        id,
+                        conversation_id: row.id,
+                        sender_id: row.last_message_sender_id,
+                        content,
+                        created_at,
+                    }),
+                    _ => None,
+                };
+
+                Conversation {
+                    id: row.id,
+                    group: row.group,
+                    participants: participants.remove(&row.id).unwrap_or_default(),
+                    last_message,
+                    unread_count: row.unread_count,
+                    created_at: row.created_at,
+                    last_message_at: row.last_message_at,
+                }
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,165 @@
+use chrono::{Duration, Utc};
+
+use crate::models::draft::Draft;
+use crate::models::tweet::CreateTweetRequest;
+use crate::repositories::draft_repository::DraftRepository;
+use crate::services::tweet_service::{TweetService, TweetServiceError};
+
+/// Drafts may run over the tweet limit while being edited, but not without bound
+const MAX_DRAFT_BYTES: usize = 10_000;
+
+/// How far ahead a tweet can be scheduled
+const MAX_SCHEDULE_AHEAD_DAYS: i64 = 540;
+
+/// Due tweets published per scheduler tick
+const PUBLISH_BATCH_SIZE: i64 = 50;
+
+/// How long an instance may hold a due draft before another one retries it
+const CLAIM_LEASE_SECS: f64 = 60.0;
+
+#[derive(Debug)]
+pub enum DraftServiceError {
+    DraftTooLong,
+    ScheduleInPast,
+    ScheduleTooFar,
+    /// A scheduled draft must already be a valid tweet
+    InvalidTweet(TweetServiceError),
+    NotFound,
+    DatabaseError,
+}
+
+#[derive(Clone)]
+pub struct DraftService {
+    repository: DraftRepository,
+    tweet_service: TweetService,
+}
+
+impl DraftService {
+    pub fn new(repository: DraftRepository, tweet_service: TweetService) -> Self {
+        Self {
+            repository,
+            tweet_service,
+        }
+    }
+
+    pub async fn create_draft(
+        &self,
+        author_id: i32,
+        draft: CreateTweetRequest,
+    ) -> Result<Draft, DraftServiceError> {
+        self.check(author_id, &draft).await?;
+
+        self.repository
+            .create(author_id, &draft)
+            .await
+            .map_err(|_| DraftServiceError::DatabaseError)
+    }
+
+    pub async fn update_draft(
+        &self,
+        id: i32,
+        author_id: i32,
+        draft: CreateTweetRequest,
+    ) -> Result<Draft, DraftServiceError> {
+        self.check(author_id, &draft).await?;
+
+        self.repository
+            .update(id, author_id, &draft)
+            .await
+            .map_err(|_| DraftServiceError::DatabaseError)?
+            .ok_or(DraftServiceError::NotFound)
+    }
+
+    pub async fn get_draft(&self, id: i32, author_id: i32) -> Result<Draft, DraftServiceError> {
+        self.repository
+            .find(id, author_id)
+            .await
+            .map_err(|_| DraftServiceError::DatabaseError)?
+            .ok_or(DraftServiceError::NotFound)
+    }
+
+    pub async fn list_drafts(&self, author_id: i32) -> Result<Vec<Draft>, DraftServiceError> {
+        self.repository
+            .list(author_id)
+            .await
+            .map_err(|_| DraftServiceError::DatabaseError)
+    }
+
+    pub async fn delete_draft(&self, id: i32, author_id: i32) -> Result<(), DraftServiceError> {
+        let deleted = self
+            .repository
+            .delete(id, author_id)
+            .await
+            .map_err(|_| DraftServiceError::DatabaseError)?;
+
+        if !deleted {
+            return Err(DraftServiceError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Publish every scheduled tweet that is due; returns how many went out
+    pub async fn publish_due(&self) -> Result<usize, DraftServiceError> {
+        let due = self
+            .repository
+            .claim_due(PUBLISH_BATCH_SIZE, CLAIM_LEASE_SECS)
+            .await
+            .map_err(|_| DraftServiceError::DatabaseError)?;
+
+        let mut published = 0;
+        for draft in due {
+            match self.tweet_service.publish_draft(&draft).await {
+                Ok(_) => published += 1,
+
+                // Left claimed: the lease runs out and a later tick retries it
+                Err(TweetServiceError::DatabaseError) => {
+                    tracing::warn!(draft_id = draft.id, "scheduled tweet publish failed");
+                }
+
+                // Retrying cannot help, so hand it back to the author
+                Err(e) => {
+                    self.repository
+                        .mark_failed(draft.claim(), &format!("{e:?}"))
+                        .await
+                        .map_err(|_| DraftServiceError::DatabaseError)?;
+                }
+            }
+        }
+
+        Ok(published)
+    }
+
+    /// Plain drafts only need a size cap; scheduled ones must be publishable
+    async fn check(
+        &self,
+        author_id: i32,
+        draft: &CreateTweetRequest,
+    ) -> Result<(), DraftServiceError> {
+        if draft.content.len() > MAX_DRAFT_BYTES {
+            return Err(DraftServiceError::DraftTooLong);
+        }
+
+        let Some(scheduled_at) = draft.scheduled_at else {
+            return Ok(());
+        };
+
+        let now = Utc::now();
+        if scheduled_at <= now {
+            return Err(DraftServiceError::ScheduleInPast);
+        }
+        if scheduled_at > now + Duration::days(MAX_SCHEDULE_AHEAD_DAYS) {
+            return Err(DraftServiceError::ScheduleTooFar);
+        }
+
+        self.tweet_service
+            .prepare_tweet(author_id, draft, None)
+            .await
+            .map_err(|e| match e {
+                TweetServiceError::DatabaseError => DraftServiceError::DatabaseError,
+                e => DraftServiceError::InvalidTweet(e),
+            })?;
+
+        Ok(())
+    }
+}
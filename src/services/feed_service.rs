@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use rand::RngCore;
+
+use crate::models::tweet::TweetResponse;
+use crate::repositories::feed_repository::FeedRepository;
+use crate::repositories::tweet_repository::TweetRepository;
+use crate::services::ranking::Ranker;
+
+/// How far back candidates are gathered
+const CANDIDATE_WINDOW_SECS: f64 = 3.0 * 24.0 * 3600.0;
+
+/// Most liked tweets mixed into every feed
+const POPULAR_CANDIDATES: i64 = 50;
+
+/// Candidates scored per feed
+const MAX_CANDIDATES: i64 = 1000;
+
+/// Ranked tweets kept in a feed session; scrolling past them ends the feed
+const MAX_FEED_LENGTH: usize = 300;
+
+/// How long the pages of one ranked feed stay available
+const SESSION_TTL_SECS: f64 = 30.0 * 60.0;
+
+#[derive(Debug)]
+pub enum FeedServiceError {
+    /// The cursor's feed expired or belongs to someone else
+    SessionExpired,
+    /// The cursor points past the end of its feed
+    InvalidCursor,
+    DatabaseError,
+}
+
+#[derive(Clone)]
+pub struct FeedService {
+    repository: FeedRepository,
+    tweet_repository: TweetRepository,
+    ranker: Arc<dyn Ranker>,
+}
+
+impl FeedService {
+    pub fn new(
+        repository: FeedRepository,
+        tweet_repository: TweetRepository,
+        ranker: Arc<dyn Ranker>,
+    ) -> Self {
+        Self {
+            repository,
+            tweet_repository,
+            ranker,
+        }
+    }
+
+    /// A page of the user's ranked feed and the cursor for the next one.
+    /// The first page ranks a fresh feed; the cursor pins later pages to it.
+    /// Cursor format: "<session id>|<offset>"
+    pub async fn for_you(
+        &self,
+        user_id: i32,
+        cursor: Option<(String, usize)>,
+        limit: usize,
+    ) -> Result<(Vec<TweetResponse>, Option<String>), FeedServiceError> {
+        let (session_id, offset, tweet_ids) = match cursor {
+            Some((session_id, offset)) => {
+                let tweet_ids = self
+                    .repository
+                    .session(&session_id, user_id)
+                    .await
+                    .map_err(|_| FeedServiceError::DatabaseError)?
+                    .ok_or(FeedServiceError::SessionExpired)?;
+
+                // Cursors we hand out never point past the end
+                if offset > tweet_ids.len() {
+                    return Err(FeedServiceError::InvalidCursor);
+                }
+
+                (session_id, offset, tweet_ids)
+            }
+            None => {
+                let (session_id, tweet_ids) = self.rank(user_id).await?;
+                (session_id, 0, tweet_ids)
+            }
+        };
+
+        let end = offset.saturating_add(limit).min(tweet_ids.len());
+        let page = &tweet_ids[offset..end];
+
+        // Tweets deleted, or blocked, muted or protected from the user since
+        // the feed was ranked are skipped
+        let tweets = self
+            .tweet_repository
+            .find_many_visible(page, user_id)
+            .await
+            .map_err(|_| FeedServiceError::DatabaseError)?;
+
+        let next_cursor = (end < tweet_ids.len()).then(|| format!("{session_id}|{end}"));
+
+        Ok((tweets, next_cursor))
+    }
+
+    /// Score the user's candidates and store the order as a new session
+    async fn rank(&self, user_id: i32) -> Result<(String, Vec<i32>), FeedServiceError> {
+        let candidates = self
+            .repository
+            .candidates(
+                user_id,
+                CANDIDATE_WINDOW_SECS,
+                POPULAR_CANDIDATES,
+                MAX_CANDIDATES,
+            )
+            .await
+            .map_err(|_| FeedServiceError::DatabaseError)?;
+
+        let now = Utc::now();
+        let mut scored: Vec<(f64, i32)> = candidates
+            .iter()
+            .map(|candidate| (self.ranker.score(candidate, now), candidate.tweet_id))
+            .collect();
+
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(b.1.cmp(&a.1)));
+        scored.truncate(MAX_FEED_LENGTH);
+
+        let tweet_ids: Vec<i32> = scored.into_iter().map(|(_, id)| id).collect();
+
+        let mut session = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut session);
+        let session_id = hex::encode(session);
+
+        self.repository
+            .create_session(&session_id, user_id, &tweet_ids, SESSION_TTL_SECS)
+            .await
+            .map_err(|_| FeedServiceError::DatabaseError)?;
+
+        Ok((session_id, tweet_ids))
+    }
+}
@@ -1,34 +1,78 @@
+use crate::models::follow::{FollowStatus, PendingFollower};
+use crate::models::outbox::OutboxEvent;
+use crate::repositories::block_repository::BlockRepository;
 use crate::repositories::follow_repository::FollowRepository;
+use crate::repositories::user_repository::UserRepository;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::events::{DomainEvent, EventBus};
+use crate::services::notification_service::NotificationService;
+use crate::services::outbox_service::OutboxHandler;
 
 #[derive(Debug)]
 pub enum FollowServiceError {
     CannotFollowSelf,
+    UserNotFound,
     AlreadyFollowing,
+    /// A request to follow this protected account is already pending
+    AlreadyRequested,
     NotFollowing,
+    RequestNotFound,
+    Blocked,
     DatabaseError,
 }
 
 #[derive(Clone)]
 pub struct FollowService {
     repository: FollowRepository,
+    user_repository: UserRepository,
+    block_repository: BlockRepository,
+    notification_service: NotificationService,
+    events: Arc<dyn EventBus>,
 }
 
 impl FollowService {
-    pub fn new(repository: FollowRepository) -> Self {
-        Self { repository }
+    pub fn new(
+        repository: FollowRepository,
+        user_repository: UserRepository,
+        block_repository: BlockRepository,
+        notification_service: NotificationService,
+        events: Arc<dyn EventBus>,
+    ) -> Self {
+        Self {
+            repository,
+            user_repository,
+            block_repository,
+            notification_service,
+            events,
+        }
     }
 
+    /// Follow a user, or ask to if their account is protected
     pub async fn follow(
         &self,
         follower_id: i32,
         following_id: i32,
-    ) -> Result<(), FollowServiceError> {
+    ) -> Result<FollowStatus, FollowServiceError> {
         // Rule 1: cannot follow yourself
         if follower_id == following_id {
             return Err(FollowServiceError::CannotFollowSelf);
         }
 
-        // Rule 2: prevent duplicate follow
+        // Rule 2: no follows between users where either blocked the other
+        let blocked = self
+            .block_repository
+            .is_blocked_either_way(follower_id, following_id)
+            .await
+            .map_err(|_| FollowServiceError::DatabaseError)?;
+
+        if blocked {
+            return Err(FollowServiceError::Blocked);
+        }
+
+        // Rule 3: prevent duplicate follow
         let already_following = self
             .repository
             .is_following(follower_id, following_id)
@@ -39,13 +83,35 @@ impl FollowService {
             return Err(FollowServiceError::AlreadyFollowing);
         }
 
-        // Perform follow
+        let settings = self
+            .user_repository
+            .settings(following_id)
+            .await
+            .map_err(|_| FollowServiceError::DatabaseError)?
+            .ok_or(FollowServiceError::UserNotFound)?;
+
+        // Rule 4: protected accounts approve their followers
+        if settings.protected {
+            let requested = self
+                .repository
+                .request(follower_id, following_id)
+                .await
+                .map_err(|_| FollowServiceError::DatabaseError)?;
+
+            if !requested {
+                return Err(FollowServiceError::AlreadyRequested);
+            }
+
+            return Ok(FollowStatus::Pending);
+        }
+
+        // Perform follow; the outbox relay announces it
         self.repository
             .follow(follower_id, following_id)
             .await
             .map_err(|_| FollowServiceError::DatabaseError)?;
 
-        Ok(())
+        Ok(FollowStatus::Following)
     }
 
     pub async fn unfollow(
@@ -61,6 +127,17 @@ impl FollowService {
             .map_err(|_| FollowServiceError::DatabaseError)?;
 
         if !is_following {
+            // Unfollowing a protected account before it approved withdraws the request
+            let withdrawn = self
+                .repository
+                .delete_request(follower_id, following_id)
+                .await
+                .map_err(|_| FollowServiceError::DatabaseError)?;
+
+            if withdrawn {
+                return Ok(());
+            }
+
             return Err(FollowServiceError::NotFollowing);
         }
 
@@ -69,6 +146,121 @@ impl FollowService {
             .await
             .map_err(|_| FollowServiceError::DatabaseError)?;
 
+        self.events
+            .publish(DomainEvent::Unfollowed {
+                follower_id,
+                following_id,
+            })
+            .await;
+
+        if let Err(e) = self
+            .notification_service
+            .withdraw_follow(follower_id, following_id)
+            .await
+        {
+            tracing::warn!(follower_id, following_id, error = ?e, "withdrawing follow notification failed");
+        }
+
+        Ok(())
+    }
+
+    /// Requests to follow `user_id` awaiting their approval
+    pub async fn pending_followers(
+        &self,
+        user_id: i32,
+    ) -> Result<Vec<PendingFollower>, FollowServiceError> {
+        self.repository
+            .pending_followers(user_id)
+            .await
+            .map_err(|_| FollowServiceError::DatabaseError)
+    }
+
+    /// Let `follower_id` follow `user_id`; the outbox relay announces it
+    pub async fn approve_request(
+        &self,
+        user_id: i32,
+        follower_id: i32,
+    ) -> Result<(), FollowServiceError> {
+        let approved = self
+            .repository
+            .approve_request(follower_id, user_id)
+            .await
+            .map_err(|_| FollowServiceError::DatabaseError)?;
+
+        if !approved {
+            return Err(FollowServiceError::RequestNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Turn a request down; the requester is not told
+    pub async fn deny_request(
+        &self,
+        user_id: i32,
+        follower_id: i32,
+    ) -> Result<(), FollowServiceError> {
+        let denied = self
+            .repository
+            .delete_request(follower_id, user_id)
+            .await
+            .map_err(|_| FollowServiceError::DatabaseError)?;
+
+        if !denied {
+            return Err(FollowServiceError::RequestNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Ids of everyone `follower_id` follows
+    pub async fn following_ids(&self, follower_id: i32) -> Result<Vec<i32>, FollowServiceError> {
+        self.repository
+            .following_ids(follower_id)
+            .await
+            .map_err(|_| FollowServiceError::DatabaseError)
+    }
+}
+
+/// Announce a committed follow and notify the followed user
+#[async_trait]
+impl OutboxHandler for FollowService {
+    fn name(&self) -> &'static str {
+        "follows"
+    }
+
+    async fn handle(&self, event: &OutboxEvent) -> Result<(), String> {
+        let OutboxEvent::Followed {
+            follower_id,
+            following_id,
+        } = *event
+        else {
+            return Ok(());
+        };
+
+        // Already undone before the relay got to it
+        let still_following = self
+            .repository
+            .is_following(follower_id, following_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !still_following {
+            return Ok(());
+        }
+
+        self.notification_service
+            .notify_follow(follower_id, following_id)
+            .await
+            .map_err(|e| format!("{e:?}"))?;
+
+        self.events
+            .publish(DomainEvent::Followed {
+                follower_id,
+                following_id,
+            })
+            .await;
+
         Ok(())
     }
 }
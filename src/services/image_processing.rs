@@ -0,0 +1,399 @@
+use std::io::Cursor;
+
+use image::codecs::gif::{GifDecoder, GifEncoder, Repeat};
+use image::codecs::jpeg::JpegEncoder;
+use image::error::{ImageFormatHint, UnsupportedError, UnsupportedErrorKind};
+use image::imageops::FilterType;
+use image::metadata::Orientation;
+use image::{AnimationDecoder, DynamicImage, ImageDecoder, ImageError, ImageFormat, ImageReader};
+
+/// JPEG quality used whenever we have to re-encode
+const JPEG_QUALITY: u8 = 85;
+
+/// PNG chunks that carry metadata rather than pixels
+const PNG_METADATA_CHUNKS: &[&[u8; 4]] = &[b"eXIf", b"tEXt", b"zTXt", b"iTXt", b"tIME"];
+
+/// A named rendition size; images are scaled to fit within `max_edge` pixels
+#[derive(Debug, Clone)]
+pub struct ThumbnailSize {
+    pub name: String,
+    pub max_edge: u32,
+}
+
+/// Parse `name:max_edge` pairs, e.g. `thumb:150,small:680`
+pub fn parse_thumbnail_sizes(spec: &str) -> Vec<ThumbnailSize> {
+    spec.split(',')
+        .filter_map(|entry| {
+            let (name, max_edge) = entry.trim().split_once(':')?;
+            let max_edge = max_edge.trim().parse().ok().filter(|edge| *edge > 0)?;
+
+            Some(ThumbnailSize {
+                name: name.trim().to_string(),
+                max_edge,
+            })
+        })
+        .filter(|size| !size.name.is_empty())
+        .collect()
+}
+
+/// An encoded image ready to be stored
+pub struct Rendition {
+    pub bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub content_type: &'static str,
+    pub extension: &'static str,
+}
+
+/// Remove EXIF and other metadata; an EXIF rotation is baked into the pixels
+/// first so the photo still displays the right way up
+pub fn strip_metadata(bytes: &[u8], format: ImageFormat) -> Result<Vec<u8>, ImageError> {
+    match format {
+        ImageFormat::Jpeg => {
+            let mut decoder =
+                ImageReader::with_format(Cursor::new(bytes), format).into_decoder()?;
+            let orientation = decoder.orientation()?;
+
+            if orientation == Orientation::NoTransforms
+                && let Some(stripped) = strip_jpeg_segments(bytes)
+            {
+                return Ok(stripped);
+            }
+
+            let mut image = DynamicImage::from_decoder(decoder)?;
+            image.apply_orientation(orientation);
+            Ok(encode(&image)?.bytes)
+        }
+        // If the chunks can't be walked, re-encode rather than risk keeping metadata
+        ImageFormat::Png => strip_png_chunks(bytes).map_or_else(|| reencode(bytes, format), Ok),
+        ImageFormat::WebP => strip_webp_chunks(bytes).map_or_else(|| reencode(bytes, format), Ok),
+        ImageFormat::Gif => reencode_gif(bytes),
+        _ => Err(ImageError::Unsupported(
+            UnsupportedError::from_format_and_kind(
+                ImageFormatHint::Exact(format),
+                UnsupportedErrorKind::Format(ImageFormatHint::Exact(format)),
+            ),
+        )),
+    }
+}
+
+/// Decode once and produce every size smaller than the original
+pub fn render_thumbnails(
+    bytes: &[u8],
+    sizes: &[ThumbnailSize],
+) -> Result<Vec<(ThumbnailSize, Rendition)>, ImageError> {
+    let image = image::load_from_memory(bytes)?;
+
+    sizes
+        .iter()
+        // Never upscale: clients fall back to the original instead
+        .filter(|size| image.width() > size.max_edge || image.height() > size.max_edge)
+        .map(|size| {
+            let thumbnail = image.resize(size.max_edge, size.max_edge, FilterType::Lanczos3);
+            Ok((size.clone(), encode(&thumbnail)?))
+        })
+        .collect()
+}
+
+/// PNG keeps transparency, everything else becomes a JPEG
+fn encode(image: &DynamicImage) -> Result<Rendition, ImageError> {
+    let mut bytes = Vec::new();
+
+    let (content_type, extension) = if image.color().has_alpha() {
+        image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
+        ("image/png", "png")
+    } else {
+        JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY).encode_image(&image.to_rgb8())?;
+        ("image/jpeg", "jpg")
+    };
+
+    Ok(Rendition {
+        bytes,
+        width: image.width(),
+        height: image.height(),
+        content_type,
+        extension,
+    })
+}
+
+/// Decode and write back in the same format, which keeps only the pixels
+fn reencode(bytes: &[u8], format: ImageFormat) -> Result<Vec<u8>, ImageError> {
+    let image = image::load_from_memory_with_format(bytes, format)?;
+
+    let mut out = Vec::new();
+    image.write_to(&mut Cursor::new(&mut out), format)?;
+    Ok(out)
+}
+
+/// Rebuild a GIF frame by frame; comment and application extensions are dropped
+fn reencode_gif(bytes: &[u8]) -> Result<Vec<u8>, ImageError> {
+    let frames = GifDecoder::new(Cursor::new(bytes))?
+        .into_frames()
+        .collect_frames()?;
+
+    let mut out = Vec::new();
+    {
+        let mut encoder = GifEncoder::new(&mut out);
+        encoder.set_repeat(Repeat::Infinite)?;
+        encoder.encode_frames(frames)?;
+    }
+    Ok(out)
+}
+
+/// Drop APP1 (EXIF/XMP), APP13 (IPTC) and comment segments
+fn strip_jpeg_segments(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut out = bytes.get(..2)?.to_vec();
+    let mut pos = 2;
+
+    loop {
+        if *bytes.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *bytes.get(pos + 1)?;
+
+        // Start of scan: the rest is entropy-coded image data
+        if marker == 0xDA {
+            out.extend_from_slice(&bytes[pos..]);
+            return Some(out);
+        }
+
+        let length = u16::from_be_bytes([*bytes.get(pos + 2)?, *bytes.get(pos + 3)?]) as usize;
+        let end = pos + 2 + length;
+        let segment = bytes.get(pos..end)?;
+
+        if !matches!(marker, 0xE1 | 0xED | 0xFE) {
+            out.extend_from_slice(segment);
+        }
+
+        pos = end;
+    }
+}
+
+fn strip_png_chunks(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut out = bytes.get(..8)?.to_vec();
+    let mut pos = 8;
+
+    while pos < bytes.len() {
+        let length = u32::from_be_bytes(bytes.get(pos..pos + 4)?.try_into().ok()?) as usize;
+        let kind = bytes.get(pos + 4..pos + 8)?;
+        // length + type + data + crc
+        let end = pos + 12 + length;
+        let chunk = bytes.get(pos..end)?;
+
+        if !PNG_METADATA_CHUNKS
+            .iter()
+            .any(|name| name.as_slice() == kind)
+        {
+            out.extend_from_slice(chunk);
+        }
+
+        pos = end;
+    }
+
+    Some(out)
+}
+
+fn strip_webp_chunks(bytes: &[u8]) -> Option<Vec<u8>> {
+    if bytes.get(..4)? != b"RIFF" || bytes.get(8..12)? != b"WEBP" {
+        return None;
+    }
+
+    let mut out = bytes[..12].to_vec();
+    let mut pos = 12;
+
+    while pos < bytes.len() {
+        let kind = bytes.get(pos..pos + 4)?;
+        let length = u32::from_le_bytes(bytes.get(pos + 4..pos + 8)?.try_into().ok()?) as usize;
+        // Chunks are padded to an even size
+        let end = (pos + 8 + length + (length & 1)).min(bytes.len());
+        let chunk = bytes.get(pos..end)?;
+
+        match kind {
+            b"EXIF" | b"XMP " => {}
+            b"VP8X" => {
+                let start = out.len();
+                out.extend_from_slice(chunk);
+                // Clear the "has EXIF" and "has XMP" feature flags
+                *out.get_mut(start + 8)? &= !0b0000_1100;
+            }
+            _ => out.extend_from_slice(chunk),
+        }
+
+        pos = end;
+    }
+
+    let riff_size = (out.len() - 8) as u32;
+    out[4..8].copy_from_slice(&riff_size.to_le_bytes());
+
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use image::{GenericImageView, RgbImage};
+
+    /// Big-endian EXIF block holding only an orientation tag
+    fn exif_with_orientation(orientation: u8) -> Vec<u8> {
+        let mut exif = b"Exif\0\0MM\0\x2a\0\0\0\x08\0\x01".to_vec();
+        exif.extend_from_slice(&[0x01, 0x12, 0, 3, 0, 0, 0, 1, 0, orientation, 0, 0]);
+        exif.extend_from_slice(&[0, 0, 0, 0]);
+        exif
+    }
+
+    fn jpeg(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY)
+            .encode_image(&RgbImage::new(width, height))
+            .unwrap();
+        bytes
+    }
+
+    /// Insert a segment straight after the SOI marker
+    fn with_jpeg_segment(jpeg: &[u8], marker: u8, data: &[u8]) -> Vec<u8> {
+        let mut out = jpeg[..2].to_vec();
+        out.extend_from_slice(&[0xFF, marker]);
+        out.extend_from_slice(&((data.len() + 2) as u16).to_be_bytes());
+        out.extend_from_slice(data);
+        out.extend_from_slice(&jpeg[2..]);
+        out
+    }
+
+    fn has_jpeg_marker(bytes: &[u8], marker: u8) -> bool {
+        bytes.windows(2).any(|pair| pair == [0xFF, marker])
+    }
+
+    fn png_chunk(kind: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+        chunk.extend_from_slice(kind);
+        chunk.extend_from_slice(data);
+        // The stripper never checks CRCs
+        chunk.extend_from_slice(&[0; 4]);
+        chunk
+    }
+
+    fn webp_chunk(kind: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut chunk = kind.to_vec();
+        chunk.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(data);
+        if data.len() % 2 == 1 {
+            chunk.push(0);
+        }
+        chunk
+    }
+
+    #[test]
+    fn jpeg_loses_exif_and_comments_but_keeps_pixels() {
+        let original = jpeg(4, 2);
+        let tagged = with_jpeg_segment(&original, 0xE1, &exif_with_orientation(1));
+        let tagged = with_jpeg_segment(&tagged, 0xFE, b"shot on my phone");
+
+        let stripped = strip_metadata(&tagged, ImageFormat::Jpeg).unwrap();
+
+        assert_eq!(stripped, original);
+        assert!(!has_jpeg_marker(&stripped, 0xE1));
+        assert!(!has_jpeg_marker(&stripped, 0xFE));
+    }
+
+    #[test]
+    fn jpeg_rotation_is_baked_into_the_pixels() {
+        let tagged = with_jpeg_segment(&jpeg(4, 2), 0xE1, &exif_with_orientation(6));
+
+        let stripped = strip_metadata(&tagged, ImageFormat::Jpeg).unwrap();
+        let image = image::load_from_memory_with_format(&stripped, ImageFormat::Jpeg).unwrap();
+
+        assert_eq!(image.dimensions(), (2, 4));
+        assert!(!has_jpeg_marker(&stripped, 0xE1));
+    }
+
+    #[test]
+    fn png_loses_text_and_exif_chunks() {
+        let mut original = Vec::new();
+        DynamicImage::new_rgb8(3, 3)
+            .write_to(&mut Cursor::new(&mut original), ImageFormat::Png)
+            .unwrap();
+
+        // IEND is always the last 12 bytes
+        let iend = original.len() - 12;
+        let mut tagged = original[..iend].to_vec();
+        tagged.extend(png_chunk(b"tEXt", b"Author\0someone"));
+        tagged.extend(png_chunk(b"eXIf", &exif_with_orientation(1)[6..]));
+        tagged.extend_from_slice(&original[iend..]);
+
+        let stripped = strip_metadata(&tagged, ImageFormat::Png).unwrap();
+
+        assert_eq!(stripped, original);
+    }
+
+    #[test]
+    fn webp_loses_exif_and_xmp_chunks_and_flags() {
+        let image_data = webp_chunk(b"VP8L", &[1, 2, 3]);
+
+        let mut tagged = b"RIFF\0\0\0\0WEBP".to_vec();
+        tagged.extend(webp_chunk(
+            b"VP8X",
+            &[0b0000_1100, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        ));
+        tagged.extend(&image_data);
+        tagged.extend(webp_chunk(b"EXIF", b"exif"));
+        tagged.extend(webp_chunk(b"XMP ", b"<xmp/>"));
+
+        let stripped = strip_metadata(&tagged, ImageFormat::WebP).unwrap();
+
+        let mut expected = b"RIFF\0\0\0\0WEBP".to_vec();
+        expected.extend(webp_chunk(b"VP8X", &[0; 10]));
+        expected.extend(&image_data);
+        let size = (expected.len() - 8) as u32;
+        expected[4..8].copy_from_slice(&size.to_le_bytes());
+
+        assert_eq!(stripped, expected);
+    }
+
+    #[test]
+    fn png_with_unwalkable_chunks_is_reencoded_without_metadata() {
+        let mut original = Vec::new();
+        DynamicImage::new_rgb8(3, 3)
+            .write_to(&mut Cursor::new(&mut original), ImageFormat::Png)
+            .unwrap();
+
+        let iend = original.len() - 12;
+        let mut tagged = original[..iend].to_vec();
+        tagged.extend(png_chunk(b"tEXt", b"Author\0someone"));
+        tagged.extend_from_slice(&original[iend..]);
+        // Trailing junk the decoder ignores but the chunk walker can't parse
+        tagged.extend_from_slice(b"\0\0");
+
+        let stripped = strip_metadata(&tagged, ImageFormat::Png).unwrap();
+
+        assert!(!stripped.windows(4).any(|window| window == b"tEXt"));
+        let image = image::load_from_memory_with_format(&stripped, ImageFormat::Png).unwrap();
+        assert_eq!(image.dimensions(), (3, 3));
+    }
+
+    #[test]
+    fn gif_loses_comment_extensions() {
+        let mut original = Vec::new();
+        GifEncoder::new(&mut original)
+            .encode_frame(image::Frame::new(image::RgbaImage::new(2, 2)))
+            .unwrap();
+
+        // A comment extension just before the trailer
+        let trailer = original.len() - 1;
+        let mut tagged = original[..trailer].to_vec();
+        tagged.extend_from_slice(b"\x21\xFE\x0Fshot on a phone\0");
+        tagged.extend_from_slice(&original[trailer..]);
+
+        let stripped = strip_metadata(&tagged, ImageFormat::Gif).unwrap();
+
+        assert!(!stripped.windows(5).any(|window| window == b"phone"));
+        let image = image::load_from_memory_with_format(&stripped, ImageFormat::Gif).unwrap();
+        assert_eq!(image.dimensions(), (2, 2));
+    }
+
+    #[test]
+    fn undecodable_input_is_rejected() {
+        let truncated = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR";
+        assert!(strip_metadata(truncated, ImageFormat::Png).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::models::job::Job;
+use crate::repositories::job_repository::JobRepository;
+
+/// Attempts before a job is moved to the dead-letter table
+const MAX_ATTEMPTS: i32 = 8;
+
+/// How long a worker may hold a job before another one retries it
+const LEASE_SECS: f64 = 300.0;
+
+/// Longest wait between two attempts
+const MAX_BACKOFF_SECS: f64 = 3600.0;
+
+#[derive(Debug)]
+pub enum JobQueueError {
+    InvalidPayload,
+    DatabaseError,
+}
+
+/// Runs jobs of one type. A job can run more than once, e.g. when a worker
+/// dies mid-job, so handlers must be idempotent.
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+    fn job_type(&self) -> &'static str;
+
+    /// Jobs of this type one instance runs at the same time
+    fn concurrency(&self) -> usize {
+        1
+    }
+
+    /// The error message is kept on the job for inspection
+    async fn run(&self, payload: serde_json::Value) -> Result<(), String>;
+}
+
+#[derive(Clone)]
+pub struct JobQueue {
+    repository: JobRepository,
+}
+
+impl JobQueue {
+    pub fn new(repository: JobRepository) -> Self {
+        Self { repository }
+    }
+
+    pub async fn enqueue<T: Serialize>(
+        &self,
+        job_type: &str,
+        payload: &T,
+    ) -> Result<i64, JobQueueError> {
+        let payload = serde_json::to_value(payload).map_err(|_| JobQueueError::InvalidPayload)?;
+
+        self.repository
+            .enqueue(job_type, &payload, MAX_ATTEMPTS)
+            .await
+            .map_err(|_| JobQueueError::DatabaseError)
+    }
+
+    /// Lease up to `limit` due jobs of one type
+    pub async fn claim(&self, job_type: &str, limit: i64) -> Result<Vec<Job>, JobQueueError> {
+        self.repository
+            .claim(job_type, limit, LEASE_SECS)
+            .await
+            .map_err(|_| JobQueueError::DatabaseError)
+    }
+
+    /// Record how a job went: done, retried with exponential backoff, or
+    /// dead-lettered once its attempts are used up
+    pub async fn finish(&self, job: &Job, result: Result<(), String>) -> Result<(), JobQueueError> {
+        let outcome = match result {
+            Ok(()) => self.repository.complete(job.id).await,
+            Err(error) if job.attempts >= job.max_attempts => {
+                tracing::warn!(
+                    job_id = job.id,
+                    job_type = job.job_type,
+                    error,
+                    "job dead-lettered"
+                );
+                self.repository.bury(job.id, &error).await
+            }
+            Err(error) => {
+                let delay_secs = 2f64.powi(job.attempts).min(MAX_BACKOFF_SECS);
+                self.repository
+                    .retry_later(job.id, &error, delay_secs)
+                    .await
+            }
+        };
+
+        outcome.map_err(|_| JobQueueError::DatabaseError)
+    }
+}
@@ -0,0 +1,324 @@
+use chrono::{DateTime, Utc};
+
+use crate::models::list::{List, ListRequest};
+use crate::models::tweet::TweetResponse;
+use crate::models::user::User;
+use crate::repositories::block_repository::BlockRepository;
+use crate::repositories::list_repository::ListRepository;
+use crate::repositories::tweet_repository::TweetRepository;
+use crate::repositories::user_repository::UserRepository;
+
+/// Longest list name, in characters
+const MAX_NAME_LENGTH: usize = 25;
+
+/// Longest list description, in characters
+const MAX_DESCRIPTION_LENGTH: usize = 100;
+
+/// Lists one user can own
+const MAX_LISTS: i64 = 1000;
+
+/// Accounts one list can hold
+const MAX_MEMBERS: i64 = 5000;
+
+#[derive(Debug)]
+pub enum ListServiceError {
+    EmptyName,
+    NameTooLong,
+    DescriptionTooLong,
+    TooManyLists,
+    /// No such list, or a private one the caller does not own
+    NotFound,
+    NotOwner,
+    UserNotFound,
+    Blocked,
+    AlreadyMember,
+    NotMember,
+    TooManyMembers,
+    CannotSubscribeOwnList,
+    AlreadySubscribed,
+    NotSubscribed,
+    DatabaseError,
+}
+
+#[derive(Clone)]
+pub struct ListService {
+    repository: ListRepository,
+    tweet_repository: TweetRepository,
+    user_repository: UserRepository,
+    block_repository: BlockRepository,
+}
+
+impl ListService {
+    pub fn new(
+        repository: ListRepository,
+        tweet_repository: TweetRepository,
+        user_repository: UserRepository,
+        block_repository: BlockRepository,
+    ) -> Self {
+        Self {
+            repository,
+            tweet_repository,
+            user_repository,
+            block_repository,
+        }
+    }
+
+    pub async fn create_list(
+        &self,
+        owner_id: i32,
+        request: ListRequest,
+    ) -> Result<List, ListServiceError> {
+        let (name, description) = Self::check_fields(&request)?;
+
+        let owned = self
+            .repository
+            .count_owned(owner_id)
+            .await
+            .map_err(|_| ListServiceError::DatabaseError)?;
+
+        if owned >= MAX_LISTS {
+            return Err(ListServiceError::TooManyLists);
+        }
+
+        self.repository
+            .create(owner_id, name, description, request.private)
+            .await
+            .map_err(|_| ListServiceError::DatabaseError)
+    }
+
+    pub async fn get_list(
+        &self,
+        id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<List, ListServiceError> {
+        self.repository
+            .find_visible(id, viewer_id)
+            .await
+            .map_err(|_| ListServiceError::DatabaseError)?
+            .ok_or(ListServiceError::NotFound)
+    }
+
+    /// The lists a user made that the viewer may see
+    pub async fn lists_of(
+        &self,
+        owner_id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<Vec<List>, ListServiceError> {
+        self.user_repository
+            .find_by_id(owner_id)
+            .await
+            .map_err(|_| ListServiceError::DatabaseError)?
+            .ok_or(ListServiceError::UserNotFound)?;
+
+        self.repository
+            .owned_by(owner_id, viewer_id)
+            .await
+            .map_err(|_| ListServiceError::DatabaseError)
+    }
+
+    pub async fn subscribed_lists(&self, user_id: i32) -> Result<Vec<List>, ListServiceError> {
+        self.repository
+            .subscribed_by(user_id)
+            .await
+            .map_err(|_| ListServiceError::DatabaseError)
+    }
+
+    pub async fn update_list(
+        &self,
+        id: i32,
+        owner_id: i32,
+        request: ListRequest,
+    ) -> Result<List, ListServiceError> {
+        let (name, description) = Self::check_fields(&request)?;
+        self.owned_list(id, owner_id).await?;
+
+        self.repository
+            .update(id, owner_id, name, description, request.private)
+            .await
+            .map_err(|_| ListServiceError::DatabaseError)?
+            .ok_or(ListServiceError::NotFound)
+    }
+
+    pub async fn delete_list(&self, id: i32, owner_id: i32) -> Result<(), ListServiceError> {
+        self.owned_list(id, owner_id).await?;
+
+        let deleted = self
+            .repository
+            .delete(id, owner_id)
+            .await
+            .map_err(|_| ListServiceError::DatabaseError)?;
+
+        if !deleted {
+            return Err(ListServiceError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    pub async fn members(
+        &self,
+        id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<Vec<User>, ListServiceError> {
+        self.get_list(id, viewer_id).await?;
+
+        self.repository
+            .members(id)
+            .await
+            .map_err(|_| ListServiceError::DatabaseError)
+    }
+
+    /// Accounts that blocked the owner, or that the owner blocked, cannot be
+    /// added
+    pub async fn add_member(
+        &self,
+        id: i32,
+        owner_id: i32,
+        user_id: i32,
+    ) -> Result<(), ListServiceError> {
+        let list = self.owned_list(id, owner_id).await?;
+
+        self.user_repository
+            .find_by_id(user_id)
+            .await
+            .map_err(|_| ListServiceError::DatabaseError)?
+            .ok_or(ListServiceError::UserNotFound)?;
+
+        if self
+            .block_repository
+            .is_blocked_either_way(owner_id, user_id)
+            .await
+            .map_err(|_| ListServiceError::DatabaseError)?
+        {
+            return Err(ListServiceError::Blocked);
+        }
+
+        if list.member_count >= MAX_MEMBERS {
+            return Err(ListServiceError::TooManyMembers);
+        }
+
+        let added = self
+            .repository
+            .add_member(id, user_id)
+            .await
+            .map_err(|_| ListServiceError::DatabaseError)?;
+
+        if !added {
+            return Err(ListServiceError::AlreadyMember);
+        }
+
+        Ok(())
+    }
+
+    pub async fn remove_member(
+        &self,
+        id: i32,
+        owner_id: i32,
+        user_id: i32,
+    ) -> Result<(), ListServiceError> {
+        self.owned_list(id, owner_id).await?;
+
+        let removed = self
+            .repository
+            .remove_member(id, user_id)
+            .await
+            .map_err(|_| ListServiceError::DatabaseError)?;
+
+        if !removed {
+            return Err(ListServiceError::NotMember);
+        }
+
+        Ok(())
+    }
+
+    pub async fn subscribe(&self, id: i32, user_id: i32) -> Result<(), ListServiceError> {
+        let list = self.get_list(id, Some(user_id)).await?;
+
+        if list.owner_id == user_id {
+            return Err(ListServiceError::CannotSubscribeOwnList);
+        }
+
+        let subscribed = self
+            .repository
+            .subscribe(id, user_id)
+            .await
+            .map_err(|_| ListServiceError::DatabaseError)?;
+
+        if !subscribed {
+            return Err(ListServiceError::AlreadySubscribed);
+        }
+
+        Ok(())
+    }
+
+    pub async fn unsubscribe(&self, id: i32, user_id: i32) -> Result<(), ListServiceError> {
+        let unsubscribed = self
+            .repository
+            .unsubscribe(id, user_id)
+            .await
+            .map_err(|_| ListServiceError::DatabaseError)?;
+
+        if !unsubscribed {
+            return Err(ListServiceError::NotSubscribed);
+        }
+
+        Ok(())
+    }
+
+    /// Tweets by the list's members, newest first, with the same cursor as
+    /// the home timeline
+    pub async fn timeline(
+        &self,
+        id: i32,
+        limit: i64,
+        before: Option<(DateTime<Utc>, i32)>,
+        viewer_id: Option<i32>,
+    ) -> Result<(Vec<TweetResponse>, Option<String>), ListServiceError> {
+        self.get_list(id, viewer_id).await?;
+
+        let rows = self
+            .tweet_repository
+            .list_timeline_before(id, limit, before, viewer_id)
+            .await
+            .map_err(|_| ListServiceError::DatabaseError)?;
+
+        let next_cursor = rows
+            .last()
+            .map(|(tweet, ts)| format!("{}|{}", ts.to_rfc3339(), tweet.id));
+
+        let tweets = rows.into_iter().map(|(t, _)| t).collect();
+
+        Ok((tweets, next_cursor))
+    }
+
+    /// A list the caller may change; others' public lists are `NotOwner`
+    /// while their private ones stay `NotFound`
+    async fn owned_list(&self, id: i32, owner_id: i32) -> Result<List, ListServiceError> {
+        let list = self.get_list(id, Some(owner_id)).await?;
+
+        if list.owner_id != owner_id {
+            return Err(ListServiceError::NotOwner);
+        }
+
+        Ok(list)
+    }
+
+    fn check_fields(request: &ListRequest) -> Result<(&str, &str), ListServiceError> {
+        let name = request.name.trim();
+        let description = request.description.trim();
+
+        if name.is_empty() {
+            return Err(ListServiceError::EmptyName);
+        }
+
+        if name.chars().count() > MAX_NAME_LENGTH {
+            return Err(ListServiceError::NameTooLong);
+        }
+
+        if description.chars().count() > MAX_DESCRIPTION_LENGTH {
+            return Err(ListServiceError::DescriptionTooLong);
+        }
+
+        Ok((name, description))
+    }
+}
@@ -0,0 +1,252 @@
+use std::io::Cursor;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use image::{ImageFormat, ImageReader};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::models::media::{Media, NewMedia, NewMediaVariant};
+use crate::repositories::media_repository::MediaRepository;
+use crate::services::image_processing::{self, ThumbnailSize};
+use crate::services::job_queue::{JobHandler, JobQueue};
+use crate::storage::BlobStore;
+
+/// Largest accepted upload, in bytes
+pub const MAX_MEDIA_BYTES: usize = 5 * 1024 * 1024;
+
+/// Longest accepted alt text, in characters
+const MAX_ALT_TEXT_LENGTH: usize = 1000;
+
+const THUMBNAIL_JOB: &str = "media.thumbnails";
+
+/// Thumbnail jobs are CPU-bound, so only a few run at once
+const THUMBNAIL_CONCURRENCY: usize = 2;
+
+#[derive(Serialize, Deserialize)]
+struct ThumbnailJob {
+    media_id: i32,
+    blob_key: String,
+}
+
+#[derive(Debug)]
+pub enum MediaServiceError {
+    EmptyFile,
+    FileTooLarge,
+    UnsupportedType,
+    InvalidImage,
+    AltTextTooLong,
+    NotFound,
+    StorageError,
+    DatabaseError,
+}
+
+#[derive(Clone)]
+pub struct MediaService {
+    repository: MediaRepository,
+    store: Arc<dyn BlobStore>,
+    thumbnail_sizes: Arc<Vec<ThumbnailSize>>,
+    job_queue: JobQueue,
+}
+
+impl MediaService {
+    pub fn new(
+        repository: MediaRepository,
+        store: Arc<dyn BlobStore>,
+        thumbnail_sizes: Vec<ThumbnailSize>,
+        job_queue: JobQueue,
+    ) -> Self {
+        Self {
+            repository,
+            store,
+            thumbnail_sizes: Arc::new(thumbnail_sizes),
+            job_queue,
+        }
+    }
+
+    pub async fn upload(
+        &self,
+        owner_id: i32,
+        bytes: &[u8],
+        alt_text: Option<String>,
+    ) -> Result<Media, MediaServiceError> {
+        if bytes.is_empty() {
+            return Err(MediaServiceError::EmptyFile);
+        }
+
+        if bytes.len() > MAX_MEDIA_BYTES {
+            return Err(MediaServiceError::FileTooLarge);
+        }
+
+        let alt_text = alt_text
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty());
+
+        if alt_text
+            .as_ref()
+            .is_some_and(|text| text.chars().count() > MAX_ALT_TEXT_LENGTH)
+        {
+            return Err(MediaServiceError::AltTextTooLong);
+        }
+
+        // Trust the bytes, not the client-supplied content type
+        let format = image::guess_format(bytes).map_err(|_| MediaServiceError::UnsupportedType)?;
+        let (content_type, extension) = match format {
+            ImageFormat::Png => ("image/png", "png"),
+            ImageFormat::Jpeg => ("image/jpeg", "jpg"),
+            ImageFormat::Gif => ("image/gif", "gif"),
+            ImageFormat::WebP => ("image/webp", "webp"),
+            _ => return Err(MediaServiceError::UnsupportedType),
+        };
+
+        ImageReader::with_format(Cursor::new(bytes), format)
+            .into_dimensions()
+            .map_err(|_| MediaServiceError::InvalidImage)?;
+
+        // Never store location or camera details; this may re-encode the image
+        let upload = bytes.to_vec();
+        let bytes =
+            tokio::task::spawn_blocking(move || image_processing::strip_metadata(&upload, format))
+                .await
+                .map_err(|_| MediaServiceError::InvalidImage)?
+                .map_err(|_| MediaServiceError::InvalidImage)?;
+
+        // Baking in an EXIF rotation can swap width and height
+        let (width, height) = ImageReader::with_format(Cursor::new(&bytes), format)
+            .into_dimensions()
+            .map_err(|_| MediaServiceError::InvalidImage)?;
+
+        // Content-addressed keys: identical uploads share one blob
+        let hash = hex::encode(Sha256::digest(&bytes));
+        let blob_key = format!("{}.{}", hash, extension);
+
+        self.store
+            .put(&blob_key, &bytes)
+            .await
+            .map_err(|_| MediaServiceError::StorageError)?;
+
+        let media = self
+            .repository
+            .create(NewMedia {
+                owner_id,
+                url: self.store.url(&blob_key),
+                blob_key: blob_key.clone(),
+                content_type: content_type.to_string(),
+                size_bytes: bytes.len() as i32,
+                width: width as i32,
+                height: height as i32,
+                alt_text,
+            })
+            .await
+            .map_err(|_| MediaServiceError::DatabaseError)?;
+
+        // Thumbnails are not needed to answer the upload, so build them later;
+        // clients fall back to the original until they exist
+        if let Err(e) = self
+            .job_queue
+            .enqueue(
+                THUMBNAIL_JOB,
+                &ThumbnailJob {
+                    media_id: media.id,
+                    blob_key,
+                },
+            )
+            .await
+        {
+            tracing::warn!(media_id = media.id, error = ?e, "queueing thumbnails failed");
+        }
+
+        Ok(media)
+    }
+
+    /// Decode the original and store every configured thumbnail size
+    async fn generate_variants(
+        &self,
+        media_id: i32,
+        hash: &str,
+        bytes: Vec<u8>,
+    ) -> Result<(), MediaServiceError> {
+        let sizes = self.thumbnail_sizes.clone();
+        let renditions = tokio::task::spawn_blocking(move || {
+            image_processing::render_thumbnails(&bytes, &sizes)
+        })
+        .await
+        .map_err(|_| MediaServiceError::InvalidImage)?
+        .map_err(|_| MediaServiceError::InvalidImage)?;
+
+        for (size, rendition) in renditions {
+            let blob_key = format!("{}_{}.{}", hash, size.name, rendition.extension);
+
+            self.store
+                .put(&blob_key, &rendition.bytes)
+                .await
+                .map_err(|_| MediaServiceError::StorageError)?;
+
+            self.repository
+                .upsert_variant(NewMediaVariant {
+                    media_id,
+                    name: size.name,
+                    url: self.store.url(&blob_key),
+                    blob_key,
+                    content_type: rendition.content_type.to_string(),
+                    width: rendition.width as i32,
+                    height: rendition.height as i32,
+                })
+                .await
+                .map_err(|_| MediaServiceError::DatabaseError)?;
+        }
+
+        Ok(())
+    }
+
+    /// Raw bytes and content type of a stored blob
+    pub async fn open(&self, blob_key: &str) -> Result<(Vec<u8>, String), MediaServiceError> {
+        let content_type = self
+            .repository
+            .content_type_of(blob_key)
+            .await
+            .map_err(|_| MediaServiceError::DatabaseError)?
+            .ok_or(MediaServiceError::NotFound)?;
+
+        let bytes = self
+            .store
+            .get(blob_key)
+            .await
+            .map_err(|_| MediaServiceError::StorageError)?
+            .ok_or(MediaServiceError::NotFound)?;
+
+        Ok((bytes, content_type))
+    }
+}
+
+#[async_trait]
+impl JobHandler for MediaService {
+    fn job_type(&self) -> &'static str {
+        THUMBNAIL_JOB
+    }
+
+    fn concurrency(&self) -> usize {
+        THUMBNAIL_CONCURRENCY
+    }
+
+    async fn run(&self, payload: serde_json::Value) -> Result<(), String> {
+        let job: ThumbnailJob = serde_json::from_value(payload).map_err(|e| e.to_string())?;
+
+        let bytes = self
+            .store
+            .get(&job.blob_key)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("original is missing from the blob store")?;
+
+        // Originals are stored under their content hash
+        let hash = job
+            .blob_key
+            .split_once('.')
+            .map_or(job.blob_key.as_str(), |(hash, _)| hash);
+
+        self.generate_variants(job.media_id, hash, bytes)
+            .await
+            .map_err(|e| format!("{e:?}"))
+    }
+}
@@ -1,3 +1,20 @@
+pub mod block_service;
+pub mod direct_message_service;
+pub mod draft_service;
+pub mod feed_service;
 pub mod follow_service;
+pub mod image_processing;
+pub mod job_queue;
+pub mod list_service;
+pub mod media_service;
+pub mod mute_service;
+pub mod notification_service;
+pub mod outbox_service;
+pub mod ranking;
+pub mod search_service;
+pub mod suggestion_service;
+pub mod trend_service;
 pub mod tweet_service;
+pub mod tweet_text;
 pub mod user_service;
+pub mod webhook_service;
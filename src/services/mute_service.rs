@@ -0,0 +1,223 @@
+use chrono::Utc;
+
+use crate::models::mute::{CreateKeywordMuteRequest, KeywordMute};
+use crate::models::user::User;
+use crate::repositories::mute_repository::MuteRepository;
+use crate::repositories::tweet_repository::TweetRepository;
+use crate::repositories::user_repository::UserRepository;
+use crate::services::tweet_text;
+
+/// Longest muted phrase, in characters
+const MAX_KEYWORD_LENGTH: usize = 100;
+
+/// Active keyword mutes one user can have
+const MAX_KEYWORD_MUTES: usize = 200;
+
+#[derive(Debug)]
+pub enum MuteServiceError {
+    CannotMuteSelf,
+    UserNotFound,
+    TweetNotFound,
+    AlreadyMuted,
+    NotMuted,
+    EmptyKeyword,
+    KeywordTooLong,
+    TooManyKeywords,
+    ExpiryInPast,
+    DatabaseError,
+}
+
+/// Mutes only change what the muter sees; the muted party is never told
+#[derive(Clone)]
+pub struct MuteService {
+    repository: MuteRepository,
+    user_repository: UserRepository,
+    tweet_repository: TweetRepository,
+}
+
+impl MuteService {
+    pub fn new(
+        repository: MuteRepository,
+        user_repository: UserRepository,
+        tweet_repository: TweetRepository,
+    ) -> Self {
+        Self {
+            repository,
+            user_repository,
+            tweet_repository,
+        }
+    }
+
+    pub async fn mute_user(&self, user_id: i32, muted_id: i32) -> Result<(), MuteServiceError> {
+        if user_id == muted_id {
+            return Err(MuteServiceError::CannotMuteSelf);
+        }
+
+        self.user_repository
+            .find_by_id(muted_id)
+            .await
+            .map_err(|_| MuteServiceError::DatabaseError)?
+            .ok_or(MuteServiceError::UserNotFound)?;
+
+        let muted = self
+            .repository
+            .mute_user(user_id, muted_id)
+            .await
+            .map_err(|_| MuteServiceError::DatabaseError)?;
+
+        if !muted {
+            return Err(MuteServiceError::AlreadyMuted);
+        }
+
+        Ok(())
+    }
+
+    pub async fn unmute_user(&self, user_id: i32, muted_id: i32) -> Result<(), MuteServiceError> {
+        let unmuted = self
+            .repository
+            .unmute_user(user_id, muted_id)
+            .await
+            .map_err(|_| MuteServiceError::DatabaseError)?;
+
+        if !unmuted {
+            return Err(MuteServiceError::NotMuted);
+        }
+
+        Ok(())
+    }
+
+    pub async fn muted_users(&self, user_id: i32) -> Result<Vec<User>, MuteServiceError> {
+        self.repository
+            .muted_users(user_id)
+            .await
+            .map_err(|_| MuteServiceError::DatabaseError)
+    }
+
+    /// Mute a word or phrase, for good or until `expires_at`
+    pub async fn mute_keyword(
+        &self,
+        user_id: i32,
+        request: CreateKeywordMuteRequest,
+    ) -> Result<KeywordMute, MuteServiceError> {
+        let phrase = Self::normalize_keyword(&request.phrase);
+
+        if phrase.is_empty() {
+            return Err(MuteServiceError::EmptyKeyword);
+        }
+
+        if phrase.chars().count() > MAX_KEYWORD_LENGTH {
+            return Err(MuteServiceError::KeywordTooLong);
+        }
+
+        if request.expires_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(MuteServiceError::ExpiryInPast);
+        }
+
+        let active = self
+            .repository
+            .keywords(user_id)
+            .await
+            .map_err(|_| MuteServiceError::DatabaseError)?;
+
+        if active.len() >= MAX_KEYWORD_MUTES {
+            return Err(MuteServiceError::TooManyKeywords);
+        }
+
+        self.repository
+            .add_keyword(user_id, &phrase, request.expires_at)
+            .await
+            .map_err(|_| MuteServiceError::DatabaseError)?
+            .ok_or(MuteServiceError::AlreadyMuted)
+    }
+
+    pub async fn muted_keywords(&self, user_id: i32) -> Result<Vec<KeywordMute>, MuteServiceError> {
+        self.repository
+            .keywords(user_id)
+            .await
+            .map_err(|_| MuteServiceError::DatabaseError)
+    }
+
+    pub async fn unmute_keyword(&self, user_id: i32, id: i32) -> Result<(), MuteServiceError> {
+        let removed = self
+            .repository
+            .remove_keyword(user_id, id)
+            .await
+            .map_err(|_| MuteServiceError::DatabaseError)?;
+
+        if !removed {
+            return Err(MuteServiceError::NotMuted);
+        }
+
+        Ok(())
+    }
+
+    /// Mute the whole thread `tweet_id` belongs to
+    pub async fn mute_conversation(
+        &self,
+        user_id: i32,
+        tweet_id: i32,
+    ) -> Result<(), MuteServiceError> {
+        let conversation_id = self.conversation_of(tweet_id).await?;
+
+        let muted = self
+            .repository
+            .mute_conversation(user_id, conversation_id)
+            .await
+            .map_err(|_| MuteServiceError::DatabaseError)?;
+
+        if !muted {
+            return Err(MuteServiceError::AlreadyMuted);
+        }
+
+        Ok(())
+    }
+
+    pub async fn unmute_conversation(
+        &self,
+        user_id: i32,
+        tweet_id: i32,
+    ) -> Result<(), MuteServiceError> {
+        let conversation_id = self.conversation_of(tweet_id).await?;
+
+        let unmuted = self
+            .repository
+            .unmute_conversation(user_id, conversation_id)
+            .await
+            .map_err(|_| MuteServiceError::DatabaseError)?;
+
+        if !unmuted {
+            return Err(MuteServiceError::NotMuted);
+        }
+
+        Ok(())
+    }
+
+    /// Whether a tweet pushed live should be kept from the viewer
+    pub async fn hides_tweet(
+        &self,
+        viewer_id: i32,
+        tweet_id: u64,
+    ) -> Result<bool, MuteServiceError> {
+        self.repository
+            .hides_tweet(viewer_id, tweet_id as i32)
+            .await
+            .map_err(|_| MuteServiceError::DatabaseError)
+    }
+
+    async fn conversation_of(&self, tweet_id: i32) -> Result<i32, MuteServiceError> {
+        self.tweet_repository
+            .conversation_of(tweet_id)
+            .await
+            .map_err(|_| MuteServiceError::DatabaseError)?
+            .ok_or(MuteServiceError::TweetNotFound)
+    }
+
+    /// NFC, lowercase and single spaces, so the same phrase is stored once
+    fn normalize_keyword(phrase: &str) -> String {
+        tweet_text::normalize(phrase)
+            .to_lowercase()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
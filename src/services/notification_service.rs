@@ -0,0 +1,346 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use crate::events::{DomainEvent, EventBus, NotificationEvent};
+use crate::models::notification::{
+    NotificationActor, NotificationGroup, NotificationGroupRow, NotificationKind,
+};
+use crate::repositories::block_repository::BlockRepository;
+use crate::repositories::mute_repository::MuteRepository;
+use crate::repositories::notification_repository::NotificationRepository;
+use crate::repositories::tweet_repository::TweetRepository;
+use crate::repositories::user_repository::UserRepository;
+use crate::services::tweet_text;
+
+/// Mentions beyond this many in one tweet do not notify anyone
+const MAX_MENTION_NOTIFICATIONS: usize = 10;
+
+#[derive(Debug)]
+pub enum NotificationServiceError {
+    DatabaseError,
+}
+
+#[derive(Clone)]
+pub struct NotificationService {
+    repository: NotificationRepository,
+    user_repository: UserRepository,
+    block_repository: BlockRepository,
+    mute_repository: MuteRepository,
+    tweet_repository: TweetRepository,
+    events: Arc<dyn EventBus>,
+}
+
+impl NotificationService {
+    pub fn new(
+        repository: NotificationRepository,
+        user_repository: UserRepository,
+        block_repository: BlockRepository,
+        mute_repository: MuteRepository,
+        tweet_repository: TweetRepository,
+        events: Arc<dyn EventBus>,
+    ) -> Self {
+        Self {
+            repository,
+            user_repository,
+            block_repository,
+            mute_repository,
+            tweet_repository,
+            events,
+        }
+    }
+
+    /// New followers of the same day are grouped together
+    pub async fn notify_follow(
+        &self,
+        follower_id: i32,
+        following_id: i32,
+    ) -> Result<(), NotificationServiceError> {
+        let group_key = format!("follow:{}", Utc::now().date_naive());
+
+        self.create(
+            following_id,
+            follower_id,
+            NotificationKind::Follow,
+            None,
+            &group_key,
+        )
+        .await
+    }
+
+    pub async fn withdraw_follow(
+        &self,
+        follower_id: i32,
+        following_id: i32,
+    ) -> Result<(), NotificationServiceError> {
+        self.repository
+            .delete(following_id, follower_id, NotificationKind::Follow, None)
+            .await
+            .map_err(|_| NotificationServiceError::DatabaseError)
+    }
+
+    pub async fn notify_like(
+        &self,
+        user_id: i32,
+        author_id: i32,
+        tweet_id: i32,
+    ) -> Result<(), NotificationServiceError> {
+        if user_id == author_id {
+            return Ok(());
+        }
+
+        self.create(
+            author_id,
+            user_id,
+            NotificationKind::Like,
+            Some(tweet_id),
+            &format!("like:{tweet_id}"),
+        )
+        .await
+    }
+
+    pub async fn withdraw_like(
+        &self,
+        user_id: i32,
+        author_id: i32,
+        tweet_id: i32,
+    ) -> Result<(), NotificationServiceError> {
+        self.repository
+            .delete(author_id, user_id, NotificationKind::Like, Some(tweet_id))
+            .await
+            .map_err(|_| NotificationServiceError::DatabaseError)
+    }
+
+    /// Notify the replied-to author and everyone mentioned; nobody gets both
+    pub async fn notify_tweet(
+        &self,
+        author_id: i32,
+        tweet_id: i32,
+        content: &str,
+        reply_to_author: Option<i32>,
+    ) -> Result<(), NotificationServiceError> {
+        // Nobody hears from, or about, someone they blocked or were blocked by
+        let blocked = self
+            .block_repository
+            .blocked_either_way(author_id)
+            .await
+            .map_err(|_| NotificationServiceError::DatabaseError)?;
+
+        let reply_recipient = reply_to_author.filter(|id| *id != author_id);
+
+        if let Some(recipient_id) = reply_recipient
+            && !blocked.contains(&recipient_id)
+            && !self.is_protected_from(tweet_id, recipient_id).await?
+        {
+            self.create(
+                recipient_id,
+                author_id,
+                NotificationKind::Reply,
+                Some(tweet_id),
+                &format!("reply:{tweet_id}"),
+            )
+            .await?;
+        }
+
+        let mut mentions = tweet_text::extract_mentions(content);
+        if mentions.is_empty() {
+            return Ok(());
+        }
+        mentions.truncate(MAX_MENTION_NOTIFICATIONS);
+
+        let mentioned = self
+            .user_repository
+            .find_by_usernames(&mentions)
+            .await
+            .map_err(|_| NotificationServiceError::DatabaseError)?;
+
+        for user in mentioned {
+            if user.id == author_id
+                || Some(user.id) == reply_recipient
+                || blocked.contains(&user.id)
+                // A protected tweet only reaches the author's approved followers
+                || self.is_protected_from(tweet_id, user.id).await?
+            {
+                continue;
+            }
+
+            self.create(
+                user.id,
+                author_id,
+                NotificationKind::Mention,
+                Some(tweet_id),
+                &format!("mention:{tweet_id}"),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn is_protected_from(
+        &self,
+        tweet_id: i32,
+        user_id: i32,
+    ) -> Result<bool, NotificationServiceError> {
+        self.tweet_repository
+            .is_protected_from(tweet_id, Some(user_id))
+            .await
+            .map_err(|_| NotificationServiceError::DatabaseError)
+    }
+
+    /// A page of grouped notifications and the cursor for the next one
+    pub async fn list(
+        &self,
+        user_id: i32,
+        before: Option<i64>,
+        limit: i64,
+    ) -> Result<(Vec<NotificationGroup>, Option<String>), NotificationServiceError> {
+        let rows = self
+            .repository
+            .groups_before(user_id, before, limit)
+            .await
+            .map_err(|_| NotificationServiceError::DatabaseError)?;
+
+        let watermark = self
+            .repository
+            .watermark(user_id)
+            .await
+            .map_err(|_| NotificationServiceError::DatabaseError)?;
+
+        let actor_ids: Vec<i32> = rows.iter().flat_map(|row| row.actor_ids.clone()).collect();
+        let usernames: HashMap<i32, String> = self
+            .user_repository
+            .find_by_ids(&actor_ids)
+            .await
+            .map_err(|_| NotificationServiceError::DatabaseError)?
+            .into_iter()
+            .map(|user| (user.id, user.username))
+            .collect();
+
+        let next_cursor = rows.last().map(|row| row.latest_id.to_string());
+
+        let groups = rows
+            .into_iter()
+            .filter_map(|row| Self::to_group(row, &usernames, watermark))
+            .collect();
+
+        Ok((groups, next_cursor))
+    }
+
+    pub async fn mark_read(
+        &self,
+        user_id: i32,
+        up_to: i64,
+    ) -> Result<i64, NotificationServiceError> {
+        self.repository
+            .advance_watermark(user_id, up_to)
+            .await
+            .map_err(|_| NotificationServiceError::DatabaseError)
+    }
+
+    pub async fn unread_count(&self, user_id: i32) -> Result<i64, NotificationServiceError> {
+        self.repository
+            .unread_count(user_id)
+            .await
+            .map_err(|_| NotificationServiceError::DatabaseError)
+    }
+
+    /// Store a notification and push it to the recipient if they are connected
+    /// and have not muted it
+    async fn create(
+        &self,
+        recipient_id: i32,
+        actor_id: i32,
+        kind: NotificationKind,
+        tweet_id: Option<i32>,
+        group_key: &str,
+    ) -> Result<(), NotificationServiceError> {
+        let created = self
+            .repository
+            .create(recipient_id, actor_id, kind, tweet_id, group_key)
+            .await
+            .map_err(|_| NotificationServiceError::DatabaseError)?;
+
+        if !created {
+            return Ok(());
+        }
+
+        // Stored all the same, so it shows up again if the mute is lifted
+        let muted = self
+            .mute_repository
+            .hides_notification(recipient_id, actor_id, tweet_id)
+            .await
+            .map_err(|_| NotificationServiceError::DatabaseError)?;
+
+        if muted {
+            return Ok(());
+        }
+
+        self.events
+            .publish(DomainEvent::Notification(NotificationEvent {
+                recipient_id,
+                kind,
+                actor_id,
+                tweet_id,
+            }))
+            .await;
+
+        Ok(())
+    }
+
+    fn to_group(
+        row: NotificationGroupRow,
+        usernames: &HashMap<i32, String>,
+        watermark: i64,
+    ) -> Option<NotificationGroup> {
+        let kind = NotificationKind::parse(&row.kind)?;
+
+        let actors: Vec<NotificationActor> = row
+            .actor_ids
+            .iter()
+            .filter_map(|id| {
+                usernames.get(id).map(|username| NotificationActor {
+                    id: *id,
+                    username: username.clone(),
+                })
+            })
+            .collect();
+
+        let summary = Self::summary(kind, &actors, row.actor_count);
+
+        Some(NotificationGroup {
+            kind,
+            tweet_id: row.tweet_id,
+            actors,
+            actor_count: row.actor_count,
+            summary,
+            latest_id: row.latest_id,
+            latest_at: row.latest_at,
+            unread: row.latest_id > watermark,
+        })
+    }
+
+    /// "alice", "alice and bob" or "alice and 3 others", then the action
+    fn summary(kind: NotificationKind, actors: &[NotificationActor], actor_count: i64) -> String {
+        let first = actors
+            .first()
+            .map(|actor| actor.username.as_str())
+            .unwrap_or("Someone");
+
+        let who = match (actor_count, actors.get(1)) {
+            (2, Some(second)) => format!("{} and {}", first, second.username),
+            (n, _) if n > 2 => format!("{} and {} others", first, n - 1),
+            _ => first.to_string(),
+        };
+
+        let action = match kind {
+            NotificationKind::Follow => "followed you",
+            NotificationKind::Mention => "mentioned you",
+            NotificationKind::Reply => "replied to your tweet",
+            NotificationKind::Like => "liked your tweet",
+        };
+
+        format!("{} {}", who, action)
+    }
+}
@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::models::outbox::OutboxEvent;
+use crate::repositories::outbox_repository::OutboxRepository;
+
+/// Events relayed per worker tick
+const RELAY_BATCH_SIZE: i64 = 100;
+
+/// How long an instance may hold an event before another one retries it
+const CLAIM_LEASE_SECS: f64 = 60.0;
+
+/// Attempts before an event is abandoned
+const MAX_ATTEMPTS: i32 = 10;
+
+/// Longest wait between two attempts
+const MAX_BACKOFF_SECS: f64 = 3600.0;
+
+/// How long processed events are kept before they are deleted
+const RETENTION_SECS: f64 = 86400.0;
+
+#[derive(Debug)]
+pub enum OutboxServiceError {
+    DatabaseError,
+}
+
+/// Carries out the side effects of committed writes. A handler that succeeded
+/// is not run again for the same event when another one fails, but a retry
+/// can still follow a crash or a failure part way through the handler
+/// itself, so handlers must be idempotent.
+#[async_trait]
+pub trait OutboxHandler: Send + Sync {
+    /// Recorded on the outbox row once the handler succeeded; must stay the
+    /// same across releases
+    fn name(&self) -> &'static str;
+
+    /// The error message is kept on the outbox row for inspection
+    async fn handle(&self, event: &OutboxEvent) -> Result<(), String>;
+}
+
+#[derive(Clone)]
+pub struct OutboxService {
+    repository: OutboxRepository,
+    handlers: Vec<Arc<dyn OutboxHandler>>,
+}
+
+impl OutboxService {
+    pub fn new(repository: OutboxRepository, handlers: Vec<Arc<dyn OutboxHandler>>) -> Self {
+        Self {
+            repository,
+            handlers,
+        }
+    }
+
+    /// Deliver one batch of pending events; returns how many were claimed
+    pub async fn relay_batch(&self) -> Result<usize, OutboxServiceError> {
+        let messages = self
+            .repository
+            .claim_batch(RELAY_BATCH_SIZE, CLAIM_LEASE_SECS)
+            .await
+            .map_err(|_| OutboxServiceError::DatabaseError)?;
+
+        let claimed = messages.len();
+
+        for message in messages {
+            let mut failure = None;
+            for handler in &self.handlers {
+                let name = handler.name();
+                if message.completed_handlers.iter().any(|done| done == name) {
+                    continue;
+                }
+
+                match handler.handle(&message.event).await {
+                    Ok(()) => self
+                        .repository
+                        .mark_handled(message.id, name)
+                        .await
+                        .map_err(|_| OutboxServiceError::DatabaseError)?,
+                    Err(e) => {
+                        failure.get_or_insert(format!("{name}: {e}"));
+                    }
+                }
+            }
+
+            let Some(error) = failure else {
+                self.repository
+                    .mark_processed(message.id)
+                    .await
+                    .map_err(|_| OutboxServiceError::DatabaseError)?;
+                continue;
+            };
+
+            let attempts = message.attempts + 1;
+            let retry_in_secs =
+                (attempts < MAX_ATTEMPTS).then(|| 2f64.powi(attempts).min(MAX_BACKOFF_SECS));
+
+            tracing::warn!(
+                outbox_id = message.id,
+                attempts,
+                error,
+                "outbox event failed"
+            );
+
+            self.repository
+                .mark_failed(message.id, &error, retry_in_secs)
+                .await
+                .map_err(|_| OutboxServiceError::DatabaseError)?;
+        }
+
+        Ok(claimed)
+    }
+
+    /// Delete processed events past their retention; returns how many went
+    pub async fn prune(&self) -> Result<u64, OutboxServiceError> {
+        self.repository
+            .delete_processed(RETENTION_SECS)
+            .await
+            .map_err(|_| OutboxServiceError::DatabaseError)
+    }
+}
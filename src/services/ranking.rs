@@ -0,0 +1,156 @@
+use chrono::{DateTime, Utc};
+
+use crate::models::feed::FeedCandidate;
+
+/// Orders ranked feed candidates. Higher scores come first; ties go to the
+/// newer tweet.
+pub trait Ranker: Send + Sync {
+    fn score(&self, candidate: &FeedCandidate, now: DateTime<Utc>) -> f64;
+}
+
+/// A weighted sum of network, social proof and engagement signals, halved
+/// for every `half_life_hours` of age
+#[derive(Debug, Clone)]
+pub struct WeightedRanker {
+    pub network_weight: f64,
+    pub social_weight: f64,
+    pub engagement_weight: f64,
+    pub half_life_hours: f64,
+}
+
+impl Default for WeightedRanker {
+    fn default() -> Self {
+        Self {
+            network_weight: 3.0,
+            social_weight: 2.0,
+            engagement_weight: 1.0,
+            half_life_hours: 6.0,
+        }
+    }
+}
+
+impl Ranker for WeightedRanker {
+    fn score(&self, candidate: &FeedCandidate, now: DateTime<Utc>) -> f64 {
+        let network = if candidate.from_network { 1.0 } else { 0.0 };
+
+        // Logarithms, so a viral tweet does not drown everything else out;
+        // a reply costs more effort than a like and counts double
+        let social = (1.0 + candidate.liked_by_followed as f64).ln();
+        let engagement = (1.0 + candidate.likes as f64 + 2.0 * candidate.replies as f64).ln();
+
+        let age_hours = (now - candidate.created_at).num_seconds().max(0) as f64 / 3600.0;
+        let decay = 0.5f64.powf(age_hours / self.half_life_hours);
+
+        (self.network_weight * network
+            + self.social_weight * social
+            + self.engagement_weight * engagement)
+            * decay
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Duration;
+
+    fn candidate(now: DateTime<Utc>) -> FeedCandidate {
+        FeedCandidate {
+            tweet_id: 1,
+            created_at: now,
+            from_network: false,
+            liked_by_followed: 0,
+            likes: 0,
+            replies: 0,
+        }
+    }
+
+    #[test]
+    fn a_fresh_tweet_without_signals_scores_zero() {
+        let now = Utc::now();
+
+        assert_eq!(WeightedRanker::default().score(&candidate(now), now), 0.0);
+    }
+
+    #[test]
+    fn signals_add_up_by_weight() {
+        let now = Utc::now();
+        let ranker = WeightedRanker::default();
+        let tweet = FeedCandidate {
+            from_network: true,
+            liked_by_followed: 1,
+            likes: 1,
+            replies: 1,
+            ..candidate(now)
+        };
+
+        let expected = 3.0 + 2.0 * 2f64.ln() + 4f64.ln();
+        assert!((ranker.score(&tweet, now) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_reply_counts_as_two_likes() {
+        let now = Utc::now();
+        let ranker = WeightedRanker::default();
+
+        let replied = FeedCandidate {
+            replies: 1,
+            ..candidate(now)
+        };
+        let liked = FeedCandidate {
+            likes: 2,
+            ..candidate(now)
+        };
+
+        assert_eq!(ranker.score(&replied, now), ranker.score(&liked, now));
+    }
+
+    #[test]
+    fn score_halves_every_half_life() {
+        let now = Utc::now();
+        let ranker = WeightedRanker::default();
+        let fresh = FeedCandidate {
+            from_network: true,
+            ..candidate(now)
+        };
+        let old = FeedCandidate {
+            created_at: now - Duration::hours(12),
+            ..fresh.clone()
+        };
+
+        assert!((ranker.score(&old, now) - ranker.score(&fresh, now) / 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn tweets_from_the_future_do_not_gain_a_boost() {
+        let now = Utc::now();
+        let ranker = WeightedRanker::default();
+        let tweet = FeedCandidate {
+            from_network: true,
+            ..candidate(now)
+        };
+        let ahead = FeedCandidate {
+            created_at: now + Duration::hours(1),
+            ..tweet.clone()
+        };
+
+        assert_eq!(ranker.score(&ahead, now), ranker.score(&tweet, now));
+    }
+
+    #[test]
+    fn the_network_outweighs_a_hundred_likes_from_strangers() {
+        let now = Utc::now();
+        let ranker = WeightedRanker::default();
+        let followed = FeedCandidate {
+            from_network: true,
+            likes: 5,
+            ..candidate(now)
+        };
+        let viral = FeedCandidate {
+            likes: 100,
+            ..candidate(now)
+        };
+
+        assert!(ranker.score(&followed, now) > ranker.score(&viral, now));
+    }
+}
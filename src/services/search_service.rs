@@ -0,0 +1,404 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::models::outbox::OutboxEvent;
+use crate::models::search::{SearchCursor, SearchSort, TweetHit, TweetSearchQuery};
+use crate::models::tweet::TweetResponse;
+use crate::repositories::block_repository::BlockRepository;
+use crate::repositories::tweet_repository::TweetRepository;
+use crate::repositories::user_repository::UserRepository;
+use crate::search::SearchIndex;
+use crate::services::outbox_service::OutboxHandler;
+
+/// Longer queries are rejected rather than truncated
+const MAX_QUERY_CHARS: usize = 500;
+
+/// Tweets read and indexed at a time by a rebuild
+const REBUILD_BATCH_SIZE: i64 = 1000;
+
+/// Index pages read at most to fill one page of results when hits the viewer
+/// may not see have to be dropped
+const MAX_SEARCH_ROUNDS: usize = 5;
+
+#[derive(Debug)]
+pub enum SearchServiceError {
+    /// Nothing to search for: no words, `from:` or `#hashtag`
+    EmptyQuery,
+    QueryTooLong,
+    /// An operator with a malformed value, e.g. `from:` or `since:yesterday`
+    InvalidOperator(String),
+    IndexError,
+    DatabaseError,
+}
+
+#[derive(Clone)]
+pub struct SearchService {
+    index: Arc<dyn SearchIndex>,
+    tweet_repository: TweetRepository,
+    user_repository: UserRepository,
+    block_repository: BlockRepository,
+}
+
+impl SearchService {
+    pub fn new(
+        index: Arc<dyn SearchIndex>,
+        tweet_repository: TweetRepository,
+        user_repository: UserRepository,
+        block_repository: BlockRepository,
+    ) -> Self {
+        Self {
+            index,
+            tweet_repository,
+            user_repository,
+            block_repository,
+        }
+    }
+
+    /// A page of matching tweets and the cursor for the next one
+    pub async fn search_tweets(
+        &self,
+        q: &str,
+        sort: SearchSort,
+        cursor: Option<SearchCursor>,
+        limit: i64,
+        viewer_id: Option<i32>,
+    ) -> Result<(Vec<TweetResponse>, Option<String>), SearchServiceError> {
+        let mut query = parse_query(q)?;
+
+        if let Some(username) = &query.from {
+            let author = self
+                .user_repository
+                .find_by_usernames(std::slice::from_ref(username))
+                .await
+                .map_err(|_| SearchServiceError::DatabaseError)?
+                .pop();
+
+            // Nobody by that name, so nothing they wrote
+            let Some(author) = author else {
+                return Ok((Vec::new(), None));
+            };
+            query.author_id = Some(author.id);
+        }
+
+        if let Some(viewer_id) = viewer_id {
+            query.excluded_authors = self
+                .block_repository
+                .blocked_either_way(viewer_id)
+                .await
+                .map_err(|_| SearchServiceError::DatabaseError)?;
+        }
+
+        query.viewer_id = viewer_id;
+
+        // Loading drops hits the viewer may not read, such as tweets of
+        // protected accounts when the index cannot tell; read on to fill the
+        // page, and let the cursor point at the last hit actually used
+        let mut tweets = Vec::new();
+        let mut cursor = cursor;
+        let mut next_cursor = None;
+
+        'rounds: for _ in 0..MAX_SEARCH_ROUNDS {
+            let hits = self
+                .index
+                .search(&query, sort, cursor, limit)
+                .await
+                .map_err(|e| {
+                    tracing::warn!(error = %e, "tweet search failed");
+                    SearchServiceError::IndexError
+                })?;
+
+            let ids: Vec<i32> = hits.iter().map(|hit| hit.tweet_id).collect();
+            let mut loaded: HashMap<u64, TweetResponse> = self
+                .tweet_repository
+                .find_many(&ids, viewer_id)
+                .await
+                .map_err(|_| SearchServiceError::DatabaseError)?
+                .into_iter()
+                .map(|tweet| (tweet.id, tweet))
+                .collect();
+
+            for hit in &hits {
+                cursor = Some(match sort {
+                    SearchSort::Relevance => SearchCursor::Relevance(hit.rank, hit.tweet_id),
+                    SearchSort::Recent => SearchCursor::Recent(hit.created_at, hit.tweet_id),
+                });
+                next_cursor = Some(Self::cursor(sort, hit));
+
+                if let Some(tweet) = loaded.remove(&(hit.tweet_id as u64)) {
+                    tweets.push(tweet);
+                    if tweets.len() as i64 >= limit {
+                        break 'rounds;
+                    }
+                }
+            }
+
+            if (hits.len() as i64) < limit {
+                break;
+            }
+        }
+
+        Ok((tweets, next_cursor))
+    }
+
+    /// Reindex every tweet from the database; returns how many were indexed
+    pub async fn rebuild_index(&self) -> Result<usize, SearchServiceError> {
+        self.index
+            .clear()
+            .await
+            .map_err(|_| SearchServiceError::IndexError)?;
+
+        let mut indexed = 0;
+        let mut after_id = 0;
+        loop {
+            let tweets = self
+                .tweet_repository
+                .indexable_after(after_id, REBUILD_BATCH_SIZE)
+                .await
+                .map_err(|_| SearchServiceError::DatabaseError)?;
+
+            let Some(last) = tweets.last() else {
+                return Ok(indexed);
+            };
+            after_id = last.id;
+
+            self.index
+                .upsert(&tweets)
+                .await
+                .map_err(|_| SearchServiceError::IndexError)?;
+
+            indexed += tweets.len();
+        }
+    }
+
+    /// Relevance cursors are "<rank>|<tweet_id>"; recency cursors use the
+    /// cursor timeline's "<RFC3339 timestamp>|<tweet_id>"
+    fn cursor(sort: SearchSort, hit: &TweetHit) -> String {
+        match sort {
+            SearchSort::Relevance => format!("{}|{}", hit.rank, hit.tweet_id),
+            SearchSort::Recent => format!("{}|{}", hit.created_at.to_rfc3339(), hit.tweet_id),
+        }
+    }
+}
+
+/// Keep the index in step with committed tweets
+#[async_trait]
+impl OutboxHandler for SearchService {
+    fn name(&self) -> &'static str {
+        "search"
+    }
+
+    async fn handle(&self, event: &OutboxEvent) -> Result<(), String> {
+        match *event {
+            OutboxEvent::TweetCreated { tweet_id, .. } => {
+                let Some(tweet) = self
+                    .tweet_repository
+                    .find_indexable(tweet_id)
+                    .await
+                    .map_err(|e| e.to_string())?
+                else {
+                    // Deleted before the relay got to it
+                    return Ok(());
+                };
+
+                self.index.upsert(&[tweet]).await.map_err(|e| e.to_string())
+            }
+
+            OutboxEvent::TweetDeleted { tweet_id, .. } => {
+                self.index.remove(tweet_id).await.map_err(|e| e.to_string())
+            }
+
+            OutboxEvent::Followed { .. } => Ok(()),
+        }
+    }
+}
+
+/// Split `q` into operators and the words left for the text index.
+/// Quoted phrases stay whole so they can be matched as phrases.
+fn parse_query(q: &str) -> Result<TweetSearchQuery, SearchServiceError> {
+    if q.chars().count() > MAX_QUERY_CHARS {
+        return Err(SearchServiceError::QueryTooLong);
+    }
+
+    let mut query = TweetSearchQuery::default();
+    let mut words: Vec<String> = Vec::new();
+
+    for token in tokenize(q) {
+        let invalid = || SearchServiceError::InvalidOperator(token.clone());
+
+        if let Some(username) = strip_operator(&token, "from:") {
+            let username = username.trim_start_matches('@');
+            if !is_word(username) {
+                return Err(invalid());
+            }
+            query.from = Some(username.to_lowercase());
+        } else if let Some(date) = strip_operator(&token, "since:") {
+            query.since = Some(parse_date(date).ok_or_else(invalid)?);
+        } else if let Some(date) = strip_operator(&token, "until:") {
+            query.until = Some(parse_date(date).ok_or_else(invalid)?);
+        } else if let Some(tag) = token.strip_prefix('#')
+            && is_word(tag)
+        {
+            let tag = tag.to_lowercase();
+
+            // The tag word also goes to the text index, which narrows the
+            // candidates before the exact `#tag` match
+            words.push(tag.clone());
+            if !query.hashtags.contains(&tag) {
+                query.hashtags.push(tag);
+            }
+        } else {
+            words.push(token);
+        }
+    }
+
+    if !words.is_empty() {
+        query.text = Some(words.join(" "));
+    }
+
+    if query.text.is_none() && query.from.is_none() {
+        return Err(SearchServiceError::EmptyQuery);
+    }
+
+    Ok(query)
+}
+
+/// Whitespace-separated tokens; a `"quoted phrase"` is one token, quotes included
+fn tokenize(q: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for ch in q.chars() {
+        match ch {
+            '"' => {
+                current.push(ch);
+                quoted = !quoted;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+/// The value of `op:value`, whatever the case of `op`
+fn strip_operator<'a>(token: &'a str, op: &str) -> Option<&'a str> {
+    token
+        .get(..op.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(op))
+        .map(|_| &token[op.len()..])
+}
+
+/// Usernames and hashtags: letters, digits and underscores
+fn is_word(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// `2026-03-01` means midnight UTC; full RFC 3339 timestamps are accepted too
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Some(date.and_hms_opt(0, 0, 0)?.and_utc());
+    }
+
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|ts| ts.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenize_splits_on_whitespace_outside_quotes() {
+        assert_eq!(
+            tokenize("  rust \"async await\"\tfrom:bob "),
+            vec!["rust", "\"async await\"", "from:bob"]
+        );
+    }
+
+    #[test]
+    fn tokenize_keeps_an_unclosed_quote_to_the_end() {
+        assert_eq!(tokenize("a \"b c"), vec!["a", "\"b c"]);
+        assert!(tokenize(" \t ").is_empty());
+    }
+
+    #[test]
+    fn parse_query_pulls_out_operators() {
+        let query =
+            parse_query("FROM:@Bob rust since:2026-03-01 until:2026-03-02T12:00:00Z").unwrap();
+
+        assert_eq!(query.from.as_deref(), Some("bob"));
+        assert_eq!(query.text.as_deref(), Some("rust"));
+        assert_eq!(
+            query.since.unwrap().to_rfc3339(),
+            "2026-03-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            query.until.unwrap().to_rfc3339(),
+            "2026-03-02T12:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn parse_query_keeps_phrases_and_search_syntax_as_text() {
+        let query = parse_query("\"open source\" OR rust -java").unwrap();
+
+        assert_eq!(query.text.as_deref(), Some("\"open source\" OR rust -java"));
+        assert!(query.from.is_none());
+    }
+
+    #[test]
+    fn parse_query_lowercases_and_dedupes_hashtags() {
+        let query = parse_query("#Rust #rust #Go").unwrap();
+
+        assert_eq!(query.hashtags, vec!["rust", "go"]);
+        assert_eq!(query.text.as_deref(), Some("rust rust go"));
+    }
+
+    #[test]
+    fn parse_query_allows_from_alone() {
+        let query = parse_query("from:alice").unwrap();
+
+        assert_eq!(query.from.as_deref(), Some("alice"));
+        assert!(query.text.is_none());
+    }
+
+    #[test]
+    fn parse_query_rejects_malformed_operators() {
+        for q in ["from:", "from:a-b", "since:yesterday", "until:2026-13-01"] {
+            assert!(
+                matches!(parse_query(q), Err(SearchServiceError::InvalidOperator(token)) if token == q),
+                "{q}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_query_rejects_empty_and_overlong_queries() {
+        assert!(matches!(
+            parse_query("   "),
+            Err(SearchServiceError::EmptyQuery)
+        ));
+        assert!(matches!(
+            parse_query("since:2026-03-01"),
+            Err(SearchServiceError::EmptyQuery)
+        ));
+        assert!(matches!(
+            parse_query(&"a".repeat(MAX_QUERY_CHARS + 1)),
+            Err(SearchServiceError::QueryTooLong)
+        ));
+    }
+}
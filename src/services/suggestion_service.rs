@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+
+use crate::models::suggestion::{FollowSuggestion, SuggestedUser};
+use crate::repositories::suggestion_repository::SuggestionRepository;
+use crate::repositories::user_repository::UserRepository;
+
+/// Following this many accounts makes friends-of-friends too expensive to
+/// compute per request; such users get precomputed suggestions
+const LARGE_GRAPH_FOLLOWING: i64 = 500;
+
+/// Suggestions stored per precomputed user
+const MAX_PRECOMPUTED: i64 = 100;
+
+#[derive(Debug)]
+pub enum SuggestionServiceError {
+    UserNotFound,
+    DatabaseError,
+}
+
+#[derive(Clone)]
+pub struct SuggestionService {
+    repository: SuggestionRepository,
+    user_repository: UserRepository,
+}
+
+impl SuggestionService {
+    pub fn new(repository: SuggestionRepository, user_repository: UserRepository) -> Self {
+        Self {
+            repository,
+            user_repository,
+        }
+    }
+
+    /// Who `user_id` might want to follow, each with a reason
+    pub async fn suggestions(
+        &self,
+        user_id: i32,
+        limit: i64,
+    ) -> Result<Vec<FollowSuggestion>, SuggestionServiceError> {
+        self.user_repository
+            .find_by_id(user_id)
+            .await
+            .map_err(|_| SuggestionServiceError::DatabaseError)?
+            .ok_or(SuggestionServiceError::UserNotFound)?;
+
+        let following = self
+            .repository
+            .following_count(user_id)
+            .await
+            .map_err(|_| SuggestionServiceError::DatabaseError)?;
+
+        let mut suggested = Vec::new();
+        if following >= LARGE_GRAPH_FOLLOWING {
+            suggested = self
+                .repository
+                .precomputed(user_id, limit)
+                .await
+                .map_err(|_| SuggestionServiceError::DatabaseError)?;
+        }
+
+        // Small graphs, and large ones the worker has not reached yet
+        if suggested.is_empty() {
+            suggested = self
+                .repository
+                .friends_of_friends(user_id, limit)
+                .await
+                .map_err(|_| SuggestionServiceError::DatabaseError)?;
+        }
+
+        self.with_reasons(suggested).await
+    }
+
+    /// Recompute stored suggestions for every large graph and drop those of
+    /// users that no longer have one; returns how many users were computed
+    pub async fn precompute(&self) -> Result<usize, SuggestionServiceError> {
+        let user_ids = self
+            .repository
+            .large_graph_users(LARGE_GRAPH_FOLLOWING)
+            .await
+            .map_err(|_| SuggestionServiceError::DatabaseError)?;
+
+        for &user_id in &user_ids {
+            self.repository
+                .precompute(user_id, MAX_PRECOMPUTED)
+                .await
+                .map_err(|_| SuggestionServiceError::DatabaseError)?;
+        }
+
+        self.repository
+            .retain_precomputed(&user_ids)
+            .await
+            .map_err(|_| SuggestionServiceError::DatabaseError)?;
+
+        Ok(user_ids.len())
+    }
+
+    async fn with_reasons(
+        &self,
+        suggested: Vec<SuggestedUser>,
+    ) -> Result<Vec<FollowSuggestion>, SuggestionServiceError> {
+        let mut mutual_ids: Vec<i32> = suggested
+            .iter()
+            .flat_map(|user| user.mutual_ids.iter().copied())
+            .collect();
+        mutual_ids.sort_unstable();
+        mutual_ids.dedup();
+
+        let usernames: HashMap<i32, String> = self
+            .user_repository
+            .find_by_ids(&mutual_ids)
+            .await
+            .map_err(|_| SuggestionServiceError::DatabaseError)?
+            .into_iter()
+            .map(|user| (user.id, user.username))
+            .collect();
+
+        Ok(suggested
+            .into_iter()
+            .map(|user| {
+                let names: Vec<&str> = user
+                    .mutual_ids
+                    .iter()
+                    .filter_map(|id| usernames.get(id).map(String::as_str))
+                    .collect();
+
+                FollowSuggestion {
+                    reason: reason(&names, user.mutual_count),
+                    id: user.id,
+                    username: user.username,
+                    display_name: user.display_name,
+                    followers_count: user.followers_count,
+                    mutual_count: user.mutual_count,
+                }
+            })
+            .collect())
+    }
+}
+
+/// "Followed by alice", "Followed by alice and bob" or "Followed by alice
+/// and 3 others"
+fn reason(names: &[&str], mutual_count: i64) -> String {
+    match names {
+        [] => format!("Followed by {mutual_count} people you follow"),
+        [first, second, ..] if mutual_count == 2 => format!("Followed by {first} and {second}"),
+        [first, ..] => match mutual_count - 1 {
+            others if others <= 0 => format!("Followed by {first}"),
+            1 => format!("Followed by {first} and 1 other"),
+            others => format!("Followed by {first} and {others} others"),
+        },
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::{Arc, RwLock};
+
+use chrono::Utc;
+
+use crate::models::trend::TrendSnapshot;
+use crate::repositories::trend_repository::TrendRepository;
+
+/// Length of the window that is compared against the baseline
+const WINDOW_SECS: f64 = 3600.0;
+
+/// Windows before the current one that make up the baseline: 24 hours
+const BASELINE_WINDOWS: i32 = 24;
+
+/// Fewer distinct accounts than this is a conversation, not a trend
+const MIN_AUTHORS: i64 = 3;
+
+/// Accounts posting more than this in the current window are ignored
+const MAX_TWEETS_PER_WINDOW: i64 = 30;
+
+/// Trends kept in the cache
+const MAX_TRENDS: i64 = 50;
+
+#[derive(Debug)]
+pub enum TrendServiceError {
+    DatabaseError,
+}
+
+/// Trends are computed by a background task and served from memory
+#[derive(Clone)]
+pub struct TrendService {
+    repository: TrendRepository,
+    snapshot: Arc<RwLock<Option<TrendSnapshot>>>,
+}
+
+impl TrendService {
+    pub fn new(repository: TrendRepository) -> Self {
+        Self {
+            repository,
+            snapshot: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Recompute the trends and replace the cached ones; returns how many there are
+    pub async fn refresh(&self) -> Result<usize, TrendServiceError> {
+        let trends = self
+            .repository
+            .trending(
+                WINDOW_SECS,
+                BASELINE_WINDOWS,
+                MIN_AUTHORS,
+                MAX_TWEETS_PER_WINDOW,
+                MAX_TRENDS,
+            )
+            .await
+            .map_err(|_| TrendServiceError::DatabaseError)?;
+
+        let count = trends.len();
+        let snapshot = TrendSnapshot {
+            trends,
+            computed_at: Utc::now(),
+        };
+
+        *self.snapshot.write().expect("trend cache lock poisoned") = Some(snapshot);
+
+        Ok(count)
+    }
+
+    /// The top `limit` cached trends; computed on the spot only if the
+    /// background task has not filled the cache yet
+    pub async fn trends(&self, limit: usize) -> Result<TrendSnapshot, TrendServiceError> {
+        if self.cached().is_none() {
+            self.refresh().await?;
+        }
+
+        let mut snapshot = self.cached().ok_or(TrendServiceError::DatabaseError)?;
+        snapshot.trends.truncate(limit);
+
+        Ok(snapshot)
+    }
+
+    fn cached(&self) -> Option<TrendSnapshot> {
+        self.snapshot
+            .read()
+            .expect("trend cache lock poisoned")
+            .clone()
+    }
+}
@@ -1,11 +1,50 @@
-use crate::models::tweet::TweetResponse;
+use crate::events::{CountersEvent, DomainEvent, EventBus, TimelineEvent};
+use crate::models::draft::Draft;
+use crate::models::outbox::OutboxEvent;
+use crate::models::poll::{CreatePollRequest, NewPoll, Poll};
+use crate::models::tweet::{
+    ClaimedDraft, CreateTweetRequest, NewTweet, TweetResponse, ValidateTweetResponse,
+};
+use crate::repositories::block_repository::BlockRepository;
+use crate::repositories::media_repository::MediaRepository;
 use crate::repositories::tweet_repository::TweetRepository;
-use chrono::{DateTime, Utc};
+use crate::services::notification_service::NotificationService;
+use crate::services::outbox_service::OutboxHandler;
+use crate::services::tweet_text::{self, MAX_TWEET_LENGTH};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashSet;
+use std::sync::Arc;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Most attachments a single tweet can carry
+const MAX_MEDIA_PER_TWEET: usize = 4;
+
+/// Poll limits
+const MIN_POLL_OPTIONS: usize = 2;
+const MAX_POLL_OPTIONS: usize = 4;
+const MAX_POLL_OPTION_LENGTH: usize = 25;
+const MIN_POLL_MINUTES: i64 = 5;
+const MAX_POLL_MINUTES: i64 = 7 * 24 * 60;
 
 #[derive(Debug)]
 pub enum TweetServiceError {
     EmptyContent,
     ContentTooLong,
+    InvalidCharacters,
+    TooManyMedia,
+    InvalidMedia,
+    InvalidPollOptions,
+    InvalidPollDuration,
+    PollNotFound,
+    PollClosed,
+    AlreadyVoted,
+    InvalidPollOption,
+    ReplyTargetNotFound,
+    /// The replied-to author blocked the replier, or the other way round
+    Blocked,
+    AlreadyLiked,
+    NotLiked,
     NotFound,
     DatabaseError,
 }
@@ -13,55 +52,444 @@ pub enum TweetServiceError {
 #[derive(Clone)]
 pub struct TweetService {
     repository: TweetRepository,
+    media_repository: MediaRepository,
+    block_repository: BlockRepository,
+    notification_service: NotificationService,
+    events: Arc<dyn EventBus>,
 }
 
 impl TweetService {
-    pub fn new(repository: TweetRepository) -> Self {
-        Self { repository }
+    pub fn new(
+        repository: TweetRepository,
+        media_repository: MediaRepository,
+        block_repository: BlockRepository,
+        notification_service: NotificationService,
+        events: Arc<dyn EventBus>,
+    ) -> Self {
+        Self {
+            repository,
+            media_repository,
+            block_repository,
+            notification_service,
+            events,
+        }
+    }
+
+    pub async fn create_tweet(
+        &self,
+        author_id: i32,
+        request: CreateTweetRequest,
+    ) -> Result<TweetResponse, TweetServiceError> {
+        let tweet = self.prepare_tweet(author_id, &request, None).await?;
+        self.store(tweet).await
+    }
+
+    /// Publish a due scheduled draft through the same checks as a direct tweet
+    pub async fn publish_draft(&self, draft: &Draft) -> Result<TweetResponse, TweetServiceError> {
+        let tweet = self
+            .prepare_tweet(draft.author_id, &draft.to_request(), Some(draft.claim()))
+            .await?;
+        self.store(tweet).await
+    }
+
+    /// Insert a prepared tweet; the outbox relay takes care of telling anyone
+    async fn store(&self, tweet: NewTweet) -> Result<TweetResponse, TweetServiceError> {
+        self.repository
+            .create(tweet)
+            .await
+            .map_err(|_| TweetServiceError::DatabaseError)
+    }
+
+    /// Only the author can delete a tweet; to anyone else it does not exist
+    pub async fn delete_tweet(&self, user_id: i32, tweet_id: u64) -> Result<(), TweetServiceError> {
+        let deleted = self
+            .repository
+            .delete(tweet_id as i32, user_id)
+            .await
+            .map_err(|_| TweetServiceError::DatabaseError)?;
+
+        if !deleted {
+            return Err(TweetServiceError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    pub async fn like(&self, user_id: i32, tweet_id: u64) -> Result<(), TweetServiceError> {
+        let tweet_id = tweet_id as i32;
+
+        let author_id = self
+            .repository
+            .author_of(tweet_id)
+            .await
+            .map_err(|_| TweetServiceError::DatabaseError)?
+            .ok_or(TweetServiceError::NotFound)?;
+
+        self.check_visible(tweet_id, user_id, TweetServiceError::NotFound)
+            .await?;
+
+        let liked = self
+            .repository
+            .like(user_id, tweet_id)
+            .await
+            .map_err(|_| TweetServiceError::DatabaseError)?;
+
+        if !liked {
+            return Err(TweetServiceError::AlreadyLiked);
+        }
+
+        if let Some(author_id) = author_id
+            && let Err(e) = self
+                .notification_service
+                .notify_like(user_id, author_id, tweet_id)
+                .await
+        {
+            tracing::warn!(tweet_id, error = ?e, "like notification failed");
+        }
+
+        self.publish_counters(tweet_id).await;
+
+        Ok(())
+    }
+
+    pub async fn unlike(&self, user_id: i32, tweet_id: u64) -> Result<(), TweetServiceError> {
+        let tweet_id = tweet_id as i32;
+
+        let author_id = self
+            .repository
+            .author_of(tweet_id)
+            .await
+            .map_err(|_| TweetServiceError::DatabaseError)?
+            .ok_or(TweetServiceError::NotFound)?;
+
+        self.check_visible(tweet_id, user_id, TweetServiceError::NotFound)
+            .await?;
+
+        let unliked = self
+            .repository
+            .unlike(user_id, tweet_id)
+            .await
+            .map_err(|_| TweetServiceError::DatabaseError)?;
+
+        if !unliked {
+            return Err(TweetServiceError::NotLiked);
+        }
+
+        if let Some(author_id) = author_id
+            && let Err(e) = self
+                .notification_service
+                .withdraw_like(user_id, author_id, tweet_id)
+                .await
+        {
+            tracing::warn!(tweet_id, error = ?e, "withdrawing like notification failed");
+        }
+
+        self.publish_counters(tweet_id).await;
+
+        Ok(())
+    }
+
+    /// A protected tweet the user cannot see does not exist for them, so it
+    /// fails with the same error as a missing one
+    async fn check_visible(
+        &self,
+        tweet_id: i32,
+        user_id: i32,
+        missing: TweetServiceError,
+    ) -> Result<(), TweetServiceError> {
+        let protected = self
+            .repository
+            .is_protected_from(tweet_id, Some(user_id))
+            .await
+            .map_err(|_| TweetServiceError::DatabaseError)?;
+
+        if protected {
+            return Err(missing);
+        }
+
+        Ok(())
+    }
+
+    /// Tell live clients a tweet's like or reply count changed
+    async fn publish_counters(&self, tweet_id: i32) {
+        match self.repository.counters(tweet_id).await {
+            Ok((likes, replies)) => {
+                self.events
+                    .publish(DomainEvent::Counters(CountersEvent {
+                        tweet_id: tweet_id as u64,
+                        likes,
+                        replies,
+                    }))
+                    .await
+            }
+            Err(e) => tracing::warn!(tweet_id, error = ?e, "loading tweet counters failed"),
+        }
+    }
+
+    /// Run every check a new tweet must pass without storing anything
+    pub async fn prepare_tweet(
+        &self,
+        author_id: i32,
+        request: &CreateTweetRequest,
+        draft: Option<ClaimedDraft>,
+    ) -> Result<NewTweet, TweetServiceError> {
+        let content = Self::prepare_content(&request.content)?;
+        let urls = tweet_text::extract_urls(&content);
+        let poll = request.poll.as_ref().map(Self::prepare_poll).transpose()?;
+
+        let reply_to_id = request.in_reply_to.map(|id| id as i32);
+        if let Some(parent_id) = reply_to_id {
+            let parent_author = self
+                .repository
+                .author_of(parent_id)
+                .await
+                .map_err(|_| TweetServiceError::DatabaseError)?
+                .ok_or(TweetServiceError::ReplyTargetNotFound)?;
+
+            self.check_visible(parent_id, author_id, TweetServiceError::ReplyTargetNotFound)
+                .await?;
+
+            if let Some(parent_author) = parent_author
+                && self
+                    .block_repository
+                    .is_blocked_either_way(author_id, parent_author)
+                    .await
+                    .map_err(|_| TweetServiceError::DatabaseError)?
+            {
+                return Err(TweetServiceError::Blocked);
+            }
+        }
+
+        let mut media_ids = request.media_ids.clone();
+
+        let mut seen = HashSet::new();
+        media_ids.retain(|id| seen.insert(*id));
+        if media_ids.len() > MAX_MEDIA_PER_TWEET {
+            return Err(TweetServiceError::TooManyMedia);
+        }
+
+        if !media_ids.is_empty() {
+            let attachable = self
+                .media_repository
+                .count_attachable(&media_ids, author_id)
+                .await
+                .map_err(|_| TweetServiceError::DatabaseError)?;
+
+            if attachable != media_ids.len() as i64 {
+                return Err(TweetServiceError::InvalidMedia);
+            }
+        }
+
+        Ok(NewTweet {
+            author_id,
+            hashtags: tweet_text::extract_hashtags(&content),
+            content,
+            urls,
+            media_ids,
+            poll,
+            reply_to_id,
+            draft,
+        })
     }
 
-    pub async fn create_tweet(&self, content: String) -> Result<TweetResponse, TweetServiceError> {
+    /// Vote once in a tweet's poll and get the now-visible results
+    pub async fn vote(
+        &self,
+        tweet_id: u64,
+        user_id: i32,
+        option: i32,
+    ) -> Result<Poll, TweetServiceError> {
+        let tweet_id = tweet_id as i32;
+
+        self.check_visible(tweet_id, user_id, TweetServiceError::PollNotFound)
+            .await?;
+
+        let poll = self
+            .repository
+            .find_poll(tweet_id, Some(user_id))
+            .await
+            .map_err(|_| TweetServiceError::DatabaseError)?
+            .ok_or(TweetServiceError::PollNotFound)?;
+
+        if poll.closed {
+            return Err(TweetServiceError::PollClosed);
+        }
+
+        if poll.voted_option.is_some() {
+            return Err(TweetServiceError::AlreadyVoted);
+        }
+
+        if option < 0 || option as usize >= poll.options.len() {
+            return Err(TweetServiceError::InvalidPollOption);
+        }
+
+        // The insert re-checks both rules in case another request got there first
+        let recorded = self
+            .repository
+            .record_vote(tweet_id, user_id, option)
+            .await
+            .map_err(|_| TweetServiceError::DatabaseError)?;
+
+        if !recorded {
+            return Err(TweetServiceError::AlreadyVoted);
+        }
+
+        self.repository
+            .find_poll(tweet_id, Some(user_id))
+            .await
+            .map_err(|_| TweetServiceError::DatabaseError)?
+            .ok_or(TweetServiceError::PollNotFound)
+    }
+
+    /// Compute the weighted length of a draft without storing anything
+    pub fn validate_content(
+        &self,
+        content: &str,
+    ) -> Result<ValidateTweetResponse, TweetServiceError> {
+        let content = tweet_text::normalize(content);
+
+        if tweet_text::has_control_chars(&content) {
+            return Err(TweetServiceError::InvalidCharacters);
+        }
+
+        let length = tweet_text::weighted_length(&content);
+
+        Ok(ValidateTweetResponse {
+            length,
+            remaining: MAX_TWEET_LENGTH as i64 - length as i64,
+            valid: !content.trim().is_empty() && length <= MAX_TWEET_LENGTH,
+        })
+    }
+
+    /// Normalize and check content, returning the text to store
+    fn prepare_content(content: &str) -> Result<String, TweetServiceError> {
+        let content = tweet_text::normalize(content);
+
         if content.trim().is_empty() {
             return Err(TweetServiceError::EmptyContent);
         }
 
-        if content.len() > 280 {
+        if tweet_text::has_control_chars(&content) {
+            return Err(TweetServiceError::InvalidCharacters);
+        }
+
+        if tweet_text::weighted_length(&content) > MAX_TWEET_LENGTH {
             return Err(TweetServiceError::ContentTooLong);
         }
 
-        self.repository
-            .create(content)
-            .await
-            .map_err(|_| TweetServiceError::DatabaseError)
+        Ok(content)
+    }
+
+    /// Check options and duration, turning the duration into a closing time
+    fn prepare_poll(poll: &CreatePollRequest) -> Result<NewPoll, TweetServiceError> {
+        if !(MIN_POLL_OPTIONS..=MAX_POLL_OPTIONS).contains(&poll.options.len()) {
+            return Err(TweetServiceError::InvalidPollOptions);
+        }
+
+        let options: Vec<String> = poll
+            .options
+            .iter()
+            .map(|option| tweet_text::normalize(option.trim()))
+            .collect();
+
+        let mut seen = HashSet::new();
+        for option in &options {
+            if option.is_empty()
+                || tweet_text::has_control_chars(option)
+                || option.graphemes(true).count() > MAX_POLL_OPTION_LENGTH
+                || !seen.insert(option.as_str())
+            {
+                return Err(TweetServiceError::InvalidPollOptions);
+            }
+        }
+
+        if !(MIN_POLL_MINUTES..=MAX_POLL_MINUTES).contains(&poll.duration_minutes) {
+            return Err(TweetServiceError::InvalidPollDuration);
+        }
+
+        Ok(NewPoll {
+            options,
+            closes_at: Utc::now() + Duration::minutes(poll.duration_minutes),
+        })
     }
 
-    pub async fn get_tweet(&self, id: u64) -> Result<TweetResponse, TweetServiceError> {
+    pub async fn get_tweet(
+        &self,
+        id: u64,
+        viewer_id: Option<i32>,
+    ) -> Result<TweetResponse, TweetServiceError> {
         self.repository
-            .find_by_id(id as i32)
+            .find_visible(id as i32, viewer_id)
             .await
             .map_err(|_| TweetServiceError::DatabaseError)?
             .ok_or(TweetServiceError::NotFound)
     }
 
+    /// As `get_tweet`, but also hidden when the viewer and the author have
+    /// blocked each other either way
+    pub async fn get_unblocked_tweet(
+        &self,
+        id: u64,
+        viewer_id: i32,
+    ) -> Result<TweetResponse, TweetServiceError> {
+        let tweet = self.get_tweet(id, Some(viewer_id)).await?;
+
+        if let Some(author_id) = tweet.author_id
+            && self
+                .block_repository
+                .is_blocked_either_way(viewer_id, author_id)
+                .await
+                .map_err(|_| TweetServiceError::DatabaseError)?
+        {
+            return Err(TweetServiceError::NotFound);
+        }
+
+        Ok(tweet)
+    }
+
     pub async fn timeline(
         &self,
         limit: i64,
         offset: i64,
+        viewer_id: Option<i32>,
     ) -> Result<Vec<TweetResponse>, TweetServiceError> {
         self.repository
-            .timeline(limit, offset)
+            .timeline(limit, offset, viewer_id)
             .await
             .map_err(|_| TweetServiceError::DatabaseError)
     }
 
+    /// Tweets created after a cursor, oldest first, for catching up a stream
+    pub async fn timeline_since(
+        &self,
+        limit: i64,
+        after: (DateTime<Utc>, i32),
+    ) -> Result<Vec<TimelineEvent>, TweetServiceError> {
+        let rows = self
+            .repository
+            .timeline_after(limit, after)
+            .await
+            .map_err(|_| TweetServiceError::DatabaseError)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(tweet, created_at)| TimelineEvent {
+                tweet,
+                created_at,
+                protected: false,
+            })
+            .collect())
+    }
+
     pub async fn timeline_cursor(
         &self,
         limit: i64,
         before: Option<(DateTime<Utc>, i32)>,
+        viewer_id: Option<i32>,
     ) -> Result<(Vec<TweetResponse>, Option<String>), TweetServiceError> {
         let rows = self
             .repository
-            .timeline_before(limit, before)
+            .timeline_before(limit, before, viewer_id)
             .await
             .map_err(|_| TweetServiceError::DatabaseError)?;
 
@@ -74,3 +502,69 @@ impl TweetService {
         Ok((tweets, next_cursor))
     }
 }
+
+/// Fan a new tweet out to live subscribers and notify mentioned and
+/// replied-to users once it is committed
+#[async_trait]
+impl OutboxHandler for TweetService {
+    fn name(&self) -> &'static str {
+        "tweets"
+    }
+
+    async fn handle(&self, event: &OutboxEvent) -> Result<(), String> {
+        let OutboxEvent::TweetCreated {
+            tweet_id,
+            author_id,
+            reply_to_id,
+            created_at,
+        } = *event
+        else {
+            return Ok(());
+        };
+
+        // Deleted before the relay got to it: nothing left to announce
+        let Some(tweet) = self
+            .repository
+            .find_by_id(tweet_id, None)
+            .await
+            .map_err(|e| e.to_string())?
+        else {
+            return Ok(());
+        };
+
+        let reply_to_author = match reply_to_id {
+            Some(parent_id) => self
+                .repository
+                .author_of(parent_id)
+                .await
+                .map_err(|e| e.to_string())?
+                .flatten(),
+            None => None,
+        };
+
+        self.notification_service
+            .notify_tweet(author_id, tweet_id, &tweet.content, reply_to_author)
+            .await
+            .map_err(|e| format!("{e:?}"))?;
+
+        let protected = self
+            .repository
+            .is_protected_from(tweet_id, None)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        self.events
+            .publish(DomainEvent::TweetCreated(TimelineEvent {
+                tweet,
+                created_at,
+                protected,
+            }))
+            .await;
+
+        if let Some(parent_id) = reply_to_id {
+            self.publish_counters(parent_id).await;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,321 @@
+use std::ops::Range;
+
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+use url::{Url, form_urlencoded};
+
+use crate::models::tweet::UrlEntity;
+
+/// Maximum weighted length of a tweet
+pub const MAX_TWEET_LENGTH: usize = 280;
+
+/// Every URL counts as this many characters, whatever its real length
+pub const URL_WEIGHT: usize = 23;
+
+/// Longest display form before it gets truncated with an ellipsis
+const DISPLAY_URL_LENGTH: usize = 27;
+
+/// Query parameters that only exist for tracking and never change the target
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "msclkid", "igshid", "mc_cid", "mc_eid", "ref_src", "_hsenc",
+    "_hsmi",
+];
+
+/// Punctuation that usually ends a sentence rather than a URL
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', '!', '?', ':', ';', ')', ']', '\'', '"'];
+
+/// NFC-normalize so visually identical text is stored identically, with
+/// `\r\n` and lone `\r` line breaks turned into `\n`
+pub fn normalize(content: &str) -> String {
+    content
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .nfc()
+        .collect()
+}
+
+/// Control characters are not allowed, except line breaks and tabs
+pub fn has_control_chars(content: &str) -> bool {
+    content
+        .chars()
+        .any(|c| c.is_control() && c != '\n' && c != '\t')
+}
+
+/// Byte ranges of every http(s) URL in the text
+pub fn find_urls(content: &str) -> Vec<Range<usize>> {
+    let mut urls = Vec::new();
+    let mut word_start = 0;
+
+    for (idx, ch) in content
+        .char_indices()
+        .chain(std::iter::once((content.len(), ' ')))
+    {
+        if ch.is_whitespace() {
+            if let Some(url) = url_in_word(content, word_start, idx) {
+                urls.push(url);
+            }
+            word_start = idx + ch.len_utf8();
+        }
+    }
+
+    urls
+}
+
+fn url_in_word(content: &str, start: usize, end: usize) -> Option<Range<usize>> {
+    let word = &content[start..end];
+
+    let scheme_len = ["https://", "http://"].iter().find_map(|scheme| {
+        word.get(..scheme.len())
+            .filter(|prefix| prefix.eq_ignore_ascii_case(scheme))
+            .map(|_| scheme.len())
+    })?;
+
+    let trimmed = word.trim_end_matches(TRAILING_PUNCTUATION);
+    if trimmed.len() <= scheme_len {
+        return None;
+    }
+
+    Some(start..start + trimmed.len())
+}
+
+/// Length in grapheme clusters, with each URL weighted as `URL_WEIGHT`
+pub fn weighted_length(content: &str) -> usize {
+    let mut length = 0;
+    let mut last = 0;
+
+    for url in find_urls(content) {
+        length += content[last..url.start].graphemes(true).count() + URL_WEIGHT;
+        last = url.end;
+    }
+
+    length + content[last..].graphemes(true).count()
+}
+
+/// Usernames mentioned as `@name`, deduplicated, in order of appearance
+pub fn extract_mentions(content: &str) -> Vec<String> {
+    let mut mentions: Vec<String> = Vec::new();
+    let mut previous = None;
+
+    for (idx, ch) in content.char_indices() {
+        // `user@example.com` is an address, not a mention
+        let at_word_start = !previous.is_some_and(|c: char| c.is_alphanumeric() || c == '_');
+
+        if ch == '@' && at_word_start {
+            let name: String = content[idx + 1..]
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+                .collect();
+
+            if !name.is_empty() && !mentions.iter().any(|m| m.eq_ignore_ascii_case(&name)) {
+                mentions.push(name);
+            }
+        }
+
+        previous = Some(ch);
+    }
+
+    mentions
+}
+
+/// Lowercased `#tags`, deduplicated, in order of appearance
+pub fn extract_hashtags(content: &str) -> Vec<String> {
+    let mut hashtags: Vec<String> = Vec::new();
+    let mut previous = None;
+
+    for (idx, ch) in content.char_indices() {
+        let at_word_start = !previous.is_some_and(|c: char| c.is_alphanumeric() || c == '_');
+
+        if ch == '#' && at_word_start {
+            let tag: String = content[idx + 1..]
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_')
+                .flat_map(char::to_lowercase)
+                .collect();
+
+            if !tag.is_empty() && !hashtags.contains(&tag) {
+                hashtags.push(tag);
+            }
+        }
+
+        previous = Some(ch);
+    }
+
+    hashtags
+}
+
+/// Detect URLs and describe them as entities with code point offsets
+pub fn extract_urls(content: &str) -> Vec<UrlEntity> {
+    find_urls(content)
+        .into_iter()
+        .filter_map(|range| {
+            let raw = &content[range.clone()];
+            let canonical = canonicalize_url(raw)?;
+
+            let start = content[..range.start].chars().count();
+            let end = start + raw.chars().count();
+
+            Some(UrlEntity {
+                start: start as i32,
+                end: end as i32,
+                url: raw.to_string(),
+                display_url: display_url(&canonical),
+                expanded_url: canonical.into(),
+            })
+        })
+        .collect()
+}
+
+/// Lowercase the host, drop default ports and strip tracking parameters
+pub fn canonicalize_url(raw: &str) -> Option<Url> {
+    // The parser already lowercases the host and drops default ports
+    let mut url = Url::parse(raw).ok()?;
+    url.host_str()?;
+
+    // Drop whole `key=value` pieces so the rest of the query keeps its
+    // original encoding
+    if let Some(query) = url.query() {
+        let kept: Vec<&str> = query
+            .split('&')
+            .filter(|pair| {
+                let key = form_urlencoded::parse(pair.as_bytes())
+                    .next()
+                    .map(|(key, _)| key);
+                !key.is_some_and(|key| is_tracking_param(&key))
+            })
+            .collect();
+
+        let kept = kept.join("&");
+        url.set_query((!kept.is_empty()).then_some(kept.as_str()));
+    }
+
+    Some(url)
+}
+
+fn is_tracking_param(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key.starts_with("utm_") || TRACKING_PARAMS.contains(&key.as_str())
+}
+
+/// Short form for rendering: no scheme or `www.`, truncated with an ellipsis
+fn display_url(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+    let host = host.strip_prefix("www.").unwrap_or(host);
+
+    let mut display = format!("{}{}", host, url.path().trim_end_matches('/'));
+    if let Some(query) = url.query() {
+        display.push('?');
+        display.push_str(query);
+    }
+
+    if display.chars().count() > DISPLAY_URL_LENGTH {
+        display = display.chars().take(DISPLAY_URL_LENGTH - 1).collect();
+        display.push('…');
+    }
+
+    display
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weighted_length_counts_grapheme_clusters() {
+        assert_eq!(weighted_length("hello"), 5);
+        assert_eq!(weighted_length("e\u{301}"), 1);
+        assert_eq!(weighted_length("👩‍👩‍👧‍👦"), 1);
+        assert_eq!(weighted_length("🇳🇱🇧🇪"), 2);
+    }
+
+    #[test]
+    fn weighted_length_weighs_every_url_the_same() {
+        assert_eq!(weighted_length("https://a.co"), URL_WEIGHT);
+        assert_eq!(
+            weighted_length("see https://example.com/a/very/long/path/that/keeps/going"),
+            4 + URL_WEIGHT
+        );
+        assert_eq!(
+            weighted_length("http://a.co and http://b.co."),
+            URL_WEIGHT * 2 + 6
+        );
+    }
+
+    #[test]
+    fn weighted_length_ignores_bare_schemes() {
+        assert_eq!(weighted_length("https://"), 8);
+    }
+
+    #[test]
+    fn normalize_composes_and_unifies_line_breaks() {
+        assert_eq!(normalize("e\u{301}"), "\u{e9}");
+        assert_eq!(normalize("one\r\ntwo\rthree"), "one\ntwo\nthree");
+    }
+
+    #[test]
+    fn control_chars_allow_line_breaks_and_tabs() {
+        assert!(!has_control_chars("one\ntwo\tthree"));
+        assert!(!has_control_chars(&normalize("one\r\ntwo")));
+        assert!(has_control_chars("one\rtwo"));
+        assert!(has_control_chars("bell\u{7}"));
+        assert!(has_control_chars("zero\0"));
+    }
+
+    #[test]
+    fn extract_urls_reports_code_point_offsets() {
+        let urls = extract_urls("héllo https://Example.com/path, bye");
+
+        assert_eq!(urls.len(), 1);
+        assert_eq!(urls[0].start, 6);
+        assert_eq!(urls[0].end, 30);
+        assert_eq!(urls[0].url, "https://Example.com/path");
+        assert_eq!(urls[0].expanded_url, "https://example.com/path");
+        assert_eq!(urls[0].display_url, "example.com/path");
+    }
+
+    #[test]
+    fn extract_urls_skips_text_that_is_not_a_url() {
+        assert!(extract_urls("ftp://example.com example.com https:// mailto:a@b.c").is_empty());
+    }
+
+    #[test]
+    fn extract_urls_truncates_long_display_urls() {
+        let urls = extract_urls("https://www.example.com/a/very/long/path/indeed");
+
+        assert_eq!(urls[0].display_url, "example.com/a/very/long/pa…");
+        assert_eq!(urls[0].display_url.chars().count(), DISPLAY_URL_LENGTH);
+    }
+
+    #[test]
+    fn canonicalize_url_lowercases_host_and_drops_default_port() {
+        let url = canonicalize_url("HTTPS://WWW.Example.COM:443/Path").unwrap();
+        assert_eq!(url.as_str(), "https://www.example.com/Path");
+
+        let url = canonicalize_url("http://example.com:8080/").unwrap();
+        assert_eq!(url.as_str(), "http://example.com:8080/");
+    }
+
+    #[test]
+    fn canonicalize_url_strips_tracking_params() {
+        let url = canonicalize_url("https://example.com/?utm_source=x&id=7&fbclid=y&UTM_Medium=z")
+            .unwrap();
+        assert_eq!(url.as_str(), "https://example.com/?id=7");
+
+        let url = canonicalize_url("https://example.com/?utm_source=x&gclid=y").unwrap();
+        assert_eq!(url.as_str(), "https://example.com/");
+
+        let url = canonicalize_url("https://example.com/?FBCLID=x&GClid=y&id=7").unwrap();
+        assert_eq!(url.as_str(), "https://example.com/?id=7");
+    }
+
+    #[test]
+    fn canonicalize_url_keeps_the_remaining_query_as_written() {
+        let url = canonicalize_url("https://example.com/?a&utm_source=x&q=two%20words&b=").unwrap();
+        assert_eq!(url.as_str(), "https://example.com/?a&q=two%20words&b=");
+    }
+
+    #[test]
+    fn canonicalize_url_rejects_urls_without_a_host() {
+        assert!(canonicalize_url("not a url").is_none());
+        assert!(canonicalize_url("data:text/plain,hi").is_none());
+    }
+}
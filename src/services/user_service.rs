@@ -1,19 +1,54 @@
-use crate::{models::user::User, repositories::user_repository::UserRepository};
+use std::sync::Arc;
+
+use crate::{
+    events::{DomainEvent, EventBus},
+    models::user::{
+        ProfileChanges, UpdateProfileRequest, UpdateSettingsRequest, User, UserSettings,
+        UserSummary,
+    },
+    repositories::{media_repository::MediaRepository, user_repository::UserRepository},
+    services::tweet_text,
+};
+
+/// Profile field limits, in characters
+const MAX_DISPLAY_NAME_LENGTH: usize = 50;
+const MAX_BIO_LENGTH: usize = 160;
+const MAX_LOCATION_LENGTH: usize = 30;
+const MAX_WEBSITE_LENGTH: usize = 100;
 
 #[derive(Debug)]
 pub enum UserServiceError {
     EmptyUsername,
+    DisplayNameTooLong,
+    BioTooLong,
+    LocationTooLong,
+    WebsiteTooLong,
+    InvalidWebsite,
+    InvalidCharacters,
+    InvalidMedia,
+    EmptyQuery,
+    NotFound,
     DatabaseError,
 }
 
 #[derive(Clone)]
 pub struct UserService {
     repository: UserRepository,
+    media_repository: MediaRepository,
+    events: Arc<dyn EventBus>,
 }
 
 impl UserService {
-    pub fn new(repository: UserRepository) -> Self {
-        Self { repository }
+    pub fn new(
+        repository: UserRepository,
+        media_repository: MediaRepository,
+        events: Arc<dyn EventBus>,
+    ) -> Self {
+        Self {
+            repository,
+            media_repository,
+            events,
+        }
     }
 
     pub async fn create_user(&self, username: String) -> Result<User, UserServiceError> {
@@ -21,9 +56,339 @@ impl UserService {
             return Err(UserServiceError::EmptyUsername);
         }
 
-        self.repository
+        let user = self
+            .repository
             .create(username)
             .await
+            .map_err(|_| UserServiceError::DatabaseError)?;
+
+        self.events
+            .publish(DomainEvent::UserCreated {
+                user_id: user.id,
+                username: user.username.clone(),
+            })
+            .await;
+
+        Ok(user)
+    }
+
+    pub async fn get_user(&self, id: i32) -> Result<User, UserServiceError> {
+        self.repository
+            .find_by_id(id)
+            .await
+            .map_err(|_| UserServiceError::DatabaseError)?
+            .ok_or(UserServiceError::NotFound)
+    }
+
+    pub async fn get_user_by_username(&self, username: &str) -> Result<User, UserServiceError> {
+        let username = username.trim().trim_start_matches('@');
+        if username.is_empty() {
+            return Err(UserServiceError::EmptyUsername);
+        }
+
+        self.repository
+            .find_by_username(username)
+            .await
+            .map_err(|_| UserServiceError::DatabaseError)?
+            .ok_or(UserServiceError::NotFound)
+    }
+
+    /// Validate and apply profile changes; returns the updated profile
+    pub async fn update_profile(
+        &self,
+        id: i32,
+        request: UpdateProfileRequest,
+    ) -> Result<User, UserServiceError> {
+        let changes = ProfileChanges {
+            display_name: Self::check_text(
+                request.display_name,
+                MAX_DISPLAY_NAME_LENGTH,
+                false,
+                UserServiceError::DisplayNameTooLong,
+            )?,
+            bio: Self::check_text(
+                request.bio,
+                MAX_BIO_LENGTH,
+                true,
+                UserServiceError::BioTooLong,
+            )?,
+            location: Self::check_text(
+                request.location,
+                MAX_LOCATION_LENGTH,
+                false,
+                UserServiceError::LocationTooLong,
+            )?,
+            website: Self::check_website(request.website)?,
+            avatar_media_id: self.check_media(id, request.avatar_media_id).await?,
+            banner_media_id: self.check_media(id, request.banner_media_id).await?,
+        };
+
+        let updated = self
+            .repository
+            .update_profile(id, &changes)
+            .await
+            .map_err(|_| UserServiceError::DatabaseError)?;
+
+        if !updated {
+            return Err(UserServiceError::NotFound);
+        }
+
+        self.get_user(id).await
+    }
+
+    /// A changed text field, NFC-normalized and trimmed; clearing it yields
+    /// an empty string. Only multi-line fields may contain line breaks.
+    fn check_text(
+        value: Option<Option<String>>,
+        max_length: usize,
+        multi_line: bool,
+        too_long: UserServiceError,
+    ) -> Result<Option<String>, UserServiceError> {
+        let Some(value) = value else {
+            return Ok(None);
+        };
+
+        let value = tweet_text::normalize(value.as_deref().unwrap_or_default());
+        let value = value.trim();
+
+        if tweet_text::has_control_chars(value) || (!multi_line && value.contains('\n')) {
+            return Err(UserServiceError::InvalidCharacters);
+        }
+
+        if value.chars().count() > max_length {
+            return Err(too_long);
+        }
+
+        Ok(Some(value.to_string()))
+    }
+
+    /// An http(s) URL; `example.com` is taken to mean `https://example.com`
+    fn check_website(value: Option<Option<String>>) -> Result<Option<String>, UserServiceError> {
+        let Some(value) = value else {
+            return Ok(None);
+        };
+
+        let value = value.as_deref().unwrap_or_default().trim();
+        if value.is_empty() {
+            return Ok(Some(String::new()));
+        }
+
+        let parsed = match url::Url::parse(value) {
+            Ok(parsed) => parsed,
+            Err(url::ParseError::RelativeUrlWithoutBase) => {
+                url::Url::parse(&format!("https://{value}"))
+                    .map_err(|_| UserServiceError::InvalidWebsite)?
+            }
+            Err(_) => return Err(UserServiceError::InvalidWebsite),
+        };
+
+        if !matches!(parsed.scheme(), "http" | "https") || parsed.host().is_none() {
+            return Err(UserServiceError::InvalidWebsite);
+        }
+
+        let website = parsed.to_string();
+        if website.chars().count() > MAX_WEBSITE_LENGTH {
+            return Err(UserServiceError::WebsiteTooLong);
+        }
+
+        Ok(Some(website))
+    }
+
+    /// A changed avatar or banner, which must be one of the user's uploads;
+    /// clearing it yields 0
+    async fn check_media(
+        &self,
+        user_id: i32,
+        media_id: Option<Option<i32>>,
+    ) -> Result<Option<i32>, UserServiceError> {
+        match media_id {
+            None => Ok(None),
+            Some(None) => Ok(Some(0)),
+            Some(Some(media_id)) => {
+                let owned = self
+                    .media_repository
+                    .is_owned_by(media_id, user_id)
+                    .await
+                    .map_err(|_| UserServiceError::DatabaseError)?;
+
+                if !owned {
+                    return Err(UserServiceError::InvalidMedia);
+                }
+
+                Ok(Some(media_id))
+            }
+        }
+    }
+
+    pub async fn settings(&self, id: i32) -> Result<UserSettings, UserServiceError> {
+        self.repository
+            .settings(id)
+            .await
+            .map_err(|_| UserServiceError::DatabaseError)?
+            .ok_or(UserServiceError::NotFound)
+    }
+
+    /// Unprotecting an account approves every pending follow request
+    pub async fn update_settings(
+        &self,
+        id: i32,
+        request: UpdateSettingsRequest,
+    ) -> Result<UserSettings, UserServiceError> {
+        self.repository
+            .update_settings(id, request.protected, request.dms_from_following_only)
+            .await
+            .map_err(|_| UserServiceError::DatabaseError)?
+            .ok_or(UserServiceError::NotFound)
+    }
+
+    pub async fn search_users(
+        &self,
+        query: &str,
+        viewer_id: Option<i32>,
+        limit: i64,
+    ) -> Result<Vec<UserSummary>, UserServiceError> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(UserServiceError::EmptyQuery);
+        }
+
+        self.repository
+            .search(query, viewer_id, limit)
+            .await
+            .map_err(|_| UserServiceError::DatabaseError)
+    }
+
+    /// `prefix` may include the `@` being completed
+    pub async fn typeahead(
+        &self,
+        prefix: &str,
+        viewer_id: Option<i32>,
+        limit: i64,
+    ) -> Result<Vec<UserSummary>, UserServiceError> {
+        let prefix = prefix.trim().trim_start_matches('@');
+        if prefix.is_empty() {
+            return Err(UserServiceError::EmptyQuery);
+        }
+
+        self.repository
+            .typeahead(prefix, viewer_id, limit)
+            .await
             .map_err(|_| UserServiceError::DatabaseError)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(
+        value: Option<Option<&str>>,
+        multi_line: bool,
+    ) -> Result<Option<String>, UserServiceError> {
+        UserService::check_text(
+            value.map(|value| value.map(String::from)),
+            10,
+            multi_line,
+            UserServiceError::BioTooLong,
+        )
+    }
+
+    fn website(value: Option<Option<&str>>) -> Result<Option<String>, UserServiceError> {
+        UserService::check_website(value.map(|value| value.map(String::from)))
+    }
+
+    #[test]
+    fn check_text_leaves_missing_fields_alone() {
+        assert_eq!(text(None, false).unwrap(), None);
+    }
+
+    #[test]
+    fn check_text_clears_with_null_or_blank() {
+        assert_eq!(text(Some(None), false).unwrap().as_deref(), Some(""));
+        assert_eq!(text(Some(Some("   ")), false).unwrap().as_deref(), Some(""));
+    }
+
+    #[test]
+    fn check_text_trims_and_normalizes() {
+        assert_eq!(
+            text(Some(Some("  Cafe\u{301} ")), false)
+                .unwrap()
+                .as_deref(),
+            Some("Caf\u{e9}")
+        );
+    }
+
+    #[test]
+    fn check_text_counts_characters_not_bytes() {
+        assert!(text(Some(Some("éééééééééé")), false).is_ok());
+        assert!(matches!(
+            text(Some(Some("ééééééééééé")), false),
+            Err(UserServiceError::BioTooLong)
+        ));
+    }
+
+    #[test]
+    fn check_text_only_allows_line_breaks_in_multi_line_fields() {
+        assert_eq!(
+            text(Some(Some("one\r\ntwo")), true).unwrap().as_deref(),
+            Some("one\ntwo")
+        );
+        assert!(matches!(
+            text(Some(Some("one\ntwo")), false),
+            Err(UserServiceError::InvalidCharacters)
+        ));
+        assert!(matches!(
+            text(Some(Some("bell\u{7}")), true),
+            Err(UserServiceError::InvalidCharacters)
+        ));
+    }
+
+    #[test]
+    fn check_website_leaves_missing_and_clears_blank() {
+        assert_eq!(website(None).unwrap(), None);
+        assert_eq!(website(Some(None)).unwrap().as_deref(), Some(""));
+        assert_eq!(website(Some(Some(" "))).unwrap().as_deref(), Some(""));
+    }
+
+    #[test]
+    fn check_website_assumes_https_without_a_scheme() {
+        assert_eq!(
+            website(Some(Some("Example.com/me"))).unwrap().as_deref(),
+            Some("https://example.com/me")
+        );
+        assert_eq!(
+            website(Some(Some("http://example.com")))
+                .unwrap()
+                .as_deref(),
+            Some("http://example.com/")
+        );
+    }
+
+    #[test]
+    fn check_website_rejects_other_schemes_and_garbage() {
+        for value in [
+            "ftp://example.com",
+            "javascript:alert(1)",
+            "https://",
+            "exa mple.com",
+        ] {
+            assert!(
+                matches!(
+                    website(Some(Some(value))),
+                    Err(UserServiceError::InvalidWebsite)
+                ),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn check_website_limits_the_normalized_length() {
+        let long = format!("example.com/{}", "a".repeat(MAX_WEBSITE_LENGTH));
+
+        assert!(matches!(
+            website(Some(Some(&long))),
+            Err(UserServiceError::WebsiteTooLong)
+        ));
+    }
+}
@@ -0,0 +1,641 @@
+use std::collections::HashSet;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::models::outbox::OutboxEvent;
+use crate::models::webhook::{
+    CreateWebhookRequest, CreatedWebhook, DeliveryAttempt, UpdateWebhookRequest, Webhook,
+    WebhookDelivery, WebhookEvent,
+};
+use crate::repositories::block_repository::BlockRepository;
+use crate::repositories::tweet_repository::TweetRepository;
+use crate::repositories::user_repository::UserRepository;
+use crate::repositories::webhook_repository::WebhookRepository;
+use crate::services::job_queue::{JobHandler, JobQueue};
+use crate::services::outbox_service::OutboxHandler;
+use crate::services::tweet_text;
+
+const DELIVERY_JOB: &str = "webhooks.deliver";
+
+/// Deliveries one instance sends at the same time
+const DELIVERY_CONCURRENCY: usize = 8;
+
+/// Attempts before a delivery is marked failed; replaying starts over
+const MAX_DELIVERY_ATTEMPTS: i32 = 6;
+
+/// How long a receiver gets to answer
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How much of a receiver's response is kept in the delivery log
+const MAX_LOGGED_BODY_CHARS: usize = 1024;
+
+/// Mentions beyond this many in one tweet do not trigger webhooks
+const MAX_MENTION_DELIVERIES: usize = 10;
+
+/// Webhooks one user may register
+const MAX_WEBHOOKS_PER_USER: usize = 10;
+
+#[derive(Debug)]
+pub enum WebhookServiceError {
+    InvalidUrl,
+    /// The URL resolves to a loopback, private or otherwise internal address
+    PrivateAddress,
+    InvalidEvents,
+    TooManyWebhooks,
+    NotFound,
+    DatabaseError,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DeliveryJob {
+    delivery_id: i64,
+}
+
+/// The JSON body receivers get
+#[derive(Serialize)]
+struct Envelope<'a> {
+    id: i64,
+    event: &'a str,
+    created_at: String,
+    data: &'a serde_json::Value,
+}
+
+#[derive(Clone)]
+pub struct WebhookService {
+    repository: WebhookRepository,
+    tweet_repository: TweetRepository,
+    user_repository: UserRepository,
+    block_repository: BlockRepository,
+    job_queue: JobQueue,
+    client: reqwest::Client,
+    allowed_hosts: Arc<HashSet<String>>,
+}
+
+/// Resolves receiver hosts for the HTTP client, refusing internal addresses
+/// at connect time so a DNS answer that changed since the URL was checked
+/// cannot reach them either
+struct ReceiverResolver {
+    allowed_hosts: Arc<HashSet<String>>,
+}
+
+impl Resolve for ReceiverResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let allowed = self.allowed_hosts.contains(name.as_str());
+        let host = name.as_str().to_string();
+
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+
+            if !allowed && addrs.iter().any(|addr| !is_public(addr.ip())) {
+                return Err("receiver resolves to an internal address".into());
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Whether a receiver at this address is somewhere on the public internet
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_v4(ip),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    // 100.64.0.0/10 is carrier-grade NAT, internal to a provider's network
+    let shared = a == 100 && (b & 0b1100_0000) == 64;
+
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || shared)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || ip.is_unique_local()
+        || ip.is_unicast_link_local())
+}
+
+impl WebhookService {
+    pub fn new(
+        repository: WebhookRepository,
+        tweet_repository: TweetRepository,
+        user_repository: UserRepository,
+        block_repository: BlockRepository,
+        job_queue: JobQueue,
+        allowed_hosts: Vec<String>,
+    ) -> Self {
+        let allowed_hosts: Arc<HashSet<String>> = Arc::new(
+            allowed_hosts
+                .into_iter()
+                .map(|host| host.trim().to_ascii_lowercase())
+                .filter(|host| !host.is_empty())
+                .collect(),
+        );
+
+        // A redirect could lead anywhere, including past the address checks
+        let client = reqwest::Client::builder()
+            .timeout(DELIVERY_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .dns_resolver(Arc::new(ReceiverResolver {
+                allowed_hosts: allowed_hosts.clone(),
+            }))
+            .build()
+            .expect("HTTP client configuration is valid");
+
+        Self {
+            repository,
+            tweet_repository,
+            user_repository,
+            block_repository,
+            job_queue,
+            client,
+            allowed_hosts,
+        }
+    }
+
+    pub async fn create_webhook(
+        &self,
+        owner_id: i32,
+        request: CreateWebhookRequest,
+    ) -> Result<CreatedWebhook, WebhookServiceError> {
+        let url = self.check_url(&request.url).await?;
+        let events = Self::check_events(request.events)?;
+
+        let existing = self
+            .repository
+            .list(owner_id)
+            .await
+            .map_err(|_| WebhookServiceError::DatabaseError)?;
+
+        if existing.len() >= MAX_WEBHOOKS_PER_USER {
+            return Err(WebhookServiceError::TooManyWebhooks);
+        }
+
+        let mut secret = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut secret);
+        let secret = hex::encode(secret);
+
+        let webhook = self
+            .repository
+            .create(owner_id, &url, &secret, &events)
+            .await
+            .map_err(|_| WebhookServiceError::DatabaseError)?;
+
+        Ok(CreatedWebhook { webhook, secret })
+    }
+
+    pub async fn list_webhooks(&self, owner_id: i32) -> Result<Vec<Webhook>, WebhookServiceError> {
+        self.repository
+            .list(owner_id)
+            .await
+            .map_err(|_| WebhookServiceError::DatabaseError)
+    }
+
+    pub async fn get_webhook(
+        &self,
+        id: i32,
+        owner_id: i32,
+    ) -> Result<Webhook, WebhookServiceError> {
+        self.repository
+            .find(id, owner_id)
+            .await
+            .map_err(|_| WebhookServiceError::DatabaseError)?
+            .ok_or(WebhookServiceError::NotFound)
+    }
+
+    pub async fn update_webhook(
+        &self,
+        id: i32,
+        owner_id: i32,
+        request: UpdateWebhookRequest,
+    ) -> Result<Webhook, WebhookServiceError> {
+        let url = self.check_url(&request.url).await?;
+        let events = Self::check_events(request.events)?;
+
+        self.repository
+            .update(id, owner_id, &url, &events, request.active)
+            .await
+            .map_err(|_| WebhookServiceError::DatabaseError)?
+            .ok_or(WebhookServiceError::NotFound)
+    }
+
+    pub async fn delete_webhook(&self, id: i32, owner_id: i32) -> Result<(), WebhookServiceError> {
+        let deleted = self
+            .repository
+            .delete(id, owner_id)
+            .await
+            .map_err(|_| WebhookServiceError::DatabaseError)?;
+
+        if !deleted {
+            return Err(WebhookServiceError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    /// The delivery log of one of the owner's webhooks, newest first
+    pub async fn list_deliveries(
+        &self,
+        id: i32,
+        owner_id: i32,
+        before: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, WebhookServiceError> {
+        self.get_webhook(id, owner_id).await?;
+
+        self.repository
+            .list_deliveries(id, before, limit)
+            .await
+            .map_err(|_| WebhookServiceError::DatabaseError)
+    }
+
+    /// Send a logged delivery again, whatever became of it the first time
+    pub async fn replay_delivery(
+        &self,
+        id: i32,
+        owner_id: i32,
+        delivery_id: i64,
+    ) -> Result<(), WebhookServiceError> {
+        self.get_webhook(id, owner_id).await?;
+
+        let reset = self
+            .repository
+            .reset_delivery(delivery_id, id)
+            .await
+            .map_err(|_| WebhookServiceError::DatabaseError)?;
+
+        if !reset {
+            return Err(WebhookServiceError::NotFound);
+        }
+
+        self.job_queue
+            .enqueue(DELIVERY_JOB, &DeliveryJob { delivery_id })
+            .await
+            .map_err(|_| WebhookServiceError::DatabaseError)?;
+
+        Ok(())
+    }
+
+    /// Receivers must be plain HTTP(S) URLs on the public internet, unless
+    /// their host is allowlisted
+    async fn check_url(&self, url: &str) -> Result<String, WebhookServiceError> {
+        let parsed = url::Url::parse(url.trim()).map_err(|_| WebhookServiceError::InvalidUrl)?;
+
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(WebhookServiceError::InvalidUrl);
+        }
+
+        let host = parsed.host_str().ok_or(WebhookServiceError::InvalidUrl)?;
+        if self.allowed_hosts.contains(host) {
+            return Ok(parsed.to_string());
+        }
+
+        let addrs: Vec<IpAddr> = match parsed.host() {
+            Some(url::Host::Ipv4(ip)) => vec![ip.into()],
+            Some(url::Host::Ipv6(ip)) => vec![ip.into()],
+            Some(url::Host::Domain(domain)) => tokio::net::lookup_host((domain, 0))
+                .await
+                .map_err(|_| WebhookServiceError::InvalidUrl)?
+                .map(|addr| addr.ip())
+                .collect(),
+            None => return Err(WebhookServiceError::InvalidUrl),
+        };
+
+        if addrs.is_empty() {
+            return Err(WebhookServiceError::InvalidUrl);
+        }
+        if addrs.into_iter().any(|ip| !is_public(ip)) {
+            return Err(WebhookServiceError::PrivateAddress);
+        }
+
+        Ok(parsed.to_string())
+    }
+
+    fn check_events(mut events: Vec<String>) -> Result<Vec<String>, WebhookServiceError> {
+        events.sort();
+        events.dedup();
+
+        if events.is_empty()
+            || events
+                .iter()
+                .any(|event| WebhookEvent::parse(event).is_none())
+        {
+            return Err(WebhookServiceError::InvalidEvents);
+        }
+
+        Ok(events)
+    }
+
+    /// Log an event for every subscribed webhook of `owner_id` and queue it
+    async fn fan_out(
+        &self,
+        owner_id: i32,
+        event: WebhookEvent,
+        event_key: &str,
+        data: serde_json::Value,
+    ) -> Result<(), String> {
+        let webhook_ids = self
+            .repository
+            .subscribed(owner_id, event.as_str())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for webhook_id in webhook_ids {
+            let created = self
+                .repository
+                .create_delivery(webhook_id, event.as_str(), event_key, &data)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            // Already logged by an earlier relay of the same event
+            let Some(delivery_id) = created else {
+                continue;
+            };
+
+            self.job_queue
+                .enqueue(DELIVERY_JOB, &DeliveryJob { delivery_id })
+                .await
+                .map_err(|e| format!("{e:?}"))?;
+        }
+
+        Ok(())
+    }
+
+    /// `t=<unix seconds>,v1=<hex HMAC-SHA256 of "<t>.<body>">`; signing the
+    /// timestamp lets receivers reject replayed requests
+    fn signature(secret: &str, timestamp: i64, body: &[u8]) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any size");
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b".");
+        mac.update(body);
+
+        format!(
+            "t={},v1={}",
+            timestamp,
+            hex::encode(mac.finalize().into_bytes())
+        )
+    }
+}
+
+/// Turn committed activity into webhook deliveries
+#[async_trait]
+impl OutboxHandler for WebhookService {
+    fn name(&self) -> &'static str {
+        "webhooks"
+    }
+
+    async fn handle(&self, event: &OutboxEvent) -> Result<(), String> {
+        match *event {
+            OutboxEvent::TweetCreated {
+                tweet_id,
+                author_id,
+                ..
+            } => {
+                let Some(tweet) = self
+                    .tweet_repository
+                    .find_by_id(tweet_id, None)
+                    .await
+                    .map_err(|e| e.to_string())?
+                else {
+                    return Ok(());
+                };
+
+                let data = serde_json::json!({ "tweet": tweet });
+
+                self.fan_out(
+                    author_id,
+                    WebhookEvent::TweetCreated,
+                    &format!("tweet.created:{tweet_id}"),
+                    data.clone(),
+                )
+                .await?;
+
+                let mut mentions = tweet_text::extract_mentions(&tweet.content);
+                if mentions.is_empty() {
+                    return Ok(());
+                }
+                mentions.truncate(MAX_MENTION_DELIVERIES);
+
+                let mentioned = self
+                    .user_repository
+                    .find_by_usernames(&mentions)
+                    .await
+                    .map_err(|e| e.to_string())?;
+
+                // Mentions between users who blocked each other go nowhere
+                let blocked = self
+                    .block_repository
+                    .blocked_either_way(author_id)
+                    .await
+                    .map_err(|e| e.to_string())?;
+
+                for user in mentioned
+                    .into_iter()
+                    .filter(|user| user.id != author_id && !blocked.contains(&user.id))
+                {
+                    // A protected tweet only reaches the author's approved followers
+                    if self
+                        .tweet_repository
+                        .is_protected_from(tweet_id, Some(user.id))
+                        .await
+                        .map_err(|e| e.to_string())?
+                    {
+                        continue;
+                    }
+
+                    self.fan_out(
+                        user.id,
+                        WebhookEvent::Mention,
+                        &format!("mention:{tweet_id}"),
+                        data.clone(),
+                    )
+                    .await?;
+                }
+
+                Ok(())
+            }
+
+            OutboxEvent::TweetDeleted {
+                tweet_id,
+                author_id,
+            } => {
+                let data = serde_json::json!({ "tweet_id": tweet_id, "author_id": author_id });
+
+                self.fan_out(
+                    author_id,
+                    WebhookEvent::TweetDeleted,
+                    &format!("tweet.deleted:{tweet_id}"),
+                    data,
+                )
+                .await
+            }
+
+            OutboxEvent::Followed {
+                follower_id,
+                following_id,
+            } => {
+                let data = serde_json::json!({
+                    "follower_id": follower_id,
+                    "following_id": following_id,
+                });
+
+                // Relays of the same follow share a key; following again another
+                // day is a new event
+                self.fan_out(
+                    following_id,
+                    WebhookEvent::Follow,
+                    &format!("follow:{}:{}", follower_id, Utc::now().date_naive()),
+                    data,
+                )
+                .await
+            }
+        }
+    }
+}
+
+/// Send one logged delivery
+#[async_trait]
+impl JobHandler for WebhookService {
+    fn job_type(&self) -> &'static str {
+        DELIVERY_JOB
+    }
+
+    fn concurrency(&self) -> usize {
+        DELIVERY_CONCURRENCY
+    }
+
+    async fn run(&self, payload: serde_json::Value) -> Result<(), String> {
+        let job: DeliveryJob = serde_json::from_value(payload).map_err(|e| e.to_string())?;
+
+        let Some(target) = self
+            .repository
+            .delivery_target(job.delivery_id)
+            .await
+            .map_err(|e| e.to_string())?
+        else {
+            // The webhook was deleted
+            return Ok(());
+        };
+
+        if target.status != "pending" {
+            return Ok(());
+        }
+
+        if !target.active {
+            let attempt = DeliveryAttempt {
+                status: "failed",
+                response_status: None,
+                response_body: None,
+                error: Some("webhook is disabled".into()),
+            };
+            return self
+                .repository
+                .record_attempt(target.delivery_id, &attempt)
+                .await
+                .map_err(|e| e.to_string());
+        }
+
+        // The URL was checked when it was saved, but what its host resolves to
+        // may have changed since
+        if let Err(e) = self.check_url(&target.url).await {
+            let attempt = DeliveryAttempt {
+                status: "failed",
+                response_status: None,
+                response_body: None,
+                error: Some(format!("receiver URL is not allowed: {e:?}")),
+            };
+            return self
+                .repository
+                .record_attempt(target.delivery_id, &attempt)
+                .await
+                .map_err(|e| e.to_string());
+        }
+
+        let body = serde_json::to_vec(&Envelope {
+            id: target.delivery_id,
+            event: &target.event_type,
+            created_at: target.created_at.to_rfc3339(),
+            data: &target.payload,
+        })
+        .map_err(|e| e.to_string())?;
+
+        let signature = Self::signature(&target.secret, Utc::now().timestamp(), &body);
+
+        let response = self
+            .client
+            .post(&target.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header("X-Webhook-Id", target.delivery_id.to_string())
+            .header("X-Webhook-Event", &target.event_type)
+            .header("X-Webhook-Signature", signature)
+            .body(body)
+            .send()
+            .await;
+
+        let (succeeded, response_status, response_body, error) = match response {
+            Ok(response) => {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                let text: String = text.chars().take(MAX_LOGGED_BODY_CHARS).collect();
+
+                let error = (!status.is_success()).then(|| format!("receiver answered {status}"));
+                (
+                    status.is_success(),
+                    Some(status.as_u16() as i32),
+                    Some(text),
+                    error,
+                )
+            }
+            Err(e) => (false, None, None, Some(e.to_string())),
+        };
+
+        let gave_up = !succeeded && target.attempts + 1 >= MAX_DELIVERY_ATTEMPTS;
+        let attempt = DeliveryAttempt {
+            status: if succeeded {
+                "succeeded"
+            } else if gave_up {
+                "failed"
+            } else {
+                "pending"
+            },
+            response_status,
+            response_body,
+            error: error.clone(),
+        };
+
+        self.repository
+            .record_attempt(target.delivery_id, &attempt)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        // Failing the job makes the queue retry it with backoff
+        match error {
+            Some(error) if !gave_up => Err(error),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use rand::RngCore;
+
+use super::{BlobStore, BlobStoreError};
+
+/// Stores blobs as plain files under a root directory
+pub struct LocalBlobStore {
+    root: PathBuf,
+    base_url: String,
+}
+
+impl LocalBlobStore {
+    pub fn new(root: impl Into<PathBuf>, base_url: impl Into<String>) -> Self {
+        Self {
+            root: root.into(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Keys are generated by us, so anything else is a traversal attempt
+    fn path(&self, key: &str) -> Result<PathBuf, BlobStoreError> {
+        let valid = !key.is_empty()
+            && !key.starts_with('.')
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-');
+
+        if !valid {
+            return Err(BlobStoreError::InvalidKey);
+        }
+
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    async fn put(&self, key: &str, bytes: &[u8]) -> Result<(), BlobStoreError> {
+        let path = self.path(key)?;
+
+        tokio::fs::create_dir_all(&self.root)
+            .await
+            .map_err(BlobStoreError::Io)?;
+
+        // Write to a temp file first so readers never see a partial blob; the
+        // random suffix keeps concurrent writes of one key from sharing it
+        let mut suffix = [0u8; 8];
+        rand::thread_rng().fill_bytes(&mut suffix);
+        let tmp = self
+            .root
+            .join(format!(".{}.{}.partial", key, hex::encode(suffix)));
+
+        let written = match tokio::fs::write(&tmp, bytes).await {
+            Ok(()) => tokio::fs::rename(&tmp, &path).await,
+            Err(e) => Err(e),
+        };
+
+        if written.is_err() {
+            let _ = tokio::fs::remove_file(&tmp).await;
+        }
+
+        written.map_err(BlobStoreError::Io)
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, BlobStoreError> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(BlobStoreError::Io(e)),
+        }
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{}", self.base_url, key)
+    }
+}
@@ -0,0 +1,33 @@
+pub mod local;
+
+use std::fmt;
+
+use async_trait::async_trait;
+
+#[derive(Debug)]
+pub enum BlobStoreError {
+    InvalidKey,
+    Io(std::io::Error),
+}
+
+impl fmt::Display for BlobStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlobStoreError::InvalidKey => write!(f, "invalid blob key"),
+            BlobStoreError::Io(e) => write!(f, "blob store I/O error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for BlobStoreError {}
+
+/// Where uploaded files live; the database only keeps their keys
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    async fn put(&self, key: &str, bytes: &[u8]) -> Result<(), BlobStoreError>;
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, BlobStoreError>;
+
+    /// Public URL clients use to fetch the blob
+    fn url(&self, key: &str) -> String;
+}
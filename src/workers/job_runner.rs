@@ -0,0 +1,73 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::Semaphore;
+
+use crate::services::job_queue::{JobHandler, JobQueue};
+
+/// How often the queue is checked for a job type that was last found idle
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Runs queued jobs, each type in its own loop so a slow type cannot starve
+/// the others. Jobs live in the database, so restarts lose nothing and
+/// several instances can run side by side
+pub fn spawn(queue: JobQueue, handlers: Vec<Arc<dyn JobHandler>>) {
+    for handler in handlers {
+        tokio::spawn(run_type(queue.clone(), handler));
+    }
+}
+
+async fn run_type(queue: JobQueue, handler: Arc<dyn JobHandler>) {
+    let permits = Arc::new(Semaphore::new(handler.concurrency()));
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        // Only lease what there is capacity to start right away
+        loop {
+            let free = permits.available_permits();
+            if free == 0 {
+                break;
+            }
+
+            let jobs = match queue.claim(handler.job_type(), free as i64).await {
+                Ok(jobs) => jobs,
+                Err(e) => {
+                    tracing::warn!(job_type = handler.job_type(), error = ?e, "claiming jobs failed");
+                    break;
+                }
+            };
+
+            if jobs.is_empty() {
+                break;
+            }
+
+            for job in jobs {
+                let permit = permits
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .expect("the semaphore is never closed");
+                let queue = queue.clone();
+                let handler = handler.clone();
+
+                tokio::spawn(async move {
+                    // Claiming counts as an attempt, so a job whose worker keeps
+                    // dying still ends up dead-lettered
+                    let result = if job.attempts > job.max_attempts {
+                        Err("lease expired too many times".to_string())
+                    } else {
+                        handler.run(job.payload.clone()).await
+                    };
+
+                    if let Err(e) = queue.finish(&job, result).await {
+                        tracing::warn!(job_id = job.id, error = ?e, "recording job outcome failed");
+                    }
+
+                    drop(permit);
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod job_runner;
+pub mod outbox_relay;
+pub mod scheduler;
+pub mod suggestions;
+pub mod trends;
@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use crate::services::outbox_service::OutboxService;
+
+/// How often the outbox is checked when it was last found empty
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often old processed events are deleted
+const PRUNE_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Delivers outbox events to their handlers. Pending events survive restarts,
+/// and several instances can run this loop side by side
+pub async fn run(outbox_service: OutboxService) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        // Keep going while there is a backlog instead of waiting a tick per batch
+        loop {
+            match outbox_service.relay_batch().await {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!(error = ?e, "outbox relay tick failed");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Keeps the outbox from growing without bound; safe to run on every instance
+pub async fn prune(outbox_service: OutboxService) {
+    let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = outbox_service.prune().await {
+            tracing::warn!(error = ?e, "outbox prune failed");
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+use crate::services::draft_service::DraftService;
+
+/// How often the database is checked for due scheduled tweets
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Publishes scheduled tweets. State lives in the database, so restarts lose
+/// nothing and several instances can run this loop side by side
+pub async fn run(draft_service: DraftService) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        match draft_service.publish_due().await {
+            Ok(0) => {}
+            Ok(published) => tracing::info!(published, "published scheduled tweets"),
+            Err(e) => tracing::warn!(error = ?e, "scheduler tick failed"),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use std::time::Duration;
+
+use crate::services::suggestion_service::SuggestionService;
+
+/// How often precomputed suggestions are rebuilt
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// Keeps follow suggestions for large graphs fresh; the first tick is immediate
+pub async fn run(suggestion_service: SuggestionService) {
+    let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = suggestion_service.precompute().await {
+            tracing::warn!(error = ?e, "precomputing follow suggestions failed");
+        }
+    }
+}
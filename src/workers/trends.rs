@@ -0,0 +1,19 @@
+use std::time::Duration;
+
+use crate::services::trend_service::TrendService;
+
+/// How often trends are recomputed
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Keeps this instance's in-memory trends fresh; the first tick is immediate
+pub async fn run(trend_service: TrendService) {
+    let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = trend_service.refresh().await {
+            tracing::warn!(error = ?e, "trend refresh failed");
+        }
+    }
+}